@@ -1,15 +1,16 @@
 use ollama_rs::{
-    generation::chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponseStream},
+    generation::chat::{
+        request::ChatMessageRequest, BorrowedChatMessageResponseStream, ChatMessage,
+    },
     Ollama,
 };
-use std::sync::{Arc, Mutex};
 use tokio::io::{stdout, AsyncWriteExt};
 use tokio_stream::StreamExt;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let ollama = Ollama::default();
-    let history = Arc::new(Mutex::new(vec![]));
+    let mut history = vec![];
     let mut stdout = stdout();
 
     loop {
@@ -24,9 +25,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             break;
         }
 
-        let mut stream: ChatMessageResponseStream = ollama
+        let mut stream: BorrowedChatMessageResponseStream = ollama
             .send_chat_messages_with_history_stream(
-                history.clone(),
+                &mut history,
                 ChatMessageRequest::new(
                     "llama3.2:latest".to_string(),
                     vec![ChatMessage::user(input.to_string())],
@@ -42,7 +43,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    dbg!(&history.lock().unwrap());
+    dbg!(&history);
 
     Ok(())
 }
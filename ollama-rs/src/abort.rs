@@ -0,0 +1,118 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Poll, Waker},
+};
+
+use crate::Ollama;
+
+/// The requests in flight of a client and of its clones, see [`Ollama::abort_all`]
+#[derive(Debug, Default)]
+pub(crate) struct AbortRegistry {
+    next_key: AtomicU64,
+    entries: Mutex<HashMap<u64, Entry>>,
+}
+
+/// The ID of a request in flight and its signal
+type Entry = (Option<String>, Arc<AbortSignal>);
+
+#[derive(Debug, Default)]
+pub(crate) struct AbortSignal {
+    aborted: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl AbortSignal {
+    fn abort(&self) {
+        self.aborted.store(true, Ordering::SeqCst);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    /// Runs a future until it completes, or returns `None` once the request is aborted
+    pub(crate) async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut future = std::pin::pin!(future);
+        std::future::poll_fn(|cx| {
+            *self.waker.lock().unwrap() = Some(cx.waker().clone());
+            if self.aborted.load(Ordering::SeqCst) {
+                return Poll::Ready(None);
+            }
+            future.as_mut().poll(cx).map(Some)
+        })
+        .await
+    }
+}
+
+/// A request registered in flight until dropped. A streamed response keeps it until the end of its stream.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    registry: Arc<AbortRegistry>,
+    key: u64,
+    pub(crate) signal: Arc<AbortSignal>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.registry.entries.lock().unwrap().remove(&self.key);
+    }
+}
+
+impl AbortRegistry {
+    pub(crate) fn register(self: &Arc<Self>, request_id: Option<String>) -> Registration {
+        let key = self.next_key.fetch_add(1, Ordering::Relaxed);
+        let signal = Arc::new(AbortSignal::default());
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, (request_id, signal.clone()));
+        Registration {
+            registry: self.clone(),
+            key,
+            signal,
+        }
+    }
+}
+
+impl Ollama {
+    /// Aborts every request in flight of this client and of its clones, such as on shutdown.
+    /// Returns the number of requests aborted.
+    ///
+    /// The aborted requests fail with [`crate::error::OllamaError::Aborted`], and the streams of
+    /// the aborted responses yield it and end. The requests sent afterwards are not affected.
+    ///
+    /// A request is in flight until its response is received, or until the end of its stream for
+    /// the streaming endpoints. In browsers, the streams can't be aborted once their response is received.
+    pub fn abort_all(&self) -> usize {
+        let entries = self.abort_registry.entries.lock().unwrap();
+        for (_, signal) in entries.values() {
+            signal.abort();
+        }
+        entries.len()
+    }
+
+    /// Aborts the requests in flight with an ID, see [`Ollama::set_request_id_header`] and [`Ollama::abort_all`].
+    /// Returns whether a request was aborted.
+    pub fn abort(&self, request_id: &str) -> bool {
+        let entries = self.abort_registry.entries.lock().unwrap();
+        let mut aborted = false;
+        for (id, signal) in entries.values() {
+            if id.as_deref() == Some(request_id) {
+                signal.abort();
+                aborted = true;
+            }
+        }
+        aborted
+    }
+
+    /// Returns the IDs of the requests in flight of this client and of its clones, `None` for the
+    /// requests sent without an ID
+    pub fn in_flight_requests(&self) -> Vec<Option<String>> {
+        let entries = self.abort_registry.entries.lock().unwrap();
+        entries.values().map(|(id, _)| id.clone()).collect()
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use base64::Engine;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+
+use crate::{error::OllamaError, transport::BoxFuture, Instant, Ollama};
+
+/// Authenticates the requests of a client to Ollama, such as a hosted gateway requiring a token,
+/// see [`Ollama::with_auth_provider`].
+///
+/// [`AuthProvider::get_token`] is called before each request to set its `Authorization` header.
+/// When Ollama answers `401 Unauthorized`, [`AuthProvider::refresh`] is called and the request
+/// is sent once more with the next token, unless its body is streamed.
+///
+/// The requests to other hosts, such as the manifests fetched from a registry, are not authenticated
+/// with the provider, see [`crate::models::registry::RegistryCredentials`].
+pub trait AuthProvider: Send + Sync {
+    /// The value of the `Authorization` header, such as `Bearer <token>`
+    fn get_token(&self) -> BoxFuture<'_, crate::error::Result<HeaderValue>>;
+
+    /// Called when a request was rejected with the last token, so that the next call to
+    /// [`AuthProvider::get_token`] returns a new one. Does nothing by default.
+    fn refresh(&self) -> BoxFuture<'_, crate::error::Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl fmt::Debug for dyn AuthProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuthProvider")
+    }
+}
+
+/// A static bearer token, sent as `Authorization: Bearer <token>`
+#[derive(Clone)]
+pub struct BearerToken(HeaderValue);
+
+impl BearerToken {
+    /// # Errors
+    ///
+    /// Returns an error if the token isn't a valid header value.
+    pub fn new(token: impl AsRef<str>) -> crate::error::Result<Self> {
+        Ok(Self(sensitive_header(format!(
+            "Bearer {}",
+            token.as_ref()
+        ))?))
+    }
+}
+
+impl fmt::Debug for BearerToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BearerToken(..)")
+    }
+}
+
+impl AuthProvider for BearerToken {
+    fn get_token(&self) -> BoxFuture<'_, crate::error::Result<HeaderValue>> {
+        let value = self.0.clone();
+        Box::pin(async move { Ok(value) })
+    }
+}
+
+/// A username and a password, sent with the `Basic` scheme
+#[derive(Clone)]
+pub struct BasicAuth {
+    username: String,
+    value: HeaderValue,
+}
+
+impl BasicAuth {
+    /// # Errors
+    ///
+    /// Returns an error if the credentials can't be encoded in a header value.
+    pub fn new(
+        username: impl Into<String>,
+        password: impl AsRef<str>,
+    ) -> crate::error::Result<Self> {
+        let username = username.into();
+        let credentials = base64::engine::general_purpose::STANDARD
+            .encode(format!("{username}:{}", password.as_ref()));
+        Ok(Self {
+            value: sensitive_header(format!("Basic {credentials}"))?,
+            username,
+        })
+    }
+}
+
+impl fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuthProvider for BasicAuth {
+    fn get_token(&self) -> BoxFuture<'_, crate::error::Result<HeaderValue>> {
+        let value = self.value.clone();
+        Box::pin(async move { Ok(value) })
+    }
+}
+
+/// A bearer token returned by the callback of a [`RefreshingToken`]
+#[derive(Clone)]
+pub struct AccessToken {
+    pub token: String,
+    /// How long the token is valid, if it expires
+    pub expires_in: Option<Duration>,
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("expires_in", &self.expires_in)
+            .finish_non_exhaustive()
+    }
+}
+
+type FetchToken = dyn Fn() -> BoxFuture<'static, crate::error::Result<AccessToken>> + Send + Sync;
+
+/// A bearer token fetched with a callback, such as an OAuth client credentials grant, and kept
+/// until shortly before it expires or until Ollama rejects it.
+///
+/// Concurrent requests needing a new token may each call the callback.
+///
+/// ```
+/// use std::time::Duration;
+/// use ollama_rs::{auth::{AccessToken, RefreshingToken}, Ollama};
+///
+/// let provider = RefreshingToken::new(|| {
+///     Box::pin(async {
+///         // Request a token from the identity provider
+///         Ok(AccessToken {
+///             token: "eyJhbGciOi...".to_string(),
+///             expires_in: Some(Duration::from_secs(3600)),
+///         })
+///     })
+/// });
+/// let ollama = Ollama::default().with_auth_provider(provider);
+/// ```
+#[derive(Clone)]
+pub struct RefreshingToken {
+    fetch: Arc<FetchToken>,
+    margin: Duration,
+    cached: Arc<Mutex<Option<CachedToken>>>,
+}
+
+/// A token and when it expires, if it does
+type CachedToken = (HeaderValue, Option<Instant>);
+
+impl RefreshingToken {
+    pub fn new(
+        fetch: impl Fn() -> BoxFuture<'static, crate::error::Result<AccessToken>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self {
+            fetch: Arc::new(fetch),
+            margin: Duration::from_secs(30),
+            cached: Default::default(),
+        }
+    }
+
+    /// How long before its expiry a token is replaced (Default: 30s)
+    pub fn margin(mut self, margin: Duration) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    fn cached(&self) -> Option<HeaderValue> {
+        let cached = self.cached.lock().unwrap();
+        match &*cached {
+            Some((value, None)) => Some(value.clone()),
+            Some((value, Some(expires_at))) if Instant::now() + self.margin < *expires_at => {
+                Some(value.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for RefreshingToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefreshingToken")
+            .field("margin", &self.margin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuthProvider for RefreshingToken {
+    fn get_token(&self) -> BoxFuture<'_, crate::error::Result<HeaderValue>> {
+        Box::pin(async move {
+            if let Some(value) = self.cached() {
+                return Ok(value);
+            }
+
+            let token = (self.fetch)().await?;
+            let value = sensitive_header(format!("Bearer {}", token.token))?;
+            let expires_at = token
+                .expires_in
+                .map(|expires_in| Instant::now() + expires_in);
+            *self.cached.lock().unwrap() = Some((value.clone(), expires_at));
+            Ok(value)
+        })
+    }
+
+    fn refresh(&self) -> BoxFuture<'_, crate::error::Result<()>> {
+        self.cached.lock().unwrap().take();
+        Box::pin(async { Ok(()) })
+    }
+}
+
+fn sensitive_header(value: String) -> crate::error::Result<HeaderValue> {
+    let mut value = HeaderValue::from_str(&value)
+        .map_err(|_| OllamaError::Other("Invalid authorization header".to_string()))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+impl Ollama {
+    /// Authenticates the requests to Ollama with a provider, see [`AuthProvider`]
+    ///
+    /// ```
+    /// use ollama_rs::{auth::BearerToken, Ollama};
+    ///
+    /// let ollama = Ollama::try_new("https://ollama.example.com")
+    ///     .unwrap()
+    ///     .with_auth_provider(BearerToken::new("secret").unwrap());
+    /// ```
+    pub fn with_auth_provider(mut self, provider: impl AuthProvider + 'static) -> Self {
+        self.auth_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Removes the authentication provider, if any
+    pub fn clear_auth_provider(&mut self) {
+        self.auth_provider = None;
+    }
+
+    /// Returns the authentication provider, if any
+    pub fn auth_provider(&self) -> Option<&dyn AuthProvider> {
+        self.auth_provider.as_deref()
+    }
+
+    /// The provider authenticating a request, if it is sent to Ollama
+    pub(crate) fn auth_provider_for(
+        &self,
+        request: &reqwest::Request,
+    ) -> Option<Arc<dyn AuthProvider>> {
+        self.auth_provider
+            .clone()
+            .filter(|_| request.url().as_str().starts_with(self.url_str()))
+    }
+}
+
+/// Sets the `Authorization` header of a request with the token of a provider
+pub(crate) async fn authorize(
+    provider: &dyn AuthProvider,
+    request: &mut reqwest::Request,
+) -> crate::error::Result<()> {
+    let value = provider.get_token().await?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(())
+}
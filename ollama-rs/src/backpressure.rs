@@ -0,0 +1,134 @@
+//! The buffering of the streamed responses, see [`StreamBuffer`].
+
+use crate::Ollama;
+
+/// What a streamed response does when its consumer falls [`StreamBuffer::read_ahead`] chunks behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Stops reading the response until the consumer catches up, so that the server waits
+    #[default]
+    Block,
+    /// Ends the stream with [`crate::error::OllamaError::StreamBufferFull`] after the buffered chunks,
+    /// closing the connection, for consumers that can't fall behind such as a relay to a client
+    Error,
+}
+
+/// The bounds of the memory used by a streamed response, see [`Ollama::set_stream_buffer`].
+///
+/// By default, the streams read the response only when they are polled: a slow consumer holds
+/// back the server through the flow control of the connection, and at most one chunk is in memory.
+/// The chunks can be read ahead of the consumer into a bounded buffer, to smooth a consumer whose
+/// pace varies, and the lines of the responses that are split in chunks are bounded in length.
+///
+/// ```
+/// use ollama_rs::{backpressure::{OverflowPolicy, StreamBuffer}, Ollama};
+///
+/// let mut ollama = Ollama::default();
+/// ollama.set_stream_buffer(StreamBuffer::new().read_ahead(64).overflow(OverflowPolicy::Error));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamBuffer {
+    pub(crate) read_ahead: usize,
+    pub(crate) overflow: OverflowPolicy,
+    pub(crate) max_line_length: usize,
+}
+
+impl Default for StreamBuffer {
+    fn default() -> Self {
+        Self {
+            read_ahead: 0,
+            overflow: OverflowPolicy::Block,
+            max_line_length: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl StreamBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of chunks read ahead of the consumer (Default: 0, reading only when polled).
+    /// The chunks are read by a task of the Tokio runtime. Browsers always read when polled.
+    pub fn read_ahead(mut self, chunks: usize) -> Self {
+        self.read_ahead = chunks;
+        self
+    }
+
+    /// What to do when the read-ahead buffer is full (Default: [`OverflowPolicy::Block`])
+    pub fn overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = policy;
+        self
+    }
+
+    /// The longest line of the generation, chat and OpenAI chat completion streams, above which the stream
+    /// ends with [`crate::error::OllamaError::StreamLineTooLong`] (Default: 16 MiB)
+    pub fn max_line_length(mut self, bytes: usize) -> Self {
+        self.max_line_length = bytes;
+        self
+    }
+}
+
+impl Ollama {
+    /// Sets the bounds of the memory used by the streamed responses, see [`StreamBuffer`]
+    pub fn set_stream_buffer(&mut self, buffer: StreamBuffer) {
+        self.stream_buffer = buffer;
+    }
+
+    /// Returns the bounds of the memory used by the streamed responses
+    pub fn stream_buffer(&self) -> &StreamBuffer {
+        &self.stream_buffer
+    }
+}
+
+/// Reads a stream ahead of its consumer in a task, into a buffer of `buffer.read_ahead` items.
+/// The task stops when the returned stream is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn read_ahead<T: Send + 'static>(
+    stream: impl tokio_stream::Stream<Item = crate::error::Result<T>> + Send + 'static,
+    buffer: StreamBuffer,
+) -> crate::BoxStream<'static, crate::error::Result<T>> {
+    use async_stream::stream;
+    use tokio_stream::StreamExt;
+
+    use crate::error::OllamaError;
+
+    // With the `Error` policy, one more slot than the buffer is kept for the error of the overflow
+    let capacity = match buffer.overflow {
+        OverflowPolicy::Block => buffer.read_ahead,
+        OverflowPolicy::Error => buffer.read_ahead + 1,
+    };
+    let (tx, mut rx) = tokio::sync::mpsc::channel(capacity);
+    let task = tokio::spawn(async move {
+        let mut stream = std::pin::pin!(stream);
+        while let Some(item) = stream.next().await {
+            let sent = match buffer.overflow {
+                OverflowPolicy::Block => tx.send(item).await.is_ok(),
+                OverflowPolicy::Error if tx.capacity() > 1 => tx.try_send(item).is_ok(),
+                OverflowPolicy::Error => {
+                    let _ = tx.try_send(Err(OllamaError::StreamBufferFull(buffer.read_ahead)));
+                    false
+                }
+            };
+            if !sent {
+                break;
+            }
+        }
+    });
+
+    struct AbortOnDrop(tokio::task::AbortHandle);
+
+    impl Drop for AbortOnDrop {
+        fn drop(&mut self) {
+            self.0.abort();
+        }
+    }
+
+    let guard = AbortOnDrop(task.abort_handle());
+    Box::pin(stream! {
+        let _guard = guard;
+        while let Some(item) = rx.recv().await {
+            yield item;
+        }
+    })
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    error::OllamaError,
+    transport::{EndpointClass, ResponseGuard},
+    Instant, IntoUrl, Ollama,
+};
+
+/// How a [`HostPool`] picks the host of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceStrategy {
+    /// Every host in turn
+    RoundRobin,
+    /// The host with the fewest requests in flight, including the streamed responses being read
+    LeastInFlight,
+    /// The same host for all the requests of a model, so that a model stays loaded on a single host.
+    /// Requests without a model are sent round-robin.
+    StickyByModel,
+}
+
+/// Spreads the requests of a client over several Ollama hosts, see [`Ollama::new_with_hosts`].
+///
+/// A request failing to connect, or answered with a `502`, `503` or `504` status, is sent again to
+/// the next host. A host failing [`HostPool::max_failures`] times in a row is ejected for
+/// [`HostPool::ejection_duration`], and only used again if all the hosts are ejected.
+///
+/// Requests for a single host, such as listing the local models, see the models of the host they are sent to.
+#[derive(Debug)]
+pub struct HostPool {
+    hosts: Vec<Host>,
+    strategy: BalanceStrategy,
+    next: AtomicUsize,
+    max_failures: u32,
+    ejection_duration: Duration,
+}
+
+#[derive(Debug)]
+struct Host {
+    url: Url,
+    in_flight: AtomicUsize,
+    health: Mutex<HostHealth>,
+}
+
+#[derive(Debug, Default)]
+struct HostHealth {
+    failures: u32,
+    ejected_until: Option<Instant>,
+}
+
+impl HostPool {
+    /// Creates a pool of hosts, such as `http://10.0.0.1:11434`
+    pub fn new<U: IntoUrl>(
+        urls: impl IntoIterator<Item = U>,
+        strategy: BalanceStrategy,
+    ) -> Result<Self, OllamaError> {
+        let hosts = urls
+            .into_iter()
+            .map(|url| {
+                Ok(Host {
+                    url: crate::base_url(url.into_url()?),
+                    in_flight: AtomicUsize::new(0),
+                    health: Mutex::new(HostHealth::default()),
+                })
+            })
+            .collect::<Result<Vec<_>, OllamaError>>()?;
+
+        if hosts.is_empty() {
+            return Err(OllamaError::Other(
+                "A host pool needs at least one host".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            hosts,
+            strategy,
+            next: AtomicUsize::new(0),
+            max_failures: 3,
+            ejection_duration: Duration::from_secs(30),
+        })
+    }
+
+    /// The number of failures in a row after which a host is ejected (Default: 3)
+    pub fn max_failures(mut self, max_failures: u32) -> Self {
+        self.max_failures = max_failures.max(1);
+        self
+    }
+
+    /// How long an ejected host is left out of the pool (Default: 30s)
+    pub fn ejection_duration(mut self, duration: Duration) -> Self {
+        self.ejection_duration = duration;
+        self
+    }
+
+    pub fn strategy(&self) -> BalanceStrategy {
+        self.strategy
+    }
+
+    /// The URLs of all the hosts
+    pub fn hosts(&self) -> Vec<&Url> {
+        self.hosts.iter().map(|host| &host.url).collect()
+    }
+
+    /// The URLs of the hosts that are not ejected
+    pub fn healthy_hosts(&self) -> Vec<&Url> {
+        let now = Instant::now();
+        self.hosts
+            .iter()
+            .filter(|host| !host.is_ejected(now))
+            .map(|host| &host.url)
+            .collect()
+    }
+
+    /// The number of requests in flight to a host
+    pub fn in_flight(&self, url: &Url) -> Option<usize> {
+        let host = self.hosts.iter().find(|host| host.url == *url)?;
+        Some(host.in_flight.load(Ordering::Relaxed))
+    }
+
+    /// The indices of the hosts to try for a request, in order
+    fn rank(&self, request: &reqwest::Request) -> Vec<usize> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut order = (0..self.hosts.len())
+            .map(|i| (start + i) % self.hosts.len())
+            .collect::<Vec<_>>();
+
+        match self.strategy {
+            BalanceStrategy::RoundRobin => {}
+            BalanceStrategy::LeastInFlight => {
+                order.sort_by_key(|&i| self.hosts[i].in_flight.load(Ordering::Relaxed));
+            }
+            BalanceStrategy::StickyByModel => {
+                if let Some(model) = request_model(request) {
+                    // Rendezvous hashing, which only moves the models of a host when it leaves the pool
+                    order.sort_by_key(|&i| {
+                        let mut hasher = DefaultHasher::new();
+                        (&model, self.hosts[i].url.as_str()).hash(&mut hasher);
+                        std::cmp::Reverse(hasher.finish())
+                    });
+                }
+            }
+        }
+
+        // The ejected hosts are only tried last
+        let now = Instant::now();
+        order.sort_by_key(|&i| self.hosts[i].is_ejected(now));
+        order
+    }
+
+    fn record_success(&self, index: usize) {
+        *self.hosts[index].health.lock().unwrap() = HostHealth::default();
+    }
+
+    fn record_failure(&self, index: usize) {
+        let host = &self.hosts[index];
+        let mut health = host.health.lock().unwrap();
+        health.failures += 1;
+        if health.failures >= self.max_failures {
+            log::warn!(
+                "Ejecting {} for {:?} after {} failures",
+                host.url,
+                self.ejection_duration,
+                health.failures
+            );
+            health.ejected_until = Some(Instant::now() + self.ejection_duration);
+        }
+    }
+}
+
+impl Host {
+    fn is_ejected(&self, now: Instant) -> bool {
+        let health = self.health.lock().unwrap();
+        health.ejected_until.is_some_and(|until| until > now)
+    }
+}
+
+/// A request in flight to a host of the pool, counted until dropped
+#[derive(Debug)]
+struct InFlight {
+    pool: Arc<HostPool>,
+    index: usize,
+}
+
+impl InFlight {
+    fn new(pool: Arc<HostPool>, index: usize) -> Self {
+        pool.hosts[index].in_flight.fetch_add(1, Ordering::Relaxed);
+        Self { pool, index }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.pool.hosts[self.index]
+            .in_flight
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// The model of a request, read from its JSON body
+pub(crate) fn request_model(request: &reqwest::Request) -> Option<String> {
+    let body = request.body()?.as_bytes()?;
+    let body = serde_json::from_slice::<Value>(body).ok()?;
+    let model = body.get("model").or_else(|| body.get("name"))?;
+    model.as_str().map(str::to_string)
+}
+
+impl Ollama {
+    /// Creates a new `Ollama` instance spreading its requests over several hosts, see [`HostPool`].
+    ///
+    /// ```
+    /// use ollama_rs::{balance::BalanceStrategy, Ollama};
+    ///
+    /// let ollama = Ollama::new_with_hosts(
+    ///     ["http://10.0.0.1:11434", "http://10.0.0.2:11434"],
+    ///     BalanceStrategy::StickyByModel,
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn new_with_hosts<U: IntoUrl>(
+        urls: impl IntoIterator<Item = U>,
+        strategy: BalanceStrategy,
+    ) -> Result<Self, OllamaError> {
+        let pool = HostPool::new(urls, strategy)?;
+        let mut ollama = Self::from_url(pool.hosts[0].url.clone());
+        ollama.set_host_pool(Some(pool));
+        Ok(ollama)
+    }
+
+    /// Spreads the requests over a pool of hosts, or only uses [`Ollama::url`] with `None`.
+    /// The URL of the client should be one of the hosts, the requests to other URLs such as
+    /// the registry are not balanced.
+    pub fn set_host_pool(&mut self, pool: Option<HostPool>) {
+        self.host_pool = pool.map(Arc::new);
+    }
+
+    /// Returns the pool of hosts, if any
+    pub fn host_pool(&self) -> Option<&HostPool> {
+        self.host_pool.as_deref()
+    }
+
+    /// Sends a request to the hosts of the pool, in the order of the strategy, until one is available
+    pub(crate) async fn execute_balanced(
+        &self,
+        pool: Arc<HostPool>,
+        request: reqwest::Request,
+        class: EndpointClass,
+        streaming: bool,
+    ) -> crate::error::Result<(reqwest::Response, Option<ResponseGuard>)> {
+        let Some(path) = request.url().as_str().strip_prefix(self.url_str()) else {
+            return Ok((self.execute(request, class, streaming).await?, None));
+        };
+        let path = path.to_string();
+
+        let order = pool.rank(&request);
+        let mut request = Some(request);
+
+        for (position, &index) in order.iter().enumerate() {
+            let is_last = position + 1 == order.len();
+
+            // The last host gets the original request, as does the first host for the requests with
+            // a streamed body, which can't be sent twice
+            let mut host_request = match &request {
+                Some(r) if !is_last => match r.try_clone() {
+                    Some(clone) => clone,
+                    None => request.take().unwrap(),
+                },
+                _ => request.take().unwrap(),
+            };
+            *host_request.url_mut() = Url::parse(&format!("{}{path}", pool.hosts[index].url))?;
+            let can_failover = request.is_some();
+
+            let in_flight = InFlight::new(pool.clone(), index);
+            let res = self.execute(host_request, class, streaming).await;
+
+            let failed = match &res {
+                Ok(res) => matches!(res.status().as_u16(), 502..=504),
+                Err(OllamaError::ReqwestError(e)) => crate::transport::is_connect_error(e),
+                Err(_) => false,
+            };
+            if !failed {
+                let res = res?;
+                pool.record_success(index);
+                return Ok((res, Some(Arc::new(in_flight))));
+            }
+
+            pool.record_failure(index);
+            if !can_failover {
+                return Ok((res?, None));
+            }
+            log::warn!(
+                "{class:?} request to {} failed, trying the next host",
+                pool.hosts[index].url
+            );
+        }
+
+        unreachable!("the last host always returns")
+    }
+}
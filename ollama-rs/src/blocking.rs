@@ -0,0 +1,317 @@
+//! A blocking client, for command line tools and applications without an async runtime.
+//!
+//! Like `reqwest::blocking`, the client runs the requests on a runtime of its own, so it must
+//! not be used from an async context, where it panics.
+//!
+//! ```no_run
+//! use ollama_rs::{blocking::Ollama, generation::completion::request::GenerationRequest};
+//!
+//! let ollama = Ollama::default();
+//! let res = ollama
+//!     .generate(GenerationRequest::new("llama2:latest".to_string(), "Why is the sky blue?"))
+//!     .unwrap();
+//! println!("{}", res.response);
+//!
+//! for chunk in ollama
+//!     .generate_stream(GenerationRequest::new("llama2:latest".to_string(), "Why is the sky blue?"))
+//!     .unwrap()
+//! {
+//!     for res in chunk.unwrap() {
+//!         print!("{}", res.response);
+//!     }
+//! }
+//! ```
+
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use tokio::runtime::Runtime;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::{
+    error::OllamaError,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessageResponse},
+        completion::{
+            request::GenerationRequest, GenerationResponse, GenerationResponseStreamChunk,
+        },
+        embeddings::{request::GenerateEmbeddingsRequest, GenerateEmbeddingsResponse},
+    },
+    history::ChatHistory,
+    models::{
+        create::{CreateModelRequest, CreateModelStatus},
+        list_running::RunningModel,
+        pull::PullModelStatus,
+        push::PushModelStatus,
+        LocalModel, ModelInfo,
+    },
+    openai::{
+        chat::{ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse},
+        embeddings::{EmbeddingRequest, EmbeddingResponse},
+    },
+    IntoUrl,
+};
+
+/// A blocking Ollama client, with the endpoints of [`crate::Ollama`].
+///
+/// The configuration of the client, such as its headers, timeouts or retries, is done on
+/// an async client converted with [`Ollama::from_async`]. Clones share the same runtime.
+#[derive(Debug, Clone)]
+pub struct Ollama {
+    inner: crate::Ollama,
+    runtime: Arc<Runtime>,
+}
+
+impl Default for Ollama {
+    /// Returns a default blocking client with the host set to `http://127.0.0.1:11434`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime of the client can't be created.
+    fn default() -> Self {
+        Self::from_async(crate::Ollama::default()).expect("Failed to create the runtime")
+    }
+}
+
+impl Ollama {
+    /// Creates a new blocking client with the specified host and port, see [`crate::Ollama::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the host is not a valid URL, or if the runtime of the client can't be created.
+    pub fn new(host: impl IntoUrl, port: u16) -> Self {
+        Self::from_async(crate::Ollama::new(host, port)).expect("Failed to create the runtime")
+    }
+
+    /// Attempts to create a new blocking client from a URL.
+    pub fn try_new(url: impl IntoUrl) -> Result<Self, OllamaError> {
+        Self::from_async(crate::Ollama::try_new(url)?)
+    }
+
+    /// Creates a blocking client sending its requests with an async client and its configuration.
+    pub fn from_async(ollama: crate::Ollama) -> Result<Self, OllamaError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            inner: ollama,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Returns the async client, to configure it or to call the endpoints without a blocking variant
+    /// with [`Ollama::block_on`].
+    pub fn async_client(&self) -> &crate::Ollama {
+        &self.inner
+    }
+
+    /// Returns the async client, to configure it.
+    pub fn async_client_mut(&mut self) -> &mut crate::Ollama {
+        &mut self.inner
+    }
+
+    /// Runs a future on the runtime of the client, such as a call to an endpoint of the async client.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    fn stream<T>(&self, stream: Pin<Box<dyn Stream<Item = T> + Send>>) -> BlockingStream<T> {
+        BlockingStream {
+            stream,
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Completion generation with a single response, see [`crate::Ollama::generate`].
+    pub fn generate(
+        &self,
+        request: GenerationRequest<'_>,
+    ) -> crate::error::Result<GenerationResponse> {
+        self.block_on(self.inner.generate(request))
+    }
+
+    /// Completion generation with streaming, see [`crate::Ollama::generate_stream`].
+    pub fn generate_stream(
+        &self,
+        request: GenerationRequest<'_>,
+    ) -> crate::error::Result<BlockingStream<crate::error::Result<GenerationResponseStreamChunk>>>
+    {
+        let stream = self.block_on(self.inner.generate_stream(request))?;
+        Ok(self.stream(stream))
+    }
+
+    /// Chat message generation, see [`crate::Ollama::send_chat_messages`].
+    pub fn send_chat_messages(
+        &self,
+        request: ChatMessageRequest,
+    ) -> crate::error::Result<ChatMessageResponse> {
+        self.block_on(self.inner.send_chat_messages(request))
+    }
+
+    /// Chat message generation with streaming, see [`crate::Ollama::send_chat_messages_stream`].
+    pub fn send_chat_messages_stream(
+        &self,
+        request: ChatMessageRequest,
+    ) -> crate::error::Result<BlockingStream<crate::error::Result<ChatMessageResponse>>> {
+        let stream = self.block_on(self.inner.send_chat_messages_stream(request))?;
+        Ok(self.stream(stream))
+    }
+
+    /// Chat message generation with a history, see [`crate::Ollama::send_chat_messages_with_history`].
+    pub fn send_chat_messages_with_history<C: ChatHistory>(
+        &self,
+        history: &mut C,
+        request: ChatMessageRequest,
+    ) -> crate::error::Result<ChatMessageResponse> {
+        self.block_on(self.inner.send_chat_messages_with_history(history, request))
+    }
+
+    /// Generate embeddings from a model, see [`crate::Ollama::generate_embeddings`].
+    pub fn generate_embeddings(
+        &self,
+        request: GenerateEmbeddingsRequest,
+    ) -> crate::error::Result<GenerateEmbeddingsResponse> {
+        self.block_on(self.inner.generate_embeddings(request))
+    }
+
+    /// Chat completion through the OpenAI-compatible endpoint, see [`crate::Ollama::openai_chat_completion`].
+    pub fn openai_chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> crate::error::Result<ChatCompletionResponse> {
+        self.block_on(self.inner.openai_chat_completion(request))
+    }
+
+    /// Chat completion with streaming through the OpenAI-compatible endpoint,
+    /// see [`crate::Ollama::openai_chat_completion_stream`].
+    pub fn openai_chat_completion_stream(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> crate::error::Result<BlockingStream<crate::error::Result<ChatCompletionChunk>>> {
+        let stream = self.block_on(self.inner.openai_chat_completion_stream(request))?;
+        Ok(self.stream(stream))
+    }
+
+    /// Generate embeddings through the OpenAI-compatible endpoint, see [`crate::Ollama::openai_embeddings`].
+    pub fn openai_embeddings(
+        &self,
+        request: EmbeddingRequest,
+    ) -> crate::error::Result<EmbeddingResponse> {
+        self.block_on(self.inner.openai_embeddings(request))
+    }
+
+    /// List the local models, see [`crate::Ollama::list_local_models`].
+    pub fn list_local_models(&self) -> crate::error::Result<Vec<LocalModel>> {
+        self.block_on(self.inner.list_local_models())
+    }
+
+    /// List the models loaded in memory, see [`crate::Ollama::list_running_models`].
+    pub fn list_running_models(&self) -> crate::error::Result<Vec<RunningModel>> {
+        self.block_on(self.inner.list_running_models())
+    }
+
+    /// Show details about a model, see [`crate::Ollama::show_model_info`].
+    pub fn show_model_info(&self, model_name: String) -> crate::error::Result<ModelInfo> {
+        self.block_on(self.inner.show_model_info(model_name))
+    }
+
+    /// Create a model, see [`crate::Ollama::create_model`].
+    pub fn create_model(
+        &self,
+        request: CreateModelRequest,
+    ) -> crate::error::Result<CreateModelStatus> {
+        self.block_on(self.inner.create_model(request))
+    }
+
+    /// Create a model with streaming, see [`crate::Ollama::create_model_stream`].
+    pub fn create_model_stream(
+        &self,
+        request: CreateModelRequest,
+    ) -> crate::error::Result<BlockingStream<crate::error::Result<CreateModelStatus>>> {
+        let stream = self.block_on(self.inner.create_model_stream(request))?;
+        Ok(self.stream(stream))
+    }
+
+    /// Copy a model, see [`crate::Ollama::copy_model`].
+    pub fn copy_model(&self, source: String, destination: String) -> crate::error::Result<()> {
+        self.block_on(self.inner.copy_model(source, destination))
+    }
+
+    /// Delete a model and its data, see [`crate::Ollama::delete_model`].
+    pub fn delete_model(&self, model_name: String) -> crate::error::Result<()> {
+        self.block_on(self.inner.delete_model(model_name))
+    }
+
+    /// Pull a model, see [`crate::Ollama::pull_model`].
+    pub fn pull_model(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> crate::error::Result<PullModelStatus> {
+        self.block_on(self.inner.pull_model(model_name, allow_insecure))
+    }
+
+    /// Pull a model with streaming, see [`crate::Ollama::pull_model_stream`].
+    pub fn pull_model_stream(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> crate::error::Result<BlockingStream<crate::error::Result<PullModelStatus>>> {
+        let stream = self.block_on(self.inner.pull_model_stream(model_name, allow_insecure))?;
+        Ok(self.stream(stream))
+    }
+
+    /// Push a model, see [`crate::Ollama::push_model`].
+    pub fn push_model(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> crate::error::Result<PushModelStatus> {
+        self.block_on(self.inner.push_model(model_name, allow_insecure))
+    }
+
+    /// Push a model with streaming, see [`crate::Ollama::push_model_stream`].
+    pub fn push_model_stream(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> crate::error::Result<BlockingStream<crate::error::Result<PushModelStatus>>> {
+        let stream = self.block_on(self.inner.push_model_stream(model_name, allow_insecure))?;
+        Ok(self.stream(stream))
+    }
+
+    /// Check if a blob exists on the server, see [`crate::Ollama::blob_exists`].
+    pub fn blob_exists(&self, digest: &str) -> crate::error::Result<bool> {
+        self.block_on(self.inner.blob_exists(digest))
+    }
+
+    /// Upload a blob, see [`crate::Ollama::create_blob`].
+    pub fn create_blob(&self, bytes: Vec<u8>) -> crate::error::Result<String> {
+        self.block_on(self.inner.create_blob(bytes))
+    }
+
+    /// Returns the version of the server, see [`crate::Ollama::version`].
+    pub fn version(&self) -> crate::error::Result<String> {
+        self.block_on(self.inner.version())
+    }
+}
+
+/// A streamed response read as an iterator, blocking until the next item is received
+pub struct BlockingStream<T> {
+    stream: Pin<Box<dyn Stream<Item = T> + Send>>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> Iterator for BlockingStream<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+impl<T> std::fmt::Debug for BlockingStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BlockingStream")
+    }
+}
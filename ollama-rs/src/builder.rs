@@ -0,0 +1,622 @@
+use std::{net::SocketAddr, time::Duration};
+
+use url::Url;
+
+use crate::{error::OllamaError, IntoUrl, Ollama};
+
+#[derive(Debug, Clone, Copy)]
+enum ProxyScheme {
+    All,
+    Http,
+    Https,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HttpVersion {
+    Auto,
+    Http1Only,
+    #[cfg(feature = "http2")]
+    Http2PriorKnowledge,
+}
+
+/// Builds an [`Ollama`] client with explicit transport settings, instead of the defaults of
+/// [`Ollama::default`] and the proxy environment variables.
+///
+/// ```
+/// use ollama_rs::Ollama;
+///
+/// let ollama = Ollama::builder()
+///     .url("http://ollama.internal:11434")
+///     .https_proxy("http://proxy.internal:3128")
+///     .no_proxy("localhost,127.0.0.1,.internal")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct OllamaBuilder {
+    url: Result<Url, url::ParseError>,
+    proxies: Vec<(ProxyScheme, String)>,
+    no_proxy: Option<String>,
+    env_proxy: bool,
+    resolve: Vec<(String, Vec<SocketAddr>)>,
+    connect_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    tcp_keepalive_interval: Option<Duration>,
+    tcp_keepalive_retries: Option<u32>,
+    http_version: HttpVersion,
+    #[cfg(feature = "gzip")]
+    gzip: Option<bool>,
+    #[cfg(feature = "zstd")]
+    zstd: Option<bool>,
+    #[cfg(feature = "http2")]
+    http2_keep_alive_interval: Option<Duration>,
+    #[cfg(feature = "http2")]
+    http2_window_sizes: (Option<u32>, Option<u32>),
+    request_timeout: Option<Duration>,
+    stream_idle_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    #[cfg(feature = "stream")]
+    stream_buffer: crate::backpressure::StreamBuffer,
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    identity: Option<ClientIdentity>,
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    root_certificates: Vec<Vec<u8>>,
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    built_in_root_certificates: bool,
+    #[cfg(all(feature = "rustls", not(target_arch = "wasm32")))]
+    pins: Vec<crate::error::Result<crate::pinning::Pin>>,
+    #[cfg(feature = "stub")]
+    stub: Option<crate::stub::StubTransport>,
+}
+
+impl Default for OllamaBuilder {
+    fn default() -> Self {
+        Self {
+            url: Url::parse("http://127.0.0.1:11434"),
+            proxies: vec![],
+            no_proxy: None,
+            env_proxy: true,
+            resolve: vec![],
+            connect_timeout: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            tcp_keepalive: None,
+            tcp_keepalive_interval: None,
+            tcp_keepalive_retries: None,
+            http_version: HttpVersion::Auto,
+            #[cfg(feature = "gzip")]
+            gzip: None,
+            #[cfg(feature = "zstd")]
+            zstd: None,
+            #[cfg(feature = "http2")]
+            http2_keep_alive_interval: None,
+            #[cfg(feature = "http2")]
+            http2_window_sizes: (None, None),
+            request_timeout: None,
+            stream_idle_timeout: None,
+            user_agent: None,
+            #[cfg(feature = "stream")]
+            stream_buffer: Default::default(),
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            identity: None,
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            root_certificates: vec![],
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            built_in_root_certificates: true,
+            #[cfg(all(feature = "rustls", not(target_arch = "wasm32")))]
+            pins: vec![],
+            #[cfg(feature = "stub")]
+            stub: None,
+        }
+    }
+}
+
+impl OllamaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The URL of the Ollama service (Default: `http://127.0.0.1:11434`)
+    pub fn url(mut self, url: impl IntoUrl) -> Self {
+        self.url = url.into_url();
+        self
+    }
+
+    /// Sends every request through a proxy. Setting any proxy disables the proxy environment variables.
+    ///
+    /// With the `socks` feature, the proxy can be a SOCKS5 proxy, such as the dynamic forwarding of
+    /// `ssh -D 1080 gpu-box` with `socks5h://localhost:1080`. The `socks5h` scheme resolves the
+    /// host of Ollama on the proxy side, `socks5` resolves it locally.
+    pub fn proxy(mut self, proxy_url: impl Into<String>) -> Self {
+        self.proxies.push((ProxyScheme::All, proxy_url.into()));
+        self
+    }
+
+    /// Sends the `http://` requests through a proxy
+    pub fn http_proxy(mut self, proxy_url: impl Into<String>) -> Self {
+        self.proxies.push((ProxyScheme::Http, proxy_url.into()));
+        self
+    }
+
+    /// Sends the `https://` requests through a proxy
+    pub fn https_proxy(mut self, proxy_url: impl Into<String>) -> Self {
+        self.proxies.push((ProxyScheme::Https, proxy_url.into()));
+        self
+    }
+
+    /// Hosts reached without the proxies, as a comma-separated list in the format of the `NO_PROXY`
+    /// environment variable, such as `localhost,127.0.0.1,.internal,10.0.0.0/8`
+    pub fn no_proxy(mut self, hosts: impl Into<String>) -> Self {
+        self.no_proxy = Some(hosts.into());
+        self
+    }
+
+    /// Ignores the proxy environment variables (`HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY`, `NO_PROXY`)
+    /// and the system proxy settings, so that only the proxies set on this builder are used
+    pub fn no_env_proxy(mut self) -> Self {
+        self.env_proxy = false;
+        self
+    }
+
+    /// Connects to `host` at this address instead of resolving it with the DNS, such as to pin
+    /// `ollama.internal` to the IP of a container without editing `/etc/hosts`. The port of the
+    /// address is ignored, the port of the URL is used. The `Host` header and the name checked
+    /// against the TLS certificate are still `host`.
+    ///
+    /// ```
+    /// use ollama_rs::Ollama;
+    ///
+    /// let ollama = Ollama::builder()
+    ///     .url("http://ollama.internal:11434")
+    ///     .resolve("ollama.internal", "10.0.0.5:0".parse().unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn resolve(self, host: impl Into<String>, addr: SocketAddr) -> Self {
+        self.resolve_to_addrs(host, &[addr])
+    }
+
+    /// Connects to `host` at one of these addresses instead of resolving it with the DNS, see [`OllamaBuilder::resolve`]
+    pub fn resolve_to_addrs(mut self, host: impl Into<String>, addrs: &[SocketAddr]) -> Self {
+        self.resolve.push((host.into(), addrs.to_vec()));
+        self
+    }
+
+    /// The timeout to establish a connection to Ollama, including the TLS handshake (Default: none)
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// The largest number of idle connections kept open to Ollama (Default: unlimited).
+    /// With many concurrent requests, such as a bulk embedding, a small pool closes the connections
+    /// opened by a burst as soon as they are released, and the next burst opens them again.
+    pub fn pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max_idle);
+        self
+    }
+
+    /// How long an idle connection is kept open before being closed (Default: 90s).
+    /// A local daemon doesn't close the connections itself, so they can be kept much longer.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sends TCP keepalive probes on the open connections once idle for this time (Default: none),
+    /// so that the connections dropped by a firewall or a NAT while idle are detected
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// The interval between the TCP keepalive probes after the first one (Default: the system setting,
+    /// often 75s). A middlebox dropping the idle connections after a minute, such as some VPNs and
+    /// mobile networks, needs a shorter interval to keep a long generation streaming.
+    pub fn tcp_keepalive_interval(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive_interval = Some(interval);
+        self
+    }
+
+    /// The number of unanswered TCP keepalive probes after which the connection is closed (Default: the system setting)
+    pub fn tcp_keepalive_retries(mut self, retries: u32) -> Self {
+        self.tcp_keepalive_retries = Some(retries);
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    #[cfg(feature = "gzip")]
+    /// Whether to accept and decode gzip-compressed responses (Default: true).
+    /// The streamed responses are always requested uncompressed, so that each chunk arrives as soon as it is generated.
+    pub fn gzip(mut self, enabled: bool) -> Self {
+        self.gzip = Some(enabled);
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "zstd")))]
+    #[cfg(feature = "zstd")]
+    /// Whether to accept and decode zstd-compressed responses (Default: true), see [`OllamaBuilder::gzip`]
+    pub fn zstd(mut self, enabled: bool) -> Self {
+        self.zstd = Some(enabled);
+        self
+    }
+
+    /// Only speaks HTTP/1.1, for proxies that mishandle long-lived HTTP/2 streams (Default: HTTP/2 when
+    /// negotiated with TLS, with the `http2` feature, and HTTP/1.1 otherwise)
+    pub fn http1_only(mut self) -> Self {
+        self.http_version = HttpVersion::Http1Only;
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "http2")))]
+    #[cfg(feature = "http2")]
+    /// Speaks HTTP/2 without negotiating it first, for proxies serving HTTP/2 over cleartext (h2c)
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http_version = HttpVersion::Http2PriorKnowledge;
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "http2")))]
+    #[cfg(feature = "http2")]
+    /// The sizes of the HTTP/2 receive windows, of each stream and of the whole connection (Default: 2 MiB and 5 MiB).
+    /// The server sends at most this many bytes not yet read by the client, which bounds the memory buffered
+    /// for a slow consumer; over a link with a high latency, larger windows keep the throughput of a pull.
+    /// Reading ahead of the consumer is configured with [`Ollama::set_stream_buffer`].
+    pub fn http2_window_sizes(mut self, stream: u32, connection: u32) -> Self {
+        self.http2_window_sizes = (Some(stream), Some(connection));
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "http2")))]
+    #[cfg(feature = "http2")]
+    /// Sends HTTP/2 pings at this interval (Default: none), so that a proxy doesn't close
+    /// a connection carrying a slow stream as idle
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// The timeout of the non-streaming requests, see [`Ollama::set_request_timeout`] (Default: none)
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// The longest wait for the next chunk of a streamed response, see [`Ollama::set_stream_idle_timeout`] (Default: none)
+    pub fn stream_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_idle_timeout = Some(timeout);
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// The bounds of the memory used by the streamed responses, see [`Ollama::set_stream_buffer`]
+    pub fn stream_buffer(mut self, buffer: crate::backpressure::StreamBuffer) -> Self {
+        self.stream_buffer = buffer;
+        self
+    }
+
+    /// The `User-Agent` of the requests, see [`Ollama::set_user_agent`] (Default: [`crate::DEFAULT_USER_AGENT`])
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Identifies the application in the `User-Agent` of the requests, see [`Ollama::set_app_name`]
+    pub fn app_name(mut self, name: &str, version: &str) -> Self {
+        self.user_agent = Some(crate::user_agent::app_user_agent(name, version));
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "native-tls", feature = "rustls"))))]
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    /// Authenticates with a client certificate, for Ollama behind a proxy requiring mutual TLS.
+    /// * `certificate_pem` - The PEM-encoded certificate chain of the client
+    /// * `key_pem` - The PEM-encoded PKCS #8 private key of the client
+    pub fn client_identity(mut self, certificate_pem: Vec<u8>, key_pem: Vec<u8>) -> Self {
+        self.identity = Some(ClientIdentity {
+            certificate_pem,
+            key_pem,
+        });
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "native-tls", feature = "rustls"))))]
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    /// Trusts the certificates of a PEM bundle, such as the CA of a private PKI, in addition to the built-in roots
+    pub fn add_root_certificates(mut self, bundle_pem: Vec<u8>) -> Self {
+        self.root_certificates.push(bundle_pem);
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "native-tls", feature = "rustls"))))]
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    /// Whether to trust the built-in root certificates (Default: true).
+    /// Disable it to only trust the certificates added with [`OllamaBuilder::add_root_certificates`].
+    pub fn built_in_root_certificates(mut self, enabled: bool) -> Self {
+        self.built_in_root_certificates = enabled;
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls")))]
+    #[cfg(all(feature = "rustls", not(target_arch = "wasm32")))]
+    /// Only trusts a server presenting this certificate, given as the base64 SHA-256 digest of its DER encoding,
+    /// such as the output of `openssl x509 -outform der | openssl dgst -sha256 -binary | base64`.
+    ///
+    /// The pins replace the certificate authorities: a self-signed certificate can be pinned, and the
+    /// root certificates are ignored. A server matching any of the pins is trusted, so that the
+    /// next certificate can be pinned before a rotation. Pinning can't be combined with a client identity.
+    pub fn pin_certificate_sha256(mut self, pin: &str) -> Self {
+        self.pins.push(crate::pinning::Pin::parse(
+            pin,
+            crate::pinning::Pin::Certificate,
+        ));
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "rustls")))]
+    #[cfg(all(feature = "rustls", not(target_arch = "wasm32")))]
+    /// Only trusts a server whose certificate has this public key, given as the base64 SHA-256 digest of its
+    /// `subjectPublicKeyInfo` (the `pin-sha256` format), see [`OllamaBuilder::pin_certificate_sha256`].
+    /// A public key pin survives the renewals of the certificate that keep its key.
+    pub fn pin_public_key_sha256(mut self, pin: &str) -> Self {
+        self.pins.push(crate::pinning::Pin::parse(
+            pin,
+            crate::pinning::Pin::PublicKey,
+        ));
+        self
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stub")))]
+    #[cfg(feature = "stub")]
+    /// Answers the requests with a stub instead of sending them, such as to develop without an Ollama
+    /// server, see [`crate::stub::StubTransport`]. The other settings of the builder still apply.
+    ///
+    /// ```
+    /// use ollama_rs::{stub::StubTransport, Ollama};
+    ///
+    /// let offline = std::env::var("OLLAMA_OFFLINE").is_ok();
+    /// let mut builder = Ollama::builder();
+    /// if offline {
+    ///     builder = builder.stub(StubTransport::new());
+    /// }
+    /// let ollama = builder.build().unwrap();
+    /// ```
+    pub fn stub(mut self, stub: crate::stub::StubTransport) -> Self {
+        self.stub = Some(stub);
+        self
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn client_builder(&self) -> Result<reqwest::ClientBuilder, OllamaError> {
+        let mut builder = crate::user_agent::client_builder();
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        for (host, addrs) in &self.resolve {
+            builder = builder.resolve_to_addrs(host, addrs);
+        }
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        if let Some(interval) = self.tcp_keepalive_interval {
+            builder = builder.tcp_keepalive_interval(interval);
+        }
+        if let Some(retries) = self.tcp_keepalive_retries {
+            builder = builder.tcp_keepalive_retries(retries);
+        }
+        #[cfg(feature = "gzip")]
+        if let Some(enabled) = self.gzip {
+            builder = builder.gzip(enabled);
+        }
+        #[cfg(feature = "zstd")]
+        if let Some(enabled) = self.zstd {
+            builder = builder.zstd(enabled);
+        }
+        match self.http_version {
+            HttpVersion::Auto => {}
+            HttpVersion::Http1Only => builder = builder.http1_only(),
+            #[cfg(feature = "http2")]
+            HttpVersion::Http2PriorKnowledge => builder = builder.http2_prior_knowledge(),
+        }
+        #[cfg(feature = "http2")]
+        if let Some(interval) = self.http2_keep_alive_interval {
+            builder = builder.http2_keep_alive_interval(interval);
+        }
+        #[cfg(feature = "http2")]
+        if let (Some(stream), Some(connection)) = self.http2_window_sizes {
+            builder = builder
+                .http2_initial_stream_window_size(stream)
+                .http2_initial_connection_window_size(connection);
+        }
+
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        {
+            if let Some(identity) = &self.identity {
+                builder = builder.identity(identity.parse()?);
+                #[cfg(feature = "rustls")]
+                {
+                    builder = builder.use_rustls_tls();
+                }
+            }
+            for bundle in &self.root_certificates {
+                for certificate in reqwest::Certificate::from_pem_bundle(bundle)? {
+                    builder = builder.add_root_certificate(certificate);
+                }
+            }
+            builder = builder.tls_built_in_root_certs(self.built_in_root_certificates);
+        }
+
+        #[cfg(feature = "rustls")]
+        if !self.pins.is_empty() {
+            if self.identity.is_some() {
+                return Err(OllamaError::Other(
+                    "Certificate pinning can't be combined with a client identity".to_string(),
+                ));
+            }
+            let pins = self
+                .pins
+                .iter()
+                .map(|pin| match pin {
+                    Ok(pin) => Ok(*pin),
+                    Err(e) => Err(OllamaError::Other(e.to_string())),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let alpn = match self.http_version {
+                HttpVersion::Http1Only => vec![b"http/1.1".to_vec()],
+                #[cfg(feature = "http2")]
+                _ => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+                #[cfg(not(feature = "http2"))]
+                _ => vec![b"http/1.1".to_vec()],
+            };
+            builder = builder.use_preconfigured_tls(crate::pinning::pinned_tls_config(pins, alpn)?);
+        }
+
+        if !self.env_proxy {
+            builder = builder.no_proxy();
+        }
+
+        let no_proxy = self
+            .no_proxy
+            .as_deref()
+            .and_then(reqwest::NoProxy::from_string);
+        for (scheme, proxy_url) in &self.proxies {
+            #[cfg(not(feature = "socks"))]
+            if proxy_url.starts_with("socks") {
+                return Err(OllamaError::Other(format!(
+                    "The SOCKS proxy {proxy_url} requires the `socks` feature"
+                )));
+            }
+            let proxy = match scheme {
+                ProxyScheme::All => reqwest::Proxy::all(proxy_url),
+                ProxyScheme::Http => reqwest::Proxy::http(proxy_url),
+                ProxyScheme::Https => reqwest::Proxy::https(proxy_url),
+            }?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy.clone()));
+        }
+
+        Ok(builder)
+    }
+
+    /// In browsers, the connections, proxies and certificates are managed by the browser
+    #[cfg(target_arch = "wasm32")]
+    fn client_builder(&self) -> Result<reqwest::ClientBuilder, OllamaError> {
+        let mut unsupported = vec![];
+        if !self.proxies.is_empty() || self.no_proxy.is_some() {
+            unsupported.push("proxies");
+        }
+        if self.connect_timeout.is_some() {
+            unsupported.push("a connect timeout");
+        }
+        if !self.resolve.is_empty() {
+            unsupported.push("the resolved addresses");
+        }
+        if self.pool_max_idle_per_host.is_some()
+            || self.pool_idle_timeout.is_some()
+            || self.tcp_keepalive.is_some()
+            || self.tcp_keepalive_interval.is_some()
+            || self.tcp_keepalive_retries.is_some()
+        {
+            unsupported.push("the connection pool");
+        }
+        #[cfg(feature = "gzip")]
+        if self.gzip.is_some() {
+            unsupported.push("the compression");
+        }
+        #[cfg(feature = "zstd")]
+        if self.zstd.is_some() && !unsupported.contains(&"the compression") {
+            unsupported.push("the compression");
+        }
+        if self.http_version != HttpVersion::Auto {
+            unsupported.push("the HTTP version");
+        }
+        #[cfg(feature = "http2")]
+        if self.http2_keep_alive_interval.is_some() || self.http2_window_sizes.0.is_some() {
+            unsupported.push("HTTP/2 settings");
+        }
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        if self.identity.is_some()
+            || !self.root_certificates.is_empty()
+            || !self.built_in_root_certificates
+        {
+            unsupported.push("TLS settings");
+        }
+        // There are no proxy environment variables to ignore
+        let _ = self.env_proxy;
+
+        if !unsupported.is_empty() {
+            return Err(OllamaError::Other(format!(
+                "The browser manages the connections, {} can't be set",
+                unsupported.join(", ")
+            )));
+        }
+
+        Ok(crate::user_agent::client_builder())
+    }
+
+    pub fn build(self) -> Result<Ollama, OllamaError> {
+        let reqwest_client = self.client_builder()?.build()?;
+        let mut ollama = Ollama::from_url(self.url?).with_client(reqwest_client);
+        ollama.set_request_timeout(self.request_timeout);
+        ollama.set_stream_idle_timeout(self.stream_idle_timeout);
+        ollama.set_user_agent(self.user_agent.as_deref())?;
+        #[cfg(feature = "stream")]
+        ollama.set_stream_buffer(self.stream_buffer);
+        #[cfg(feature = "stub")]
+        if let Some(stub) = self.stub {
+            ollama = ollama.with_transport(stub);
+        }
+
+        Ok(ollama)
+    }
+}
+
+/// A client certificate and its private key, kept out of the `Debug` output
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+struct ClientIdentity {
+    certificate_pem: Vec<u8>,
+    key_pem: Vec<u8>,
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+impl std::fmt::Debug for ClientIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ClientIdentity(..)")
+    }
+}
+
+#[cfg(all(
+    any(feature = "native-tls", feature = "rustls"),
+    not(target_arch = "wasm32")
+))]
+impl ClientIdentity {
+    /// Parses the identity with the TLS backend in use
+    fn parse(&self) -> reqwest::Result<reqwest::Identity> {
+        #[cfg(feature = "rustls")]
+        {
+            let pem = [&self.certificate_pem[..], &b"\n"[..], &self.key_pem[..]].concat();
+            reqwest::Identity::from_pem(&pem)
+        }
+        #[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+        {
+            reqwest::Identity::from_pkcs8_pem(&self.certificate_pem, &self.key_pem)
+        }
+    }
+}
+
+impl Ollama {
+    /// Returns a builder to configure the transport of the client, see [`OllamaBuilder`].
+    pub fn builder() -> OllamaBuilder {
+        OllamaBuilder::new()
+    }
+}
@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
 use crate::{
+    error::OllamaError,
     generation::{
         chat::{request::ChatMessageRequest, ChatMessage, ChatMessageResponse, MessageRole},
         parameters::{FormatType, KeepAlive},
         tools::{Tool, ToolHolder, ToolInfo},
     },
-    history::ChatHistory,
+    history::{drop_last_response, ChatHistory},
     models::ModelOptions,
     Ollama,
 };
@@ -26,6 +27,9 @@ pub struct Coordinator<C: ChatHistory> {
     debug: bool,
     format: Option<FormatType>,
     keep_alive: Option<KeepAlive>,
+    keep_thinking: bool,
+    /// Whether [`Coordinator::check_capabilities`] succeeded for the current tools
+    capabilities_checked: bool,
 }
 
 impl<C: ChatHistory> Coordinator<C> {
@@ -51,12 +55,15 @@ impl<C: ChatHistory> Coordinator<C> {
             debug: false,
             format: None,
             keep_alive: None,
+            keep_thinking: false,
+            capabilities_checked: false,
         }
     }
 
     pub fn add_tool<T: Tool + 'static>(mut self, tool: T) -> Self {
         self.tool_infos.push(ToolInfo::new::<_, T>());
         self.tools.insert(T::name().to_string(), Box::new(tool));
+        self.capabilities_checked = false;
         self
     }
 
@@ -80,10 +87,38 @@ impl<C: ChatHistory> Coordinator<C> {
         self
     }
 
+    /// Keeps the thinking of the responses in the history (Default: false),
+    /// see [`ChatMessageRequest::keep_thinking_in_history`].
+    pub fn keep_thinking(mut self, keep_thinking: bool) -> Self {
+        self.keep_thinking = keep_thinking;
+        self
+    }
+
+    /// Checks that the model supports tool calling if tools were added, so that misconfigurations
+    /// fail with [`OllamaError::MissingCapability`] before the conversation starts.
+    ///
+    /// Called on the first turn of [`Coordinator::chat`] and `chat_stream`, the success is then cached.
+    pub async fn check_capabilities(&self) -> crate::error::Result<()> {
+        if self.tool_infos.is_empty() {
+            return Ok(());
+        }
+
+        self.ollama.require_tools(self.model.clone()).await
+    }
+
+    async fn ensure_capabilities(&mut self) -> crate::error::Result<()> {
+        if !self.capabilities_checked {
+            self.check_capabilities().await?;
+            self.capabilities_checked = true;
+        }
+        Ok(())
+    }
+
     fn generate_request(&self, messages: Vec<ChatMessage>) -> ChatMessageRequest {
         let mut request = ChatMessageRequest::new(self.model.clone(), messages)
             .options(self.options.clone())
-            .tools(self.tool_infos.clone());
+            .tools(self.tool_infos.clone())
+            .keep_thinking_in_history(self.keep_thinking);
 
         if let Some(keep_alive) = &self.keep_alive {
             request = request.keep_alive(keep_alive.clone());
@@ -110,6 +145,8 @@ impl<C: ChatHistory> Coordinator<C> {
         &mut self,
         messages: Vec<ChatMessage>,
     ) -> crate::error::Result<ChatMessageResponse> {
+        self.ensure_capabilities().await?;
+
         if self.debug {
             for m in &messages {
                 eprintln!("Hit {} with:", self.model);
@@ -134,16 +171,17 @@ impl<C: ChatHistory> Coordinator<C> {
                     return Err(crate::error::ToolCallError::UnknownToolName.into());
                 };
 
-                let resp = tool
-                    .call(call.function.arguments)
-                    .await
-                    .map_err(crate::error::ToolCallError::InternalToolError)?;
+                let resp = tool.call(call.function.arguments).await;
+                crate::telemetry::record_tool_call(&call.function.name, resp.is_ok());
+                let resp = resp.map_err(crate::error::ToolCallError::InternalToolError)?;
 
                 if self.debug {
                     eprintln!("Tool response: {}", &resp);
                 }
 
-                self.history.push(ChatMessage::tool(resp))
+                let mut message = ChatMessage::tool(resp);
+                message.tool_call_id = call.id;
+                self.history.push(message)
             }
 
             // recurse
@@ -159,6 +197,31 @@ impl<C: ChatHistory> Coordinator<C> {
             Ok(resp)
         }
     }
+
+    /// Generates the response to the last user message again, e.g. for a "regenerate" button.
+    ///
+    /// The last assistant message and the tool messages it depends on are removed from the history
+    /// before running the turn again. `options` (e.g. with a different seed) replace the coordinator
+    /// options for this turn only.
+    pub async fn regenerate(
+        &mut self,
+        options: Option<ModelOptions>,
+    ) -> crate::error::Result<ChatMessageResponse> {
+        if !drop_last_response(&mut self.history) {
+            return Err(OllamaError::Other(
+                "There is no user message to regenerate a response for, or the history can't be truncated"
+                    .to_string(),
+            ));
+        }
+
+        let previous_options = options.map(|o| std::mem::replace(&mut self.options, o));
+        let resp = self.chat(vec![]).await;
+        if let Some(previous_options) = previous_options {
+            self.options = previous_options;
+        }
+
+        resp
+    }
 }
 
 #[cfg(feature = "stream")]
@@ -172,9 +235,7 @@ pub mod chat_stream {
     use std::sync::Arc;
     use tokio::sync::Mutex;
 
-    pub type ChatStream = std::pin::Pin<
-        Box<dyn tokio_stream::Stream<Item = Result<ChatMessageResponse, OllamaError>> + Send>,
-    >;
+    pub type ChatStream = crate::BoxStream<'static, Result<ChatMessageResponse, OllamaError>>;
 
     impl<C: ChatHistory + Default + Clone + Debug + Send + 'static> Coordinator<C> {
         pub async fn chat_stream(
@@ -184,6 +245,8 @@ pub mod chat_stream {
             use async_stream::try_stream;
             use tokio_stream::StreamExt;
 
+            self.ensure_capabilities().await?;
+
             if self.debug {
                 for m in &messages {
                     eprintln!("Hit {} with:", self.model);
@@ -207,7 +270,7 @@ pub mod chat_stream {
                         if let Ok(i) = i.as_ref() {
                             tool_calls.extend_from_slice(&i.message.tool_calls);
                         }
-                        yield i.unwrap();
+                        yield i?;
                     }
 
                     let keep_going = !tool_calls.is_empty();
@@ -221,16 +284,18 @@ pub mod chat_stream {
                             panic!();
                         };
 
-                        let resp = tool
-                            .call(call.function.arguments)
-                            .await.unwrap();
+                        let resp = tool.call(call.function.arguments).await;
+                        crate::telemetry::record_tool_call(&call.function.name, resp.is_ok());
+                        let resp = resp.unwrap();
                         //.map_err(|x| crate::error::OllamaError::from(crate::error::ToolCallError::InternalToolError(x)))?;
 
                         if self.debug {
                             eprintln!("Tool response: {}", &resp);
                         }
 
-                        history.lock().await.push(ChatMessage::tool(resp))
+                        let mut message = ChatMessage::tool(resp);
+                        message.tool_call_id = call.id;
+                        history.lock().await.push(message)
                     }
 
                     if keep_going {
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::{generation::parameters::KeepAlive, models::ModelOptions, Ollama};
+
+/// The parameters applied to the requests of a client that omit them, see [`Ollama::with_defaults`]
+///
+/// ```
+/// use ollama_rs::{defaults::DefaultParams, generation::parameters::KeepAlive, models::ModelOptions, Ollama};
+///
+/// let ollama = Ollama::default();
+/// let summarizer = ollama.with_defaults(
+///     DefaultParams::new()
+///         .model("llama3.2:3b")
+///         .options(ModelOptions::default().temperature(0.2))
+///         .keep_alive(KeepAlive::Indefinitely),
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DefaultParams {
+    model: Option<String>,
+    options: Option<ModelOptions>,
+    keep_alive: Option<KeepAlive>,
+}
+
+impl DefaultParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The model of the requests whose model name is empty
+    pub fn model(mut self, model_name: impl Into<String>) -> Self {
+        self.model = Some(model_name.into());
+        self
+    }
+
+    /// The options of the requests without options
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// The keep alive of the requests without one
+    pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// Returns the default model, if any
+    pub fn default_model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Returns the default options, if any
+    pub fn default_options(&self) -> Option<&ModelOptions> {
+        self.options.as_ref()
+    }
+
+    /// Returns the default keep alive, if any
+    pub fn default_keep_alive(&self) -> Option<&KeepAlive> {
+        self.keep_alive.as_ref()
+    }
+}
+
+impl Ollama {
+    /// Returns a clone of the client applying defaults to the generation, chat and embeddings
+    /// requests that omit them, such as a client handed to a subsystem using its own model.
+    ///
+    /// A request with an empty model name uses the default model, and a request without options
+    /// or keep alive uses the default ones. The options of a request replace the default options
+    /// as a whole. The clone shares the connections, caches and requests in flight of the client,
+    /// and its defaults replace the defaults of the client, if any.
+    pub fn with_defaults(&self, defaults: DefaultParams) -> Self {
+        let mut ollama = self.clone();
+        ollama.defaults = Some(Arc::new(defaults));
+        ollama
+    }
+
+    /// Returns the defaults of the client, if any
+    pub fn defaults(&self) -> Option<&DefaultParams> {
+        self.defaults.as_deref()
+    }
+
+    /// Fills the parameters omitted by a request with the defaults of the client
+    pub(crate) fn apply_defaults(
+        &self,
+        model_name: &mut String,
+        options: &mut Option<ModelOptions>,
+        keep_alive: &mut Option<KeepAlive>,
+    ) {
+        let Some(defaults) = &self.defaults else {
+            return;
+        };
+        if let (true, Some(model)) = (model_name.is_empty(), &defaults.model) {
+            model_name.clone_from(model);
+        }
+        if options.is_none() {
+            options.clone_from(&defaults.options);
+        }
+        if keep_alive.is_none() {
+            keep_alive.clone_from(&defaults.keep_alive);
+        }
+    }
+}
@@ -27,6 +27,180 @@ pub enum OllamaError {
     Other(String),
     #[error("URL Parse error: {0}")]
     Url(#[from] url::ParseError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Input exceeds the context length of the model: {0}")]
+    ContextLengthExceeded(String),
+    #[error("Model not found: {0}")]
+    ModelNotFound(String),
+    #[error("Out of memory: {0}")]
+    OutOfMemory(String),
+    #[error("Unsupported option: {0}")]
+    UnsupportedOption(String),
+    #[error("Unsupported image format, expected PNG, JPEG or WebP")]
+    UnsupportedImageFormat,
+    #[error("Server unavailable ({status}): {message}")]
+    ServerUnavailable { status: u16, message: String },
+    #[error("Expected embeddings of dimension {expected}, got {actual}")]
+    EmbeddingDimensionMismatch { expected: usize, actual: usize },
+    #[error("Registry authentication required: {0}")]
+    RegistryUnauthorized(String),
+    #[error("Registry permission denied: {0}")]
+    RegistryPermissionDenied(String),
+    #[error("{feature} requires Ollama >= {required}, the server is {actual}")]
+    UnsupportedServerVersion {
+        feature: String,
+        required: crate::version::ServerVersion,
+        actual: crate::version::ServerVersion,
+    },
+    #[error("Model {model} does not support {capability}")]
+    MissingCapability { model: String, capability: String },
+    #[error("Model {0} already exists")]
+    ModelAlreadyExists(String),
+    #[error("No data received from the stream for {0:?}")]
+    StreamIdleTimeout(std::time::Duration),
+    #[error("The consumer of the stream fell more than {0} chunks behind")]
+    StreamBufferFull(usize),
+    #[error("A line of the streamed response exceeds {0} bytes")]
+    StreamLineTooLong(usize),
+    #[error("The request was aborted")]
+    Aborted,
+    #[error("Vector store error: {0}")]
+    VectorStoreError(Box<dyn std::error::Error + Send + Sync>),
+    #[error("{source} (request ID {request_id})")]
+    WithRequestId {
+        request_id: String,
+        #[source]
+        source: Box<OllamaError>,
+    },
+    /// An error shared by several results, e.g. the failure of a batch of embeddings.
+    /// Use [`OllamaError::inner`] to match on the variant of the original error.
+    #[error(transparent)]
+    Shared(std::sync::Arc<OllamaError>),
+}
+
+impl OllamaError {
+    /// The ID of the request that failed, see [`crate::Ollama::set_request_id_header`]
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::WithRequestId { request_id, .. } => Some(request_id),
+            Self::Shared(error) => error.request_id(),
+            _ => None,
+        }
+    }
+
+    /// The error without the ID of its request, to match on its variant
+    pub fn inner(&self) -> &OllamaError {
+        match self {
+            Self::WithRequestId { source, .. } => source.inner(),
+            Self::Shared(error) => error.inner(),
+            _ => self,
+        }
+    }
+
+    /// Attaches the ID of the request that failed, if any
+    pub(crate) fn with_request_id(self, request_id: Option<String>) -> Self {
+        match request_id {
+            Some(request_id) => Self::WithRequestId {
+                request_id,
+                source: Box::new(self),
+            },
+            None => self,
+        }
+    }
+
+    /// Reads the error of a response whose status isn't a success, see [`OllamaError::from_response_body`].
+    /// The error carries the ID of the request, if any.
+    /// The statuses that may go away by retrying the request, such as a busy server, are [`OllamaError::ServerUnavailable`].
+    pub(crate) async fn from_response(res: reqwest::Response) -> Self {
+        let status = res.status();
+        if crate::transport::is_retryable_status(status) {
+            return Self::from_response_with(res, |body| Self::ServerUnavailable {
+                status: status.as_u16(),
+                message: error_message(&body).unwrap_or(body),
+            })
+            .await;
+        }
+
+        Self::from_response_with(res, Self::from_response_body).await
+    }
+
+    /// Reads the error of a response whose status isn't a success, mapping its body with `map`.
+    /// The error carries the ID of the request, if any.
+    pub(crate) async fn from_response_with(
+        res: reqwest::Response,
+        map: impl FnOnce(String) -> Self,
+    ) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let request_id = res
+            .extensions()
+            .get::<crate::request_id::RequestId>()
+            .map(|id| id.0.clone());
+        #[cfg(target_arch = "wasm32")]
+        let request_id = None;
+
+        let error = match res.text().await {
+            Ok(body) => map(body),
+            Err(e) => Self::ReqwestError(e),
+        };
+        error.with_request_id(request_id)
+    }
+
+    /// Maps the body of an error response of Ollama, `{"error":"..."}` such as `model "llama3" not found`,
+    /// to the variant of the failure, or to [`OllamaError::Other`] with the message if it isn't recognized.
+    /// The errors of the OpenAI-compatible endpoints, `{"error":{"message":"..."}}`, are recognized too.
+    pub(crate) fn from_response_body(body: String) -> Self {
+        let message = error_message(&body).unwrap_or_else(|| body.clone());
+        let text = message.to_lowercase();
+
+        if text.contains("model") && (text.contains("not found") || text.contains("does not exist"))
+        {
+            Self::ModelNotFound(message)
+        } else if [
+            "out of memory",
+            "requires more system memory",
+            "insufficient memory",
+            "unable to allocate",
+        ]
+        .iter()
+        .any(|s| text.contains(s))
+        {
+            Self::OutOfMemory(message)
+        } else if text.contains("context length") {
+            Self::ContextLengthExceeded(message)
+        } else if [
+            "does not support",
+            "not supported",
+            "invalid option",
+            "unknown option",
+        ]
+        .iter()
+        .any(|s| text.contains(s))
+        {
+            Self::UnsupportedOption(message)
+        } else {
+            Self::Other(message)
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    /// Maps a line of a streamed response that failed to parse as `error`: an error sent by Ollama
+    /// in the middle of the stream, `{"error":"..."}`, is mapped with [`OllamaError::from_response_body`].
+    pub(crate) fn from_stream_line(line: &[u8], error: serde_json::Error) -> Self {
+        match std::str::from_utf8(line) {
+            Ok(line) if error_message(line).is_some() => Self::from_response_body(line.to_string()),
+            _ => Self::JsonError(error),
+        }
+    }
+}
+
+/// The message of an error response, `{"error":"..."}` or `{"error":{"message":"..."}}`
+fn error_message(body: &str) -> Option<String> {
+    let value = serde_json::from_str::<serde_json::Value>(body).ok()?;
+    match &value["error"] {
+        serde_json::Value::String(message) => Some(message.clone()),
+        error => error["message"].as_str().map(str::to_string),
+    }
 }
 
 /// Represents an internal error within the Ollama service.
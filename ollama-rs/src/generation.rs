@@ -1,11 +1,13 @@
 /// Modules related to generation tasks.
 ///
 /// This file aggregates various submodules that handle different aspects
-/// of generation tasks, including chat, completion, embeddings, images,
-/// options, parameters, and tools.
+/// of generation tasks, including chat, completion, embeddings, grammars,
+/// images, options, parameters, tools, and usage reporting.
 pub mod chat;
 pub mod completion;
 pub mod embeddings;
+pub mod grammar;
 pub mod images;
 pub mod parameters;
 pub mod tools;
+pub mod usage;
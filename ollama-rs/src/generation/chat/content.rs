@@ -0,0 +1,87 @@
+use crate::generation::images::Image;
+
+/// The content of a chat message, either plain text, a single image, or a mix of both.
+///
+/// Ollama receives the text of a message and its images separately, so text parts are joined
+/// with new lines into the message content and image parts are attached to the message, in order.
+#[derive(Debug, Clone)]
+pub enum MessageContent {
+    Text(String),
+    Image(Image),
+    Parts(Vec<ContentPart>),
+}
+
+/// A segment of a multi-part message.
+#[derive(Debug, Clone)]
+pub enum ContentPart {
+    Text(String),
+    Image(Image),
+}
+
+impl MessageContent {
+    /// Returns the parts making up the content
+    pub fn into_parts(self) -> Vec<ContentPart> {
+        match self {
+            MessageContent::Text(text) => vec![ContentPart::Text(text)],
+            MessageContent::Image(image) => vec![ContentPart::Image(image)],
+            MessageContent::Parts(parts) => parts,
+        }
+    }
+
+    /// Splits the content into the text and the images sent to Ollama
+    pub fn into_text_and_images(self) -> (String, Vec<Image>) {
+        let mut texts = Vec::new();
+        let mut images = Vec::new();
+
+        for part in self.into_parts() {
+            match part {
+                ContentPart::Text(text) => texts.push(text),
+                ContentPart::Image(image) => images.push(image),
+            }
+        }
+
+        (texts.join("\n"), images)
+    }
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Text(text)
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        MessageContent::Text(text.to_string())
+    }
+}
+
+impl From<Image> for MessageContent {
+    fn from(image: Image) -> Self {
+        MessageContent::Image(image)
+    }
+}
+
+impl From<Vec<ContentPart>> for MessageContent {
+    fn from(parts: Vec<ContentPart>) -> Self {
+        MessageContent::Parts(parts)
+    }
+}
+
+impl From<String> for ContentPart {
+    fn from(text: String) -> Self {
+        ContentPart::Text(text)
+    }
+}
+
+impl From<&str> for ContentPart {
+    fn from(text: &str) -> Self {
+        ContentPart::Text(text.to_string())
+    }
+}
+
+impl From<Image> for ContentPart {
+    fn from(image: Image) -> Self {
+        ContentPart::Image(image)
+    }
+}
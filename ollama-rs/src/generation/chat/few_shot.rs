@@ -0,0 +1,84 @@
+use super::{request::ChatMessageRequest, ChatMessage};
+
+/// Assembles a few-shot prompt: an optional system message, example exchanges, then the actual query.
+///
+/// The system message and the examples form a fixed prefix that is the same for every query.
+/// History layers that trim old messages should keep the first [`FewShot::pinned_len`] messages.
+///
+/// ```
+/// use ollama_rs::generation::chat::few_shot::FewShot;
+///
+/// let few_shot = FewShot::new()
+///     .system("Classify the sentiment of the message as positive or negative.")
+///     .example("I love it!", "positive")
+///     .example("This is terrible.", "negative");
+///
+/// let messages = few_shot.build("What a great day");
+/// assert_eq!(messages.len(), 6);
+/// assert_eq!(few_shot.pinned_len(), 5);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FewShot {
+    system: Option<String>,
+    examples: Vec<(String, String)>,
+}
+
+impl FewShot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The system message placed before the examples
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Adds an example exchange: a user message and the expected assistant answer
+    pub fn example(mut self, user: impl Into<String>, assistant: impl Into<String>) -> Self {
+        self.examples.push((user.into(), assistant.into()));
+        self
+    }
+
+    /// Adds several example exchanges
+    pub fn examples<I, U, A>(mut self, examples: I) -> Self
+    where
+        I: IntoIterator<Item = (U, A)>,
+        U: Into<String>,
+        A: Into<String>,
+    {
+        self.examples
+            .extend(examples.into_iter().map(|(u, a)| (u.into(), a.into())));
+        self
+    }
+
+    /// The number of leading messages that are the same for every query (system message and examples)
+    pub fn pinned_len(&self) -> usize {
+        usize::from(self.system.is_some()) + self.examples.len() * 2
+    }
+
+    /// Returns the fixed prefix: the system message and the examples
+    pub fn pinned_messages(&self) -> Vec<ChatMessage> {
+        let mut messages = Vec::with_capacity(self.pinned_len() + 1);
+        if let Some(system) = &self.system {
+            messages.push(ChatMessage::system(system.clone()));
+        }
+        for (user, assistant) in &self.examples {
+            messages.push(ChatMessage::user(user.clone()));
+            messages.push(ChatMessage::assistant(assistant.clone()));
+        }
+        messages
+    }
+
+    /// Returns the messages of the prompt, ending with the query
+    pub fn build(&self, query: impl Into<String>) -> Vec<ChatMessage> {
+        let mut messages = self.pinned_messages();
+        messages.push(ChatMessage::user(query.into()));
+        messages
+    }
+
+    /// Creates a chat request for the query
+    pub fn request(&self, model_name: String, query: impl Into<String>) -> ChatMessageRequest {
+        ChatMessageRequest::new(model_name, self.build(query))
+    }
+}
@@ -0,0 +1,92 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use crate::{Instant, Ollama};
+
+use super::ChatMessageResponse;
+
+/// The maximum number of responses kept by an [`IdempotencyCache`].
+const DEFAULT_CAPACITY: usize = 256;
+
+/// A cache of recently completed chat responses, keyed by request fingerprint.
+///
+/// When enabled with [`Ollama::set_idempotency_ttl`], sending a request that carries a fingerprint
+/// (see [`ChatMessageRequest::idempotency_key`](super::request::ChatMessageRequest::idempotency_key))
+/// returns the cached response if the same fingerprint completed less than `ttl` ago,
+/// instead of generating a new one.
+#[derive(Debug)]
+pub struct IdempotencyCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, (Instant, ChatMessageResponse)>>,
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_capacity(ttl, DEFAULT_CAPACITY)
+    }
+
+    /// Creates a cache keeping at most `capacity` responses, the oldest ones are evicted first
+    pub fn with_capacity(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the response cached for the fingerprint, if it hasn't expired
+    pub fn get(&self, fingerprint: &str) -> Option<ChatMessageResponse> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(fingerprint)
+            .filter(|(completed_at, _)| completed_at.elapsed() < self.ttl)
+            .map(|(_, response)| response.clone())
+    }
+
+    /// Caches the response completed for the fingerprint
+    pub fn insert(&self, fingerprint: String, response: ChatMessageResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (completed_at, _)| completed_at.elapsed() < self.ttl);
+
+        while entries.len() >= self.capacity.max(1) {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (completed_at, _))| *completed_at)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+
+        entries.insert(fingerprint, (Instant::now(), response));
+    }
+
+    /// Removes every cached response
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Computes a stable 64-bit FNV-1a hash of the given bytes, rendered as hexadecimal.
+pub(crate) fn fingerprint_bytes(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+impl Ollama {
+    /// Enables the client-side idempotency layer for chat requests that carry a fingerprint.
+    /// Completed responses are reused for `ttl`. `None` disables the layer.
+    pub fn set_idempotency_ttl(&mut self, ttl: Option<Duration>) {
+        self.idempotency_cache = ttl.map(|ttl| std::sync::Arc::new(IdempotencyCache::new(ttl)));
+    }
+
+    /// Returns the idempotency cache, if the layer is enabled
+    pub fn idempotency_cache(&self) -> Option<&IdempotencyCache> {
+        self.idempotency_cache.as_deref()
+    }
+}
@@ -1,7 +1,15 @@
-use serde::{Deserialize, Serialize};
-
-use super::{images::Image, tools::ToolCall};
-use crate::{error::OllamaError, history::ChatHistory, Ollama};
+use std::{path::Path, time::Duration};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    images::{Image, ImageHints},
+    parameters::{FormatType, JsonSchema, JsonStructure},
+    tools::ToolCall,
+    usage::Usage,
+};
+use crate::{error::OllamaError, history::ChatHistory, transport::EndpointClass, Ollama};
+use content::{ContentPart, MessageContent};
 use request::ChatMessageRequest;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
@@ -9,18 +17,26 @@ use request::ChatMessageRequest;
 use async_stream::stream;
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 use tokio_stream::StreamExt;
 
+pub mod content;
+pub mod few_shot;
+pub mod idempotency;
 pub mod request;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 /// A stream of `ChatMessageResponse` objects
-pub type ChatMessageResponseStream =
-    std::pin::Pin<Box<dyn tokio_stream::Stream<Item = Result<ChatMessageResponse, ()>> + Send>>;
+pub type ChatMessageResponseStream = BorrowedChatMessageResponseStream<'static>;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+/// A stream of `ChatMessageResponse` objects that borrows from its caller, e.g. a chat history
+pub type BorrowedChatMessageResponseStream<'a> =
+    crate::BoxStream<'a, crate::error::Result<ChatMessageResponse>>;
 
 impl Ollama {
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
@@ -33,76 +49,55 @@ impl Ollama {
     ) -> crate::error::Result<ChatMessageResponseStream> {
         let mut request = request;
         request.stream = true;
+        self.apply_defaults(
+            &mut request.model_name,
+            &mut request.options,
+            &mut request.keep_alive,
+        );
+        request.model_name = self.resolve_model_alias(request.model_name);
+
+        if request.think.is_some() {
+            self.require_version(crate::version::THINKING_VERSION, "Thinking")
+                .await?;
+        }
 
-        let url = format!("{}api/chat", self.url_str());
+        let url = self.endpoint_url("api/chat");
         let builder = self.reqwest_client.post(url);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send_stream(builder.json(&request), EndpointClass::Generation)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            return Err(OllamaError::from_response(res).await);
         }
 
+        let telemetry = crate::telemetry::RequestTelemetry::of(&res);
+        let lines =
+            crate::timeout::response_lines(res, self.stream_idle_timeout, self.stream_buffer);
         let s = stream! {
-            let mut buffer = String::new();
-
-            let mut stream = res.bytes_stream();
-            while let Some(chunk_result) = stream.next().await {
-                match chunk_result {
-                    Ok(chunk) => {
-                        // Convert bytes to string and append to buffer
-                        if let Ok(chunk_str) = String::from_utf8(chunk.to_vec()) {
-                            buffer.push_str(&chunk_str);
-
-                            // Process complete lines in the buffer
-                            let mut lines_to_process = Vec::new();
-                            let mut start_pos = 0;
-
-                            // Find all complete lines in the buffer and collect them
-                            while let Some(pos) = buffer[start_pos..].find('\n') {
-                                let actual_pos = start_pos + pos;
-                                let line = buffer[start_pos..actual_pos].trim().to_string();
-                                if !line.is_empty() {
-                                    lines_to_process.push(line);
-                                }
-                                start_pos = actual_pos + 1;
-                            }
-
-                            // If we processed any lines, truncate the buffer
-                            if start_pos > 0 {
-                                buffer = buffer[start_pos..].to_string();
-                            }
-
-                            // Process all collected lines
-                            for line in lines_to_process {
-                                // Parse the JSON line
-                                match serde_json::from_str::<ChatMessageResponse>(&line) {
-                                    Ok(response) => yield Ok(response),
-                                    Err(e) => {
-                                        eprintln!("Failed to deserialize response: {e}");
-                                        // Continue processing other lines even if one fails
-                                    }
-                                }
-                            }
-                        }
-                    }
+            let mut lines = lines;
+            while let Some(chunk_lines) = lines.next().await {
+                let chunk_lines = match chunk_lines {
+                    Ok(chunk_lines) => chunk_lines,
                     Err(e) => {
-                        eprintln!("Failed to read response: {e}");
-                        yield Err(());
+                        yield Err(e);
                         break;
                     }
-                }
-            }
+                };
 
-            // Process any remaining data in the buffer
-            if !buffer.is_empty() {
-                if let Ok(response) = serde_json::from_str::<ChatMessageResponse>(&buffer) {
-                    yield Ok(response);
+                for line in chunk_lines {
+                    match serde_json::from_slice::<ChatMessageResponse>(&line) {
+                        Ok(response) => {
+                            telemetry.record_usage(response.usage());
+                            yield Ok(response);
+                        }
+                        // The following lines may still be read
+                        Err(e) => yield Err(OllamaError::from_stream_line(&line, e)),
+                    }
                 }
             }
         };
@@ -110,6 +105,34 @@ impl Ollama {
         Ok(Box::pin(s))
     }
 
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// Chat message generation with streaming, calling `on_delta` with each piece of content as it arrives.
+    /// Returns the final `ChatMessageResponse` with the whole content, see [`ChatMessageAccumulator`]
+    pub async fn send_chat_messages_with_callback<F>(
+        &self,
+        request: ChatMessageRequest,
+        mut on_delta: F,
+    ) -> crate::error::Result<ChatMessageResponse>
+    where
+        F: FnMut(&str),
+    {
+        let mut stream = self.send_chat_messages_stream(request).await?;
+        let mut accumulator = ChatMessageAccumulator::new();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if !chunk.message.content.is_empty() {
+                on_delta(&chunk.message.content);
+            }
+            accumulator.push(chunk);
+        }
+
+        accumulator
+            .finish()
+            .ok_or_else(|| OllamaError::Other("The response stream was empty".to_string()))
+    }
+
     /// Chat message generation.
     /// Returns a `ChatMessageResponse` object
     pub async fn send_chat_messages(
@@ -118,23 +141,78 @@ impl Ollama {
     ) -> crate::error::Result<ChatMessageResponse> {
         let mut request = request;
         request.stream = false;
+        self.apply_defaults(
+            &mut request.model_name,
+            &mut request.options,
+            &mut request.keep_alive,
+        );
+        request.model_name = self.resolve_model_alias(request.model_name);
+
+        let fingerprint = request.idempotency_key.clone();
+        if let (Some(cache), Some(fingerprint)) = (&self.idempotency_cache, &fingerprint) {
+            if let Some(res) = cache.get(fingerprint) {
+                return Ok(res);
+            }
+        }
+
+        if request.think.is_some() {
+            self.require_version(crate::version::THINKING_VERSION, "Thinking")
+                .await?;
+        }
 
-        let url = format!("{}api/chat", self.url_str());
+        let res = self
+            .with_auto_pull(&request.model_name, || {
+                self.send_chat_messages_inner(&request)
+            })
+            .await?;
+
+        if let (Some(cache), Some(fingerprint)) = (&self.idempotency_cache, fingerprint) {
+            cache.insert(fingerprint, res.clone());
+        }
+
+        Ok(res)
+    }
+
+    async fn send_chat_messages_inner(
+        &self,
+        request: &ChatMessageRequest,
+    ) -> crate::error::Result<ChatMessageResponse> {
+        let url = self.endpoint_url("api/chat");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(request), EndpointClass::Generation)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            return Err(OllamaError::from_response(res).await);
         }
 
+        let telemetry = crate::telemetry::RequestTelemetry::of(&res);
         let bytes = res.bytes().await?;
         let res = serde_json::from_slice::<ChatMessageResponse>(&bytes)?;
+        telemetry.record_usage(res.usage());
+
+        Ok(res)
+    }
+
+    /// Chat message generation with a structured output.
+    /// The format of the request is set to the JSON schema of `T` and the content of the response is deserialized into `T`.
+    /// Requires Ollama 0.5.0 or greater.
+    pub async fn send_chat_messages_structured<T: JsonSchema + DeserializeOwned>(
+        &self,
+        request: ChatMessageRequest,
+    ) -> crate::error::Result<T> {
+        let request = request.format(FormatType::StructuredJson(Box::new(
+            JsonStructure::new::<T>(),
+        )));
+
+        let res = self.send_chat_messages(request).await?;
+        let res = serde_json::from_str::<T>(&res.message.content)?;
 
         Ok(res)
     }
@@ -143,38 +221,53 @@ impl Ollama {
 impl Ollama {
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
     #[cfg(feature = "stream")]
-    pub async fn send_chat_messages_with_history_stream<C: ChatHistory + Send + 'static>(
+    /// Chat message generation with streaming, using and updating the given history.
+    /// The messages of the request are appended to the history, and the response is appended once the stream is done,
+    /// with the tool calls and images of all its chunks but without its thinking, unless
+    /// [`ChatMessageRequest::keep_thinking_in_history`] is set.
+    /// The returned stream borrows the history until it is dropped.
+    pub async fn send_chat_messages_with_history_stream<'a, C: ChatHistory + Send>(
         &self,
-        history: Arc<Mutex<C>>,
+        history: &'a mut C,
         mut request: ChatMessageRequest,
-    ) -> crate::error::Result<ChatMessageResponseStream> {
+    ) -> crate::error::Result<BorrowedChatMessageResponseStream<'a>> {
         use async_stream::stream;
         use tokio_stream::StreamExt;
 
         // The request is modified to include the current chat messages
-        {
-            let mut hist = history.lock().unwrap();
-            for m in request.messages {
-                hist.push(m);
-            }
+        for m in request.messages {
+            history.push(m);
         }
 
-        request.messages = history.lock().unwrap().messages().to_vec();
+        request.messages = history.messages().to_vec();
         request.stream = true;
+        let keep_thinking = request.keep_thinking;
 
         let mut resp_stream: ChatMessageResponseStream =
             self.send_chat_messages_stream(request.clone()).await?;
 
         let s = stream! {
-            let mut result = String::new();
+            let mut accumulator = ChatMessageAccumulator::new();
 
-            while let Some(item) = resp_stream.try_next().await.unwrap() {
-                let msg_part = item.clone().message.content;
+            while let Some(item) = resp_stream.next().await {
+                let item = match item {
+                    Ok(item) => item,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+                accumulator.push(item.clone());
 
                 if item.done {
-                    history.lock().unwrap().push(ChatMessage::assistant(result.clone()));
-                } else {
-                    result.push_str(&msg_part);
+                    // The whole message, with the tool calls and images of every chunk
+                    if let Some(response) = std::mem::take(&mut accumulator).finish() {
+                        let mut message = response.message;
+                        message.thinking = message
+                            .thinking
+                            .filter(|thinking| keep_thinking && !thinking.is_empty());
+                        history.push(message);
+                    }
                 }
 
                 yield Ok(item);
@@ -186,6 +279,7 @@ impl Ollama {
 
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
     #[cfg(feature = "stream")]
+    /// Same as [`Ollama::send_chat_messages_with_history_stream`], for a history shared behind a tokio mutex.
     pub async fn send_chat_messages_with_history_stream_tokio<C: ChatHistory + Send + 'static>(
         &self,
         history: Arc<tokio::sync::Mutex<C>>,
@@ -204,20 +298,33 @@ impl Ollama {
 
         request.messages = history.lock().await.messages().to_vec();
         request.stream = true;
+        let keep_thinking = request.keep_thinking;
 
         let mut resp_stream: ChatMessageResponseStream =
             self.send_chat_messages_stream(request.clone()).await?;
 
         let s = stream! {
-            let mut result = String::new();
+            let mut accumulator = ChatMessageAccumulator::new();
 
-            while let Some(item) = resp_stream.try_next().await.unwrap() {
-                let msg_part = item.clone().message.content;
+            while let Some(item) = resp_stream.next().await {
+                let item = match item {
+                    Ok(item) => item,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+                accumulator.push(item.clone());
 
                 if item.done {
-                    history.lock().await.push(ChatMessage::assistant(result.clone()));
-                } else {
-                    result.push_str(&msg_part);
+                    // The whole message, with the tool calls and images of every chunk
+                    if let Some(response) = std::mem::take(&mut accumulator).finish() {
+                        let mut message = response.message;
+                        message.thinking = message
+                            .thinking
+                            .filter(|thinking| keep_thinking && !thinking.is_empty());
+                        history.lock().await.push(message);
+                    }
                 }
 
                 yield Ok(item);
@@ -227,10 +334,11 @@ impl Ollama {
         Ok(Box::pin(s))
     }
 
-    /// Chat message generation
+    /// Chat message generation, using and updating the given history.
+    /// The response is appended to the history without its thinking, unless [`ChatMessageRequest::keep_thinking_in_history`] is set.
     /// Returns a `ChatMessageResponse` object
     pub async fn send_chat_messages_with_history<C: ChatHistory>(
-        &mut self,
+        &self,
         history: &mut C,
         mut request: ChatMessageRequest,
     ) -> crate::error::Result<ChatMessageResponse> {
@@ -244,7 +352,11 @@ impl Ollama {
         let result = self.send_chat_messages(request.clone()).await;
 
         if let Ok(result) = result {
-            history.push(result.message.clone());
+            let mut message = result.message.clone();
+            if !request.keep_thinking {
+                message.thinking = None;
+            }
+            history.push(message);
 
             return Ok(result);
         }
@@ -267,6 +379,86 @@ pub struct ChatMessageResponse {
     pub final_data: Option<ChatMessageFinalResponseData>,
 }
 
+/// Folds the chunks of a streamed chat response into a single `ChatMessageResponse`.
+///
+/// Content and thinking deltas are concatenated and tool calls are merged. The model, creation time,
+/// `done` flag and final data are taken from the last chunk.
+#[derive(Debug, Clone, Default)]
+pub struct ChatMessageAccumulator {
+    response: Option<ChatMessageResponse>,
+}
+
+impl ChatMessageAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a chunk to the accumulated response
+    pub fn push(&mut self, chunk: ChatMessageResponse) {
+        let Some(response) = self.response.as_mut() else {
+            self.response = Some(chunk);
+            return;
+        };
+
+        let message = &mut response.message;
+        message.content.push_str(&chunk.message.content);
+        message.tool_calls.extend(chunk.message.tool_calls);
+        if let Some(thinking) = chunk.message.thinking {
+            message
+                .thinking
+                .get_or_insert_with(String::new)
+                .push_str(&thinking);
+        }
+        if let Some(images) = chunk.message.images {
+            message.images.get_or_insert_with(Vec::new).extend(images);
+        }
+
+        response.model = chunk.model;
+        response.created_at = chunk.created_at;
+        response.done = chunk.done;
+        if chunk.final_data.is_some() {
+            response.final_data = chunk.final_data;
+        }
+    }
+
+    /// Returns the accumulated response, or `None` if no chunk was pushed
+    pub fn finish(self) -> Option<ChatMessageResponse> {
+        self.response
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+/// Extension methods for streams of `ChatMessageResponse` chunks.
+pub trait ChatMessageResponseStreamExt {
+    /// Consumes the stream and returns a single `ChatMessageResponse` with the concatenated content,
+    /// the merged tool calls and the final data of the last chunk. See [`ChatMessageAccumulator`].
+    fn accumulate(
+        self,
+    ) -> impl std::future::Future<Output = crate::error::Result<ChatMessageResponse>> + crate::MaybeSend;
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+impl<S> ChatMessageResponseStreamExt for S
+where
+    S: tokio_stream::Stream<Item = crate::error::Result<ChatMessageResponse>>
+        + Unpin
+        + crate::MaybeSend,
+{
+    async fn accumulate(mut self) -> crate::error::Result<ChatMessageResponse> {
+        let mut accumulator = ChatMessageAccumulator::new();
+
+        while let Some(chunk) = self.next().await {
+            accumulator.push(chunk?);
+        }
+
+        accumulator
+            .finish()
+            .ok_or_else(|| OllamaError::Other("The response stream was empty".to_string()))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessageFinalResponseData {
     /// Time spent generating the response
@@ -283,6 +475,29 @@ pub struct ChatMessageFinalResponseData {
     pub eval_duration: u64,
 }
 
+impl ChatMessageResponse {
+    /// Returns the timings and token counts of the generation, only present on the final response
+    pub fn usage(&self) -> Option<Usage> {
+        self.final_data
+            .as_ref()
+            .map(ChatMessageFinalResponseData::usage)
+    }
+}
+
+impl ChatMessageFinalResponseData {
+    /// Returns the timings as `Duration`s along with the token counts
+    pub fn usage(&self) -> Usage {
+        Usage {
+            total_duration: Duration::from_nanos(self.total_duration),
+            load_duration: Duration::from_nanos(self.load_duration),
+            prompt_eval_count: self.prompt_eval_count,
+            prompt_eval_duration: Duration::from_nanos(self.prompt_eval_duration),
+            eval_count: self.eval_count,
+            eval_duration: Duration::from_nanos(self.eval_duration),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: MessageRole,
@@ -291,7 +506,12 @@ pub struct ChatMessage {
     pub tool_calls: Vec<ToolCall>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub images: Option<Vec<Image>>,
+    /// The reasoning of the model, returned when `ChatMessageRequest::think` is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub thinking: Option<String>,
+    /// The ID of the tool call a tool message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
 }
 
 impl ChatMessage {
@@ -302,9 +522,36 @@ impl ChatMessage {
             tool_calls: vec![],
             images: None,
             thinking: None,
+            tool_call_id: None,
         }
     }
 
+    /// Creates a message from text, images or a mix of both.
+    /// Text parts are joined with new lines and images are attached in order, see [`MessageContent`].
+    pub fn from_content(role: MessageRole, content: impl Into<MessageContent>) -> Self {
+        let (text, images) = content.into().into_text_and_images();
+
+        let message = Self::new(role, text);
+        if images.is_empty() {
+            message
+        } else {
+            message.with_images(images)
+        }
+    }
+
+    /// Returns the content of the message as parts: the text, if any, followed by the images
+    pub fn content_parts(&self) -> MessageContent {
+        let mut parts = Vec::new();
+        if !self.content.is_empty() {
+            parts.push(ContentPart::Text(self.content.clone()));
+        }
+        for image in self.images.iter().flatten() {
+            parts.push(ContentPart::Image(image.clone()));
+        }
+
+        MessageContent::Parts(parts)
+    }
+
     pub fn user(content: String) -> Self {
         Self::new(MessageRole::User, content)
     }
@@ -313,6 +560,13 @@ impl ChatMessage {
         Self::new(MessageRole::Assistant, content)
     }
 
+    /// Creates a partial assistant message for the model to continue, useful to force a response prefix such as "```json".
+    /// It must be the last message of the request, the response will only contain the continuation, not the prefix.
+    /// Requires a server that continues trailing assistant messages (Ollama 0.4.7 or greater).
+    pub fn assistant_prefill(content: String) -> Self {
+        Self::assistant(content)
+    }
+
     pub fn system(content: String) -> Self {
         Self::new(MessageRole::System, content)
     }
@@ -321,6 +575,12 @@ impl ChatMessage {
         Self::new(MessageRole::Tool, content)
     }
 
+    /// Sets the ID of the tool call this message answers
+    pub fn with_tool_call_id(mut self, tool_call_id: impl Into<String>) -> Self {
+        self.tool_call_id = Some(tool_call_id.into());
+        self
+    }
+
     pub fn with_images(mut self, images: Vec<Image>) -> Self {
         self.images = Some(images);
         self
@@ -334,6 +594,44 @@ impl ChatMessage {
         }
         self
     }
+
+    /// Adds several images at once, keeping the ones already attached
+    pub fn add_images(mut self, images: impl IntoIterator<Item = Image>) -> Self {
+        self.images.get_or_insert_with(Vec::new).extend(images);
+        self
+    }
+
+    /// Adds an image along with client-side hints, see [`ImageHints`]
+    pub fn add_image_with_hints(self, image: Image, hints: ImageHints) -> Self {
+        self.add_image(image.with_hints(hints))
+    }
+
+    /// Creates a user message with the images read from the given files.
+    /// Returns an error if a file can't be read or is not a supported image format.
+    pub fn user_with_images<I, P>(content: String, paths: I) -> crate::error::Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let images = paths
+            .into_iter()
+            .map(Image::from_path)
+            .collect::<crate::error::Result<Vec<_>>>()?;
+
+        Ok(Self::user(content).with_images(images))
+    }
+
+    /// Adds an image read from the given file.
+    /// Returns an error if the file can't be read or is not a supported image format.
+    pub fn with_image_path(self, path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        Ok(self.add_image(Image::from_path(path)?))
+    }
+
+    /// Adds an image from its raw bytes.
+    /// Returns an error if the bytes are not a supported image format.
+    pub fn with_image_bytes(self, bytes: &[u8]) -> crate::error::Result<Self> {
+        Ok(self.add_image(Image::from_bytes(bytes)?))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -346,4 +644,32 @@ pub enum MessageRole {
     System,
     #[serde(rename = "tool")]
     Tool,
+    /// Any other role, for templates that define roles beyond the standard ones
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl MessageRole {
+    /// Creates a role, mapping the standard role names to their variants
+    pub fn custom(role: impl Into<String>) -> Self {
+        let role = role.into();
+        match role.as_str() {
+            "user" => Self::User,
+            "assistant" => Self::Assistant,
+            "system" => Self::System,
+            "tool" => Self::Tool,
+            _ => Self::Custom(role),
+        }
+    }
+
+    /// Returns the name of the role as sent to Ollama
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::User => "user",
+            Self::Assistant => "assistant",
+            Self::System => "system",
+            Self::Tool => "tool",
+            Self::Custom(role) => role,
+        }
+    }
 }
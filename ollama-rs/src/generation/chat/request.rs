@@ -1,22 +1,22 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     generation::{
-        parameters::{FormatType, KeepAlive},
+        parameters::{FormatType, KeepAlive, Think},
         tools::ToolInfo,
     },
     models::ModelOptions,
 };
 
-use super::ChatMessage;
+use super::{idempotency::fingerprint_bytes, ChatMessage};
 
 /// A chat message request to Ollama.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessageRequest {
     #[serde(rename = "model")]
     pub model_name: String,
     pub messages: Vec<ChatMessage>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<ToolInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<ModelOptions>,
@@ -27,8 +27,18 @@ pub struct ChatMessageRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keep_alive: Option<KeepAlive>,
     /// Must be false if tools are provided
+    #[serde(default)]
     pub(crate) stream: bool,
-    pub think: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub think: Option<Think>,
+    /// Client-side fingerprint used by the idempotency layer, never sent to Ollama.
+    /// Not serialized, so it only lives for the calls made with this value of the request.
+    #[serde(skip)]
+    pub(crate) idempotency_key: Option<String>,
+    /// Whether the history methods keep the thinking of the response in the history, never sent to Ollama.
+    /// Not serialized, so it only lives for the calls made with this value of the request.
+    #[serde(skip)]
+    pub(crate) keep_thinking: bool,
 }
 
 impl ChatMessageRequest {
@@ -44,7 +54,53 @@ impl ChatMessageRequest {
             stream: false,
             tools: vec![],
             think: None,
+            idempotency_key: None,
+            keep_thinking: false,
+        }
+    }
+
+    /// Ends the messages with a partial assistant message that the model will continue.
+    /// See [`ChatMessage::assistant_prefill`].
+    pub fn prefill(mut self, content: String) -> Self {
+        self.messages.push(ChatMessage::assistant_prefill(content));
+        self
+    }
+
+    /// Keeps the thinking of the response in the history when sent with the history methods, such as
+    /// [`Ollama::send_chat_messages_with_history`](crate::Ollama::send_chat_messages_with_history) (Default: false).
+    /// The thinking is otherwise dropped, so that it isn't sent back to the model with the next messages.
+    ///
+    /// This is a client-side setting: it isn't serialized, and a request deserialized from JSON doesn't keep it.
+    pub fn keep_thinking_in_history(mut self, keep_thinking: bool) -> Self {
+        self.keep_thinking = keep_thinking;
+        self
+    }
+
+    /// Attaches a fingerprint to the request. When the idempotency layer is enabled (see [`Ollama::set_idempotency_ttl`](crate::Ollama::set_idempotency_ttl)),
+    /// sending a request with the fingerprint of a recently completed one returns the cached response.
+    ///
+    /// The fingerprint is client-side: it isn't sent to Ollama nor serialized, and a request deserialized from JSON,
+    /// such as one read back from a queue, doesn't carry it. Attach it again before sending such a request.
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Attaches a fingerprint computed from the content of the request, see [`ChatMessageRequest::idempotency_key`].
+    /// Should be called once all the other fields are set.
+    pub fn with_fingerprint(self) -> Self {
+        let fingerprint = self.fingerprint();
+        self.idempotency_key(fingerprint)
+    }
+
+    /// Returns the fingerprint attached to the request, or one computed from its content
+    pub fn fingerprint(&self) -> String {
+        if let Some(key) = &self.idempotency_key {
+            return key.clone();
         }
+
+        let bytes = serde_json::to_vec(self).unwrap_or_default();
+        fingerprint_bytes(&bytes)
     }
 
     /// Additional model parameters listed in the documentation for the Modelfile
@@ -77,9 +133,10 @@ impl ChatMessageRequest {
         self
     }
 
-    /// Used to control whether thinking/reasoning models will think before responding
-    pub fn think(mut self, think: bool) -> Self {
-        self.think = Some(think);
+    /// Used to control whether thinking/reasoning models will think before responding.
+    /// Accepts a `bool` or a thinking level for models that support it.
+    pub fn think(mut self, think: impl Into<Think>) -> Self {
+        self.think = Some(think.into());
         self
     }
 }
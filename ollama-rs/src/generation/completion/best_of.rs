@@ -0,0 +1,121 @@
+use crate::{
+    error::OllamaError,
+    generation::embeddings::{math::cosine_similarity, request::GenerateEmbeddingsRequest},
+    Ollama,
+};
+
+use super::{request::GenerationRequest, GenerationResponse};
+
+/// How the candidates of a best-of-N generation are ranked. The candidate with the highest score wins.
+pub enum BestOfScorer {
+    /// Scores each candidate with a user-supplied function
+    Custom(Box<dyn Fn(&GenerationResponse) -> f32 + Send + Sync>),
+    /// Scores each candidate by the cosine similarity between its embedding and the embedding of the prompt,
+    /// using the given embedding model
+    EmbeddingSimilarity(String),
+}
+
+impl BestOfScorer {
+    /// Scores each candidate with the given function
+    pub fn custom(scorer: impl Fn(&GenerationResponse) -> f32 + Send + Sync + 'static) -> Self {
+        Self::Custom(Box::new(scorer))
+    }
+
+    /// Scores each candidate by its embedding similarity to the prompt
+    pub fn embedding_similarity(model_name: String) -> Self {
+        Self::EmbeddingSimilarity(model_name)
+    }
+}
+
+/// The result of a best-of-N generation.
+#[derive(Debug, Clone)]
+pub struct BestOfResponse {
+    /// Every generated candidate, in seed order
+    pub candidates: Vec<GenerationResponse>,
+    /// The score of each candidate, in the same order as `candidates`
+    pub scores: Vec<f32>,
+    /// The index of the winning candidate
+    pub best_index: usize,
+}
+
+impl BestOfResponse {
+    /// Returns the winning candidate
+    pub fn best(&self) -> &GenerationResponse {
+        &self.candidates[self.best_index]
+    }
+}
+
+impl Ollama {
+    /// Runs `n` completions of the same request concurrently, each one with a different seed, and picks the best one using `scorer`.
+    /// The seeds start at the seed set in the request options, or 0 if there is none.
+    /// Returns all the candidates along with the winner.
+    pub async fn generate_best_of(
+        &self,
+        request: GenerationRequest<'static>,
+        n: usize,
+        scorer: BestOfScorer,
+    ) -> crate::error::Result<BestOfResponse> {
+        if n == 0 {
+            return Err(OllamaError::Other(
+                "Best-of generation requires at least one candidate".to_string(),
+            ));
+        }
+
+        let options = request.options.clone().unwrap_or_default();
+        let base_seed = options.seed.unwrap_or(0);
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..n {
+            let ollama = self.clone();
+            let request = request
+                .clone()
+                .options(options.clone().seed(base_seed.wrapping_add(i as i32)));
+
+            tasks.spawn(async move { (i, ollama.generate(request).await) });
+        }
+
+        let mut candidates = vec![None; n];
+        while let Some(res) = tasks.join_next().await {
+            let (i, res) =
+                res.map_err(|e| OllamaError::Other(format!("Generation task failed: {e}")))?;
+            candidates[i] = Some(res?);
+        }
+        let candidates: Vec<GenerationResponse> = candidates.into_iter().flatten().collect();
+
+        let scores: Vec<f32> = match scorer {
+            BestOfScorer::Custom(scorer) => candidates.iter().map(scorer).collect(),
+            BestOfScorer::EmbeddingSimilarity(model_name) => {
+                let mut input = vec![request.prompt.to_string()];
+                input.extend(candidates.iter().map(|c| c.response.clone()));
+
+                let res = self
+                    .generate_embeddings(GenerateEmbeddingsRequest::new(model_name, input.into()))
+                    .await?;
+
+                let Some((prompt, responses)) = res.embeddings.split_first() else {
+                    return Err(OllamaError::Other(
+                        "Ollama returned no embeddings".to_string(),
+                    ));
+                };
+
+                responses
+                    .iter()
+                    .map(|e| cosine_similarity(prompt, e))
+                    .collect()
+            }
+        };
+
+        let best_index = scores
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap_or_default();
+
+        Ok(BestOfResponse {
+            candidates,
+            scores,
+            best_index,
+        })
+    }
+}
@@ -1,21 +1,29 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, generation::usage::Usage, transport::EndpointClass, Ollama};
 
 use request::GenerationRequest;
 
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub mod best_of;
 pub mod request;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
 /// A stream of `GenerationResponse` objects
-pub type GenerationResponseStream = std::pin::Pin<
-    Box<
-        dyn tokio_stream::Stream<Item = crate::error::Result<GenerationResponseStreamChunk>> + Send,
-    >,
->;
+pub type GenerationResponseStream =
+    crate::BoxStream<'static, crate::error::Result<GenerationResponseStreamChunk>>;
 pub type GenerationResponseStreamChunk = Vec<GenerationResponse>;
 
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+/// A merged stream of `(prompt_index, chunk)` items produced by several generations at once
+pub type MultiplexedGenerationStream =
+    crate::BoxStream<'static, (usize, crate::error::Result<GenerationResponseStreamChunk>)>;
+
 impl Ollama {
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
     #[cfg(feature = "stream")]
@@ -31,33 +39,100 @@ impl Ollama {
 
         let mut request = request;
         request.stream = true;
+        self.apply_defaults(
+            &mut request.model_name,
+            &mut request.options,
+            &mut request.keep_alive,
+        );
+        request.model_name = self.resolve_model_alias(request.model_name);
+
+        if request.think.is_some() {
+            self.require_version(crate::version::THINKING_VERSION, "Thinking")
+                .await?;
+        }
 
-        let url = format!("{}api/generate", self.url_str());
+        let url = self.endpoint_url("api/generate");
         let builder = self.reqwest_client.post(url);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send_stream(builder.json(&request), EndpointClass::Generation)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            return Err(OllamaError::from_response(res).await);
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
-            Ok(bytes) => {
-                let res = serde_json::Deserializer::from_slice(&bytes).into_iter();
-                let res = res
-                    .filter_map(Result::ok) // Filter out the errors
-                    .collect::<Vec<GenerationResponse>>();
-                Ok(res)
+        let telemetry = crate::telemetry::RequestTelemetry::of(&res);
+        let lines =
+            crate::timeout::response_lines(res, self.stream_idle_timeout, self.stream_buffer);
+        let stream = lines.map(move |res| {
+            let res = res?
+                .iter()
+                .map(|line| {
+                    serde_json::from_slice::<GenerationResponse>(line)
+                        .map_err(|e| OllamaError::from_stream_line(line, e))
+                })
+                .collect::<crate::error::Result<Vec<_>>>()?;
+            for res in &res {
+                telemetry.record_usage(res.usage());
             }
-            Err(e) => Err(OllamaError::Other(format!("Failed to read response: {e}"))),
-        }));
+            Ok(res)
+        });
 
-        Ok(std::pin::Pin::from(stream))
+        Ok(Box::pin(stream))
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// Completion generation with streaming for several prompts at once.
+    /// Returns a single stream of `(prompt_index, chunk)` items, where `prompt_index` is the position of the request in `requests`.
+    /// At most `max_concurrency` generations are in flight at any time, the next request is started as soon as one finishes.
+    pub fn generate_stream_multiplexed(
+        &self,
+        requests: Vec<GenerationRequest<'static>>,
+        max_concurrency: usize,
+    ) -> MultiplexedGenerationStream {
+        use async_stream::stream;
+        use tokio_stream::{StreamExt, StreamMap};
+
+        let ollama = self.clone();
+        let max_concurrency = max_concurrency.max(1);
+
+        let s = stream! {
+            let mut pending = requests.into_iter().enumerate();
+            let mut active = StreamMap::new();
+
+            loop {
+                // Fill the free slots with the next pending requests
+                while active.len() < max_concurrency {
+                    let Some((index, request)) = pending.next() else {
+                        break;
+                    };
+
+                    match ollama.generate_stream(request).await {
+                        Ok(stream) => {
+                            active.insert(index, stream);
+                        }
+                        Err(e) => yield (index, Err(e)),
+                    }
+                }
+
+                // The pending requests are exhausted once no slot could be filled
+                if active.is_empty() {
+                    break;
+                }
+
+                // `None` when the last active generation finished, the next ones are started above
+                if let Some(item) = active.next().await {
+                    yield item;
+                }
+            }
+        };
+
+        Box::pin(s)
     }
 
     /// Completion generation with a single response.
@@ -68,23 +143,45 @@ impl Ollama {
     ) -> crate::error::Result<GenerationResponse> {
         let mut request = request;
         request.stream = false;
+        self.apply_defaults(
+            &mut request.model_name,
+            &mut request.options,
+            &mut request.keep_alive,
+        );
+        request.model_name = self.resolve_model_alias(request.model_name);
+
+        if request.think.is_some() {
+            self.require_version(crate::version::THINKING_VERSION, "Thinking")
+                .await?;
+        }
+
+        self.with_auto_pull(&request.model_name, || self.generate_inner(&request))
+            .await
+    }
 
-        let url = format!("{}api/generate", self.url_str());
+    async fn generate_inner(
+        &self,
+        request: &GenerationRequest<'_>,
+    ) -> crate::error::Result<GenerationResponse> {
+        let url = self.endpoint_url("api/generate");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(request), EndpointClass::Generation)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            return Err(OllamaError::from_response(res).await);
         }
 
+        let telemetry = crate::telemetry::RequestTelemetry::of(&res);
         let res = res.bytes().await?;
         let res = serde_json::from_slice::<GenerationResponse>(&res)?;
+        telemetry.record_usage(res.usage());
 
         Ok(res)
     }
@@ -118,6 +215,24 @@ pub struct GenerationResponse {
     pub eval_count: Option<u64>,
     /// Time spent in nanoseconds generating the response
     pub eval_duration: Option<u64>,
-    /// Contains the text that was inside thinking tags in the original model output when ChatMessageRequest.Think is enabled.
+    /// Contains the text that was inside thinking tags in the original model output when GenerationRequest.think is enabled.
     pub thinking: Option<String>,
 }
+
+impl GenerationResponse {
+    /// Returns the timings and token counts of the generation, only present on the final response
+    pub fn usage(&self) -> Option<Usage> {
+        let total_duration = self.total_duration?;
+
+        Some(Usage {
+            total_duration: Duration::from_nanos(total_duration),
+            load_duration: Duration::from_nanos(self.load_duration.unwrap_or_default()),
+            prompt_eval_count: self.prompt_eval_count.unwrap_or_default(),
+            prompt_eval_duration: Duration::from_nanos(
+                self.prompt_eval_duration.unwrap_or_default(),
+            ),
+            eval_count: self.eval_count.unwrap_or_default(),
+            eval_duration: Duration::from_nanos(self.eval_duration.unwrap_or_default()),
+        })
+    }
+}
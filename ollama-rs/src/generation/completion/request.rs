@@ -1,11 +1,11 @@
 use std::borrow::Cow;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     generation::{
         images::Image,
-        parameters::{FormatType, KeepAlive},
+        parameters::{FormatType, KeepAlive, Think},
     },
     models::ModelOptions,
 };
@@ -13,13 +13,14 @@ use crate::{
 use super::GenerationContext;
 
 /// A generation request to Ollama.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationRequest<'a> {
     #[serde(rename = "model")]
     pub model_name: String,
     pub prompt: Cow<'a, str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suffix: Option<Cow<'a, str>>,
+    #[serde(default)]
     pub images: Vec<Image>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<ModelOptions>,
@@ -35,8 +36,10 @@ pub struct GenerationRequest<'a> {
     pub format: Option<FormatType>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keep_alive: Option<KeepAlive>,
+    #[serde(default)]
     pub(crate) stream: bool,
-    pub think: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub think: Option<Think>,
 }
 
 impl<'a> GenerationRequest<'a> {
@@ -125,9 +128,10 @@ impl<'a> GenerationRequest<'a> {
         self
     }
 
-    /// Used to control whether thinking/reasoning models will think before responding
-    pub fn think(mut self, think: bool) -> Self {
-        self.think = Some(think);
+    /// Used to control whether thinking/reasoning models will think before responding.
+    /// Accepts a `bool` or a thinking level for models that support it.
+    pub fn think(mut self, think: impl Into<Think>) -> Self {
+        self.think = Some(think.into());
         self
     }
 }
@@ -0,0 +1,225 @@
+//! Splitting of long texts into chunks small enough to be embedded, for retrieval-augmented generation.
+//!
+//! ```
+//! use ollama_rs::generation::embeddings::chunking::Chunker;
+//!
+//! let chunker = Chunker::paragraphs(500).overlap(50);
+//! let chunks = chunker.split("First paragraph.\n\nSecond paragraph.");
+//! assert_eq!(chunks, vec!["First paragraph.\n\nSecond paragraph."]);
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use super::request::GenerateEmbeddingsRequest;
+
+/// Separators tried in order by [`SplitStrategy::Recursive`], from the coarsest to the finest.
+/// The empty separator splits between characters.
+pub const RECURSIVE_SEPARATORS: &[&str] = &["\n\n", "\n", ". ", " ", ""];
+
+/// How a text is first cut into pieces, before the pieces are merged back into chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitStrategy {
+    /// Cut after sentence-ending punctuation (`.`, `!`, `?`) followed by whitespace
+    Sentence,
+    /// Cut on blank lines
+    Paragraph,
+    /// Cut on the coarsest separator of [`RECURSIVE_SEPARATORS`] that gives pieces small enough
+    Recursive,
+}
+
+type LengthFn = Arc<dyn Fn(&str) -> usize + Send + Sync>;
+
+/// Splits texts into chunks of at most `chunk_size`, measured by a length function.
+///
+/// Pieces that are still too long after the split strategy is applied are split recursively,
+/// so chunks only exceed `chunk_size` when a single character does.
+///
+/// By default lengths are counted in characters. Use [`Chunker::length_fn`] with a tokenizer to
+/// make chunks fit the context length of an embedding model. The length of a chunk is
+/// approximated as the sum of the lengths of its pieces.
+#[derive(Clone)]
+pub struct Chunker {
+    strategy: SplitStrategy,
+    chunk_size: usize,
+    overlap: usize,
+    length: LengthFn,
+}
+
+impl fmt::Debug for Chunker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Chunker")
+            .field("strategy", &self.strategy)
+            .field("chunk_size", &self.chunk_size)
+            .field("overlap", &self.overlap)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Chunker {
+    /// Creates a chunker with the given strategy and no overlap
+    pub fn new(strategy: SplitStrategy, chunk_size: usize) -> Self {
+        Self {
+            strategy,
+            chunk_size: chunk_size.max(1),
+            overlap: 0,
+            length: Arc::new(|s: &str| s.chars().count()),
+        }
+    }
+
+    /// Creates a chunker keeping sentences together
+    pub fn sentences(chunk_size: usize) -> Self {
+        Self::new(SplitStrategy::Sentence, chunk_size)
+    }
+
+    /// Creates a chunker keeping paragraphs together
+    pub fn paragraphs(chunk_size: usize) -> Self {
+        Self::new(SplitStrategy::Paragraph, chunk_size)
+    }
+
+    /// Creates a chunker splitting on the coarsest separator possible
+    pub fn recursive(chunk_size: usize) -> Self {
+        Self::new(SplitStrategy::Recursive, chunk_size)
+    }
+
+    /// Maximum length of the end of a chunk repeated at the start of the next one,
+    /// so that context is not lost at chunk boundaries. (Default: 0)
+    pub fn overlap(mut self, overlap: usize) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Measures lengths with a custom function, typically the number of tokens given by a tokenizer
+    pub fn length_fn(mut self, length: impl Fn(&str) -> usize + Send + Sync + 'static) -> Self {
+        self.length = Arc::new(length);
+        self
+    }
+
+    /// Splits a text into chunks. Chunks are trimmed and empty chunks are dropped.
+    pub fn split(&self, text: &str) -> Vec<String> {
+        let pieces = match self.strategy {
+            SplitStrategy::Sentence => split_sentences(text),
+            SplitStrategy::Paragraph => split_paragraphs(text),
+            SplitStrategy::Recursive => vec![text],
+        };
+
+        let pieces = pieces
+            .into_iter()
+            .flat_map(|piece| self.split_recursive(piece, RECURSIVE_SEPARATORS))
+            .collect::<Vec<_>>();
+
+        self.merge(pieces)
+    }
+
+    /// Splits several texts into chunks, in order
+    pub fn split_all<S: AsRef<str>>(&self, texts: &[S]) -> Vec<String> {
+        texts.iter().flat_map(|t| self.split(t.as_ref())).collect()
+    }
+
+    /// Splits a text and builds a request embedding all of its chunks at once
+    pub fn embeddings_request(&self, model_name: String, text: &str) -> GenerateEmbeddingsRequest {
+        GenerateEmbeddingsRequest::new(model_name, self.split(text).into())
+    }
+
+    fn len(&self, s: &str) -> usize {
+        (self.length)(s)
+    }
+
+    fn split_recursive<'a>(&self, text: &'a str, separators: &[&str]) -> Vec<&'a str> {
+        if self.len(text) <= self.chunk_size {
+            return vec![text];
+        }
+
+        let Some((separator, rest)) = separators.split_first() else {
+            return vec![text];
+        };
+
+        if separator.is_empty() {
+            return text
+                .char_indices()
+                .map(|(i, c)| &text[i..i + c.len_utf8()])
+                .collect();
+        }
+
+        if !text.contains(separator) {
+            return self.split_recursive(text, rest);
+        }
+
+        text.split_inclusive(separator)
+            .flat_map(|piece| self.split_recursive(piece, rest))
+            .collect()
+    }
+
+    fn merge(&self, pieces: Vec<&str>) -> Vec<String> {
+        let mut chunks = Vec::new();
+        let mut current: Vec<(&str, usize)> = Vec::new();
+        let mut current_len = 0;
+
+        for piece in pieces {
+            let len = self.len(piece);
+
+            if !current.is_empty() && current_len + len > self.chunk_size {
+                chunks.push(concat(&current));
+
+                // Keep the end of the chunk as overlap, as long as the new piece still fits
+                while !current.is_empty()
+                    && (current_len > self.overlap || current_len + len > self.chunk_size)
+                {
+                    current_len -= current.remove(0).1;
+                }
+            }
+
+            current.push((piece, len));
+            current_len += len;
+        }
+
+        if !current.is_empty() {
+            chunks.push(concat(&current));
+        }
+
+        chunks
+            .into_iter()
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect()
+    }
+}
+
+fn concat(pieces: &[(&str, usize)]) -> String {
+    pieces.iter().map(|(p, _)| *p).collect()
+}
+
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((_, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+
+        let mut end = None;
+        while let Some(&(i, next)) = chars.peek() {
+            if !next.is_whitespace() {
+                break;
+            }
+            end = Some(i + next.len_utf8());
+            chars.next();
+        }
+
+        if let Some(end) = end {
+            pieces.push(&text[start..end]);
+            start = end;
+        }
+    }
+
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+
+    pieces
+}
+
+fn split_paragraphs(text: &str) -> Vec<&str> {
+    text.split_inclusive("\n\n").collect()
+}
@@ -0,0 +1,210 @@
+//! On-disk embedding files, searched through a memory map so that corpora larger than RAM can be scanned.
+//!
+//! The format is an 8 bytes magic number, the dimension as a little-endian `u64`, then the embeddings
+//! one after the other as little-endian `f32`s. The index of an embedding is its position in the file.
+
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use memmap2::Mmap;
+
+use crate::error::OllamaError;
+
+const MAGIC: &[u8; 8] = b"OLEMBED1";
+const HEADER_LEN: usize = 16;
+
+/// Writes embeddings to a file readable by [`EmbeddingFile`]
+#[derive(Debug)]
+pub struct EmbeddingFileWriter {
+    writer: BufWriter<File>,
+    dimension: usize,
+    len: usize,
+}
+
+impl EmbeddingFileWriter {
+    /// Creates the file, or truncates it if it exists
+    pub fn create(path: impl AsRef<Path>, dimension: usize) -> crate::error::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(dimension as u64).to_le_bytes())?;
+
+        Ok(Self {
+            writer,
+            dimension,
+            len: 0,
+        })
+    }
+
+    /// Appends an embedding, which must have the dimension of the file
+    pub fn push(&mut self, embedding: &[f32]) -> crate::error::Result<()> {
+        if embedding.len() != self.dimension {
+            return Err(OllamaError::EmbeddingDimensionMismatch {
+                expected: self.dimension,
+                actual: embedding.len(),
+            });
+        }
+
+        for x in embedding {
+            self.writer.write_all(&x.to_le_bytes())?;
+        }
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// The number of embeddings written so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Flushes the buffered embeddings to the file
+    pub fn finish(mut self) -> crate::error::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A memory-mapped file of embeddings. Only the pages being read are loaded in memory,
+/// and the operating system can evict them at any time.
+#[derive(Debug)]
+pub struct EmbeddingFile {
+    mmap: Mmap,
+    dimension: usize,
+    len: usize,
+}
+
+impl EmbeddingFile {
+    pub fn open(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the file must not be modified while it is mapped, as documented by `memmap2`
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        if mmap.len() < HEADER_LEN || &mmap[..8] != MAGIC {
+            return Err(invalid("Not an embedding file").into());
+        }
+
+        // The header is untrusted: a dimension whose records don't fit in the file is rejected
+        let dimension = u64::from_le_bytes(mmap[8..HEADER_LEN].try_into().unwrap());
+        let data_len = mmap.len() - HEADER_LEN;
+        let record_len = usize::try_from(dimension)
+            .ok()
+            .and_then(|dimension| dimension.checked_mul(4))
+            .filter(|&record_len| record_len != 0 && data_len.is_multiple_of(record_len))
+            .ok_or_else(|| invalid("Embedding file is truncated or corrupted"))?;
+        let dimension = record_len / 4;
+
+        Ok(Self {
+            dimension,
+            len: data_len / record_len,
+            mmap,
+        })
+    }
+
+    /// The dimension of the embeddings in the file
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// The number of embeddings in the file
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reads the embedding at `index`
+    pub fn get(&self, index: usize) -> Option<Vec<f32>> {
+        (index < self.len).then(|| self.components(index).collect())
+    }
+
+    fn components(&self, index: usize) -> impl Iterator<Item = f32> + '_ {
+        let start = HEADER_LEN + index * self.dimension * 4;
+        self.mmap[start..start + self.dimension * 4]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    /// Lazily computes the cosine similarity of the query to every embedding, as `(index, similarity)` pairs in file order
+    pub fn scores<'a>(
+        &'a self,
+        query: &'a [f32],
+    ) -> crate::error::Result<impl Iterator<Item = (usize, f32)> + 'a> {
+        if query.len() != self.dimension {
+            return Err(OllamaError::EmbeddingDimensionMismatch {
+                expected: self.dimension,
+                actual: query.len(),
+            });
+        }
+
+        let query_norm = super::math::norm(query);
+
+        Ok((0..self.len).map(move |index| {
+            let (dot, norm) = self
+                .components(index)
+                .zip(query)
+                .fold((0.0f32, 0.0f32), |(dot, norm), (x, q)| {
+                    (dot + x * q, norm + x * x)
+                });
+
+            let norms = norm.sqrt() * query_norm;
+            (index, if norms == 0.0 { 0.0 } else { dot / norms })
+        }))
+    }
+
+    /// Returns the `k` embeddings most similar to the query, sorted from the most to the least similar.
+    /// Only `k` scores are kept in memory during the scan.
+    pub fn top_k(&self, query: &[f32], k: usize) -> crate::error::Result<Vec<(usize, f32)>> {
+        let mut heap = BinaryHeap::with_capacity(k.min(self.len) + 1);
+
+        for (index, score) in self.scores(query)? {
+            heap.push(MinScored(index, score));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        let mut best = heap
+            .into_iter()
+            .map(|MinScored(index, score)| (index, score))
+            .collect::<Vec<_>>();
+        best.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        Ok(best)
+    }
+}
+
+/// Ordered so that the lowest score is at the top of a [`BinaryHeap`]
+struct MinScored(usize, f32);
+
+impl PartialEq for MinScored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MinScored {}
+
+impl PartialOrd for MinScored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MinScored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.1.total_cmp(&self.1)
+    }
+}
@@ -0,0 +1,80 @@
+/// Computes the dot product of two vectors.
+/// If the vectors have different lengths, the extra components of the longest one are ignored.
+pub fn dot_product(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Computes the L2 norm (length) of a vector.
+pub fn norm(v: &[f32]) -> f32 {
+    dot_product(v, v).sqrt()
+}
+
+/// Scales a vector in place to a unit L2 norm. A null vector is left untouched.
+pub fn normalize(v: &mut [f32]) {
+    let norm = norm(v);
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+/// Returns a copy of the vector scaled to a unit L2 norm, see [`normalize`].
+pub fn normalized(v: &[f32]) -> Vec<f32> {
+    let mut v = v.to_vec();
+    normalize(&mut v);
+    v
+}
+
+/// Keeps the first `dimensions` components of a vector and scales it back to a unit L2 norm.
+///
+/// Only meaningful for models trained with matryoshka representation learning, such as
+/// `nomic-embed-text` v1.5, whose leading components carry most of the information.
+/// Vectors shorter than `dimensions` are only normalized.
+pub fn truncate_dim(v: &mut Vec<f32>, dimensions: usize) {
+    v.truncate(dimensions);
+    normalize(v);
+}
+
+/// Computes the cosine similarity of two vectors, between -1 and 1.
+/// Returns 0 if one of the vectors is null.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let norm_a = norm(a);
+    let norm_b = norm(b);
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot_product(a, b) / (norm_a * norm_b)
+    }
+}
+
+/// Computes the Euclidean distance between two vectors.
+pub fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// Returns the `k` candidates most similar to the query by cosine similarity,
+/// as `(index, similarity)` pairs sorted from the most to the least similar.
+pub fn top_k<V: AsRef<[f32]>>(query: &[f32], candidates: &[V], k: usize) -> Vec<(usize, f32)> {
+    top_k_by(candidates, k, |c| cosine_similarity(query, c))
+}
+
+/// Returns the `k` candidates with the highest score, as `(index, score)` pairs sorted from the highest to the lowest score.
+pub fn top_k_by<V, F>(candidates: &[V], k: usize, mut score: F) -> Vec<(usize, f32)>
+where
+    V: AsRef<[f32]>,
+    F: FnMut(&[f32]) -> f32,
+{
+    let mut scored: Vec<(usize, f32)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, score(c.as_ref())))
+        .collect();
+
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(k);
+    scored
+}
@@ -1,10 +1,24 @@
+use std::path::Path;
+
 use serde::Deserialize;
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, generation::images::Image, transport::EndpointClass, Ollama};
 
-use self::request::GenerateEmbeddingsRequest;
+use self::request::{EmbeddingsInput, GenerateEmbeddingsRequest};
 
+pub mod chunking;
+#[cfg_attr(docsrs, doc(cfg(feature = "mmap")))]
+#[cfg(feature = "mmap")]
+pub mod file;
+pub mod math;
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub mod pipeline;
+pub mod quantize;
 pub mod request;
+pub mod rerank;
+pub mod router;
+pub mod store;
 
 impl Ollama {
     /// Generate embeddings from a model
@@ -12,32 +26,182 @@ impl Ollama {
     /// * `prompt` - Prompt to generate embeddings for
     pub async fn generate_embeddings(
         &self,
-        request: GenerateEmbeddingsRequest,
+        mut request: GenerateEmbeddingsRequest,
     ) -> crate::error::Result<GenerateEmbeddingsResponse> {
-        let url = format!("{}api/embed", self.url_str());
+        self.apply_defaults(
+            &mut request.model_name,
+            &mut request.options,
+            &mut request.keep_alive,
+        );
+        request.model_name = self.resolve_model_alias(request.model_name);
+
+        let url = self.endpoint_url("api/embed");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(&request), EndpointClass::Embeddings)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(
-                res.text().await.unwrap_or_else(|e| e.to_string()),
-            ));
+            // With truncation disabled, Ollama rejects inputs longer than the context length
+            return Err(OllamaError::from_response(res).await);
         }
 
         let res = res.bytes().await?;
-        let res = serde_json::from_slice::<GenerateEmbeddingsResponse>(&res)?;
+        let mut res = serde_json::from_slice::<GenerateEmbeddingsResponse>(&res)?;
+
+        if let Some(dimensions) = request.truncate_dim {
+            res.truncate_dim(dimensions);
+        } else if request.normalize {
+            res.normalize();
+        }
 
         Ok(res)
     }
+
+    /// Generate embeddings with a dimension known at compile time, for vector stores with fixed-size columns
+    /// * `request` - The embeddings request, with one or several inputs
+    ///
+    /// Returns an error if the model produces embeddings of another dimension than `D`
+    pub async fn embed_fixed<const D: usize>(
+        &self,
+        request: GenerateEmbeddingsRequest,
+    ) -> crate::error::Result<Vec<[f32; D]>> {
+        self.generate_embeddings(request).await?.into_fixed::<D>()
+    }
+
+    /// Generate the embedding of an image, with a multimodal embedding model
+    /// * `model_name` - Name of model to generate embeddings from, which must support image inputs
+    /// * `bytes` - The encoded image, in one of the formats listed in [`ImageFormat`](crate::generation::images::ImageFormat)
+    ///
+    /// Returns [`OllamaError::UnsupportedImageFormat`] for the other formats, or an error if the server or the model
+    /// doesn't produce an embedding for the image
+    pub async fn embed_image(
+        &self,
+        model_name: String,
+        bytes: &[u8],
+    ) -> crate::error::Result<Vec<f32>> {
+        self.embed_image_inner(model_name, Image::from_bytes(bytes)?)
+            .await
+    }
+
+    /// Generate the embedding of an image file, see [`Ollama::embed_image`]
+    pub async fn embed_image_path(
+        &self,
+        model_name: String,
+        path: impl AsRef<Path>,
+    ) -> crate::error::Result<Vec<f32>> {
+        self.embed_image_inner(model_name, Image::from_path(path)?)
+            .await
+    }
+
+    async fn embed_image_inner(
+        &self,
+        model_name: String,
+        image: Image,
+    ) -> crate::error::Result<Vec<f32>> {
+        // No text input, so that only the image is embedded
+        let request = GenerateEmbeddingsRequest::new(model_name, EmbeddingsInput::Multiple(vec![]))
+            .images(vec![image]);
+
+        self.generate_embeddings(request)
+            .await?
+            .embeddings
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                OllamaError::Other(
+                    "No embedding returned for the image, the model may not support image inputs"
+                        .to_string(),
+                )
+            })
+    }
+
+    /// Generate embeddings for several inputs in a single request
+    /// * `model_name` - Name of model to generate embeddings from
+    /// * `inputs` - Texts to generate embeddings for
+    ///
+    /// Returns one embedding per input, in the same order as the inputs
+    pub async fn generate_embeddings_batch(
+        &self,
+        model_name: String,
+        inputs: Vec<String>,
+    ) -> crate::error::Result<Vec<Vec<f32>>> {
+        if inputs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let expected = inputs.len();
+        let res = self
+            .generate_embeddings(GenerateEmbeddingsRequest::new(model_name, inputs.into()))
+            .await?;
+
+        if res.embeddings.len() != expected {
+            return Err(OllamaError::Other(format!(
+                "Expected {expected} embeddings, got {}",
+                res.embeddings.len()
+            )));
+        }
+
+        Ok(res.embeddings)
+    }
 }
 
 /// An embeddings generation response from Ollama.
 #[derive(Debug, Deserialize, Clone)]
 pub struct GenerateEmbeddingsResponse {
+    /// One embedding per input, in the same order as the inputs
     #[allow(dead_code)]
     pub embeddings: Vec<Vec<f32>>,
+    /// The name of the model used for the embeddings
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Time spent in nanoseconds generating the embeddings
+    #[serde(default)]
+    pub total_duration: Option<u64>,
+    /// Time spent in nanoseconds loading the model
+    #[serde(default)]
+    pub load_duration: Option<u64>,
+    /// Number of tokens in the inputs
+    #[serde(default)]
+    pub prompt_eval_count: Option<u64>,
+}
+
+impl GenerateEmbeddingsResponse {
+    /// Converts the embeddings to fixed-size arrays, checking that they all have dimension `D`
+    pub fn into_fixed<const D: usize>(self) -> crate::error::Result<Vec<[f32; D]>> {
+        self.embeddings
+            .into_iter()
+            .map(|e| {
+                let actual = e.len();
+                <[f32; D]>::try_from(e).map_err(|_| OllamaError::EmbeddingDimensionMismatch {
+                    expected: D,
+                    actual,
+                })
+            })
+            .collect()
+    }
+
+    /// L2-normalizes every embedding in place
+    pub fn normalize(&mut self) {
+        self.embeddings.iter_mut().for_each(|e| math::normalize(e));
+    }
+
+    /// Truncates every embedding to its first `dimensions` components and re-normalizes it,
+    /// see [`math::truncate_dim`]
+    pub fn truncate_dim(&mut self, dimensions: usize) {
+        self.embeddings
+            .iter_mut()
+            .for_each(|e| math::truncate_dim(e, dimensions));
+    }
+
+    /// Returns the `k` embeddings most similar to the query by cosine similarity,
+    /// as `(index, similarity)` pairs sorted from the most to the least similar
+    pub fn top_k(&self, query: &[f32], k: usize) -> Vec<(usize, f32)> {
+        math::top_k(query, &self.embeddings, k)
+    }
 }
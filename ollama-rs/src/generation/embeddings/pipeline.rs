@@ -0,0 +1,320 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use crate::{
+    error::OllamaError, generation::parameters::KeepAlive, models::ModelOptions, Instant, Ollama,
+};
+
+use super::request::GenerateEmbeddingsRequest;
+
+/// A stream of `(index, embedding)` items, where `index` is the position of the text in the pipeline input
+pub type EmbeddingPipelineStream =
+    crate::BoxStream<'static, (usize, crate::error::Result<Vec<f32>>)>;
+
+/// A snapshot of the progress of an [`EmbeddingPipeline`], reported after each batch, whether it succeeded or failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingProgress {
+    /// The number of texts embedded successfully
+    pub done: usize,
+    /// The number of texts whose batch failed after all retries
+    pub failed: usize,
+    /// The number of batches finished, successfully or not
+    pub batches: usize,
+    /// The number of batches that failed after all retries
+    pub failed_batches: usize,
+    /// The total number of texts, if the input iterator knows its exact length
+    pub total: Option<usize>,
+    /// Time elapsed since the pipeline started
+    pub elapsed: Duration,
+}
+
+impl EmbeddingProgress {
+    /// The number of texts processed, successfully or not
+    pub fn processed(&self) -> usize {
+        self.done + self.failed
+    }
+
+    /// The number of texts left to process, if the total is known
+    pub fn remaining(&self) -> Option<usize> {
+        self.total
+            .map(|total| total.saturating_sub(self.processed()))
+    }
+
+    /// The number of texts processed per second
+    pub fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.processed() as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ProgressCallback(Arc<dyn Fn(&EmbeddingProgress) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Embeds a large number of texts: the texts are grouped in batches, several batches are sent concurrently
+/// and failed batches are retried with an exponential backoff.
+///
+/// Results are yielded as soon as their batch completes, so they may be out of order.
+#[derive(Debug, Clone)]
+pub struct EmbeddingPipeline {
+    ollama: Ollama,
+    model_name: String,
+    options: Option<ModelOptions>,
+    keep_alive: Option<KeepAlive>,
+    batch_size: usize,
+    max_concurrency: usize,
+    max_retries: u32,
+    retry_delay: Duration,
+    min_interval: Option<Duration>,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl EmbeddingPipeline {
+    pub fn new(ollama: Ollama, model_name: String) -> Self {
+        Self {
+            ollama,
+            model_name,
+            options: None,
+            keep_alive: None,
+            batch_size: 32,
+            max_concurrency: 4,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+            min_interval: None,
+            on_progress: None,
+        }
+    }
+
+    /// The number of texts sent in each request (Default: 32)
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// The maximum number of requests in flight at any time (Default: 4)
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// How many times a failed batch is retried before its texts are reported as failed (Default: 3)
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The delay before the first retry, doubled for each following retry (Default: 500ms)
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Rate limits the pipeline by waiting at least `min_interval` between the start of two requests
+    pub fn min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = Some(min_interval);
+        self
+    }
+
+    /// Additional model parameters listed in the documentation for the Modelfile
+    pub fn options(mut self, options: ModelOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Used to control how long the model stays loaded in memory between batches
+    pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// Calls `callback` after each finished batch, successful or not, with the progress of the pipeline,
+    /// for instance to update a progress bar.
+    ///
+    /// Failed texts are also yielded as errors by the stream along with their index,
+    /// so a long job can be resumed by running the pipeline again on those texts only.
+    pub fn on_progress(
+        mut self,
+        callback: impl Fn(&EmbeddingProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(ProgressCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Embeds the texts, returning a stream of `(index, embedding)` items.
+    /// If a batch still fails after the retries, an error is yielded for each of its texts.
+    pub fn run<I>(self, texts: I) -> EmbeddingPipelineStream
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: Send + 'static,
+    {
+        self.run_stream(tokio_stream::iter(texts))
+    }
+
+    /// Embeds the texts of a stream, such as the rows of a database cursor, as they arrive,
+    /// see [`EmbeddingPipeline::run`]. The texts are read only when a batch can be sent.
+    pub fn run_stream<S>(self, texts: S) -> EmbeddingPipelineStream
+    where
+        S: tokio_stream::Stream<Item = String> + Send + 'static,
+    {
+        use async_stream::stream;
+        use tokio_stream::StreamExt;
+
+        let total = match texts.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        };
+        let mut texts = Box::pin(texts.fuse());
+
+        let s = stream! {
+            let mut tasks = tokio::task::JoinSet::new();
+            let mut last_start: Option<Instant> = None;
+            let mut next_index = 0;
+            let start = Instant::now();
+            // The indices of the batches in flight, to report the texts of a batch whose task panicked
+            let mut in_flight = std::collections::HashMap::new();
+            let mut progress = EmbeddingProgress {
+                done: 0,
+                failed: 0,
+                batches: 0,
+                failed_batches: 0,
+                total,
+                elapsed: Duration::ZERO,
+            };
+
+            loop {
+                while tasks.len() < self.max_concurrency {
+                    let mut batch: Vec<(usize, String)> = Vec::with_capacity(self.batch_size);
+                    while batch.len() < self.batch_size {
+                        let Some(text) = texts.next().await else {
+                            break;
+                        };
+                        batch.push((next_index, text));
+                        next_index += 1;
+                    }
+                    if batch.is_empty() {
+                        break;
+                    }
+
+                    if let (Some(interval), Some(last_start)) = (self.min_interval, last_start) {
+                        let elapsed = last_start.elapsed();
+                        if elapsed < interval {
+                            crate::runtime::sleep(interval - elapsed).await;
+                        }
+                    }
+                    last_start = Some(Instant::now());
+
+                    let indices: Vec<usize> = batch.iter().map(|(index, _)| *index).collect();
+                    let pipeline = self.clone();
+                    let task = tasks.spawn(async move { pipeline.embed_batch(batch).await });
+                    in_flight.insert(task.id(), indices);
+                }
+
+                let Some(res) = tasks.join_next_with_id().await else {
+                    break;
+                };
+
+                let results = match res {
+                    Ok((id, results)) => {
+                        in_flight.remove(&id);
+                        results
+                    }
+                    Err(e) => {
+                        // The batch task panicked or was cancelled, its texts are reported as failed
+                        log::error!("Embedding batch task failed: {e}");
+                        let message = format!("Embedding batch task failed: {e}");
+                        in_flight
+                            .remove(&e.id())
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|index| (index, Err(OllamaError::Other(message.clone()))))
+                            .collect()
+                    }
+                };
+
+                let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+                progress.failed += failed;
+                progress.done += results.len() - failed;
+                progress.batches += 1;
+                if failed > 0 {
+                    progress.failed_batches += 1;
+                }
+                progress.elapsed = start.elapsed();
+                if let Some(callback) = &self.on_progress {
+                    (callback.0)(&progress);
+                }
+
+                for item in results {
+                    yield item;
+                }
+            }
+        };
+
+        Box::pin(s)
+    }
+
+    async fn embed_batch(
+        &self,
+        batch: Vec<(usize, String)>,
+    ) -> Vec<(usize, crate::error::Result<Vec<f32>>)> {
+        let (indices, inputs): (Vec<usize>, Vec<String>) = batch.into_iter().unzip();
+
+        let mut attempt = 0;
+        let error = loop {
+            let mut request =
+                GenerateEmbeddingsRequest::new(self.model_name.clone(), inputs.clone().into());
+            if let Some(options) = &self.options {
+                request = request.options(options.clone());
+            }
+            if let Some(keep_alive) = &self.keep_alive {
+                request = request.keep_alive(keep_alive.clone());
+            }
+
+            match self.ollama.generate_embeddings(request).await {
+                Ok(res) if res.embeddings.len() == indices.len() => {
+                    return indices
+                        .into_iter()
+                        .zip(res.embeddings.into_iter().map(Ok))
+                        .collect();
+                }
+                Ok(res) => {
+                    break OllamaError::Other(format!(
+                        "Expected {} embeddings, got {}",
+                        indices.len(),
+                        res.embeddings.len()
+                    ))
+                }
+                Err(e) if attempt < self.max_retries && is_transient(&e) => {
+                    crate::runtime::sleep(self.retry_delay * 2u32.saturating_pow(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => break e,
+            }
+        };
+
+        let error = Arc::new(error);
+        indices
+            .into_iter()
+            .map(|i| (i, Err(OllamaError::Shared(error.clone()))))
+            .collect()
+    }
+}
+
+/// Whether an error may go away by retrying the request: the network failures and the statuses
+/// of a busy server, but not the requests that Ollama rejects
+fn is_transient(error: &OllamaError) -> bool {
+    match error.inner() {
+        OllamaError::ReqwestError(e) => {
+            e.is_connect() || e.is_timeout() || e.is_request() || e.is_body()
+        }
+        OllamaError::ServerUnavailable { .. } => true,
+        _ => false,
+    }
+}
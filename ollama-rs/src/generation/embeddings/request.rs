@@ -1,13 +1,31 @@
 use serde::{Serialize, Serializer};
 
-use crate::{generation::parameters::KeepAlive, models::ModelOptions};
+use crate::{
+    generation::{images::Image, parameters::KeepAlive},
+    models::ModelOptions,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum EmbeddingsInput {
     Single(String),
     Multiple(Vec<String>),
 }
 
+impl EmbeddingsInput {
+    /// The number of texts to embed, and so the number of embeddings in the response
+    pub fn len(&self) -> usize {
+        match self {
+            EmbeddingsInput::Single(_) => 1,
+            EmbeddingsInput::Multiple(v) => v.len(),
+        }
+    }
+
+    /// Whether there is no text to embed
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 impl Default for EmbeddingsInput {
     fn default() -> Self {
         Self::Single(String::default())
@@ -47,21 +65,41 @@ impl Serialize for EmbeddingsInput {
     }
 }
 
+/// What the server does with inputs longer than the context length of the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Truncation {
+    /// The end of the input is cut off to fit the context length (server default)
+    Truncate,
+    /// The request fails with [`OllamaError::ContextLengthExceeded`](crate::error::OllamaError::ContextLengthExceeded)
+    Error,
+}
+
 /// An embeddings generation request to Ollama.
-#[derive(Debug, Serialize, Default)]
+///
+/// Several inputs can be embedded in a single request, which is much faster than one request per input.
+#[derive(Debug, Clone, Serialize, Default)]
 pub struct GenerateEmbeddingsRequest {
     #[serde(rename = "model")]
-    model_name: String,
+    pub(crate) model_name: String,
     input: EmbeddingsInput,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    images: Vec<Image>,
     #[serde(skip_serializing_if = "Option::is_none")]
     truncate: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    options: Option<ModelOptions>,
+    pub(crate) options: Option<ModelOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    keep_alive: Option<KeepAlive>,
+    pub(crate) keep_alive: Option<KeepAlive>,
+    /// Client-side normalization of the embeddings, never sent to Ollama
+    #[serde(skip)]
+    pub(crate) normalize: bool,
+    /// Client-side dimensionality truncation of the embeddings, never sent to Ollama
+    #[serde(skip)]
+    pub(crate) truncate_dim: Option<usize>,
 }
 
 impl GenerateEmbeddingsRequest {
+    /// Creates a request embedding one input, or several at once with [`EmbeddingsInput::Multiple`]
     pub fn new(model_name: String, input: EmbeddingsInput) -> Self {
         Self {
             model_name,
@@ -70,18 +108,53 @@ impl GenerateEmbeddingsRequest {
         }
     }
 
+    /// Additional model parameters listed in the documentation for the Modelfile
     pub fn options(mut self, options: ModelOptions) -> Self {
         self.options = Some(options);
         self
     }
 
+    /// Used to control how long a model stays loaded in memory, by default models are unloaded after 5 minutes of inactivity
     pub fn keep_alive(mut self, keep_alive: KeepAlive) -> Self {
         self.keep_alive = Some(keep_alive);
         self
     }
 
+    /// Truncates the end of each input to fit within the context length. Returns an error if `false` and the context length is exceeded. (Default: true)
     pub fn truncate(mut self, truncate: bool) -> Self {
         self.truncate = Some(truncate);
         self
     }
+
+    /// Chooses what happens when an input exceeds the context length, see [`Truncation`]
+    pub fn truncation(self, truncation: Truncation) -> Self {
+        self.truncate(truncation == Truncation::Truncate)
+    }
+
+    /// L2-normalizes the embeddings on the client side once received, so they can be compared with
+    /// normalized vectors from other sources. (Default: false)
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Keeps only the first `dimensions` components of each embedding, then re-normalizes it.
+    /// Trades accuracy for storage with models trained with matryoshka representation, such as
+    /// `nomic-embed-text` v1.5; other models give meaningless truncated embeddings.
+    pub fn truncate_dim(mut self, dimensions: usize) -> Self {
+        self.truncate_dim = Some(dimensions);
+        self
+    }
+
+    /// Images to embed, for multimodal embedding models supporting image inputs.
+    /// Servers or models without image support ignore them.
+    pub fn images(mut self, images: Vec<Image>) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Returns the inputs to embed
+    pub fn input(&self) -> &EmbeddingsInput {
+        &self.input
+    }
 }
@@ -0,0 +1,61 @@
+use crate::{error::OllamaError, Ollama};
+
+use super::{math, request::GenerateEmbeddingsRequest};
+
+/// A candidate document scored against a query by [`Ollama::rerank`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RerankResult {
+    /// Index of the candidate in the input
+    pub index: usize,
+    pub document: String,
+    /// Cosine similarity between the query and the candidate
+    pub score: f32,
+}
+
+impl Ollama {
+    /// Reorders candidate documents by their embedding similarity to a query.
+    /// The query and the candidates are embedded in a single request.
+    /// * `query` - The query the candidates are compared to
+    /// * `candidates` - The documents to sort, typically retrieved by a first, coarser search
+    /// * `model_name` - Name of model to generate embeddings from
+    ///
+    /// Returns all candidates sorted from the most to the least similar
+    pub async fn rerank(
+        &self,
+        query: impl Into<String>,
+        candidates: Vec<String>,
+        model_name: String,
+    ) -> crate::error::Result<Vec<RerankResult>> {
+        if candidates.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut inputs = Vec::with_capacity(candidates.len() + 1);
+        inputs.push(query.into());
+        inputs.extend(candidates.iter().cloned());
+
+        let res = self
+            .generate_embeddings(GenerateEmbeddingsRequest::new(model_name, inputs.into()))
+            .await?;
+
+        let Some((query, embeddings)) = res.embeddings.split_first() else {
+            return Err(OllamaError::Other("No embeddings returned".to_string()));
+        };
+        if embeddings.len() != candidates.len() {
+            return Err(OllamaError::Other(format!(
+                "Expected {} embeddings, got {}",
+                candidates.len() + 1,
+                res.embeddings.len()
+            )));
+        }
+
+        Ok(math::top_k(query, embeddings, embeddings.len())
+            .into_iter()
+            .map(|(index, score)| RerankResult {
+                index,
+                document: candidates[index].clone(),
+                score,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{error::OllamaError, Ollama};
+
+use super::math;
+
+/// A destination of a [`SemanticRouter`], recognized by its example messages
+#[derive(Debug, Clone)]
+pub struct Route<T> {
+    pub name: String,
+    /// Example messages that should be sent to this route
+    pub exemplars: Vec<String>,
+    /// What the route dispatches to, such as a model name, a toolset or a system prompt
+    pub target: T,
+}
+
+impl<T> Route<T> {
+    pub fn new(name: impl Into<String>, exemplars: Vec<String>, target: T) -> Self {
+        Self {
+            name: name.into(),
+            exemplars,
+            target,
+        }
+    }
+}
+
+/// The route chosen for a message by [`SemanticRouter::route`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteMatch<'a, T> {
+    pub name: &'a str,
+    pub target: &'a T,
+    /// Cosine similarity between the message and the closest exemplar of the route
+    pub score: f32,
+}
+
+/// Builds a [`SemanticRouter`], embedding the exemplars of all routes at once
+#[derive(Debug, Clone)]
+pub struct SemanticRouterBuilder<T> {
+    ollama: Ollama,
+    model_name: String,
+    routes: Vec<Route<T>>,
+    threshold: Option<f32>,
+}
+
+impl<T> SemanticRouterBuilder<T> {
+    /// Adds a route with its example messages
+    pub fn route(mut self, name: impl Into<String>, exemplars: Vec<String>, target: T) -> Self {
+        self.routes.push(Route::new(name, exemplars, target));
+        self
+    }
+
+    /// The minimum similarity for a message to be routed, below which no route matches
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Embeds the exemplars of all routes
+    pub async fn build(self) -> crate::error::Result<SemanticRouter<T>> {
+        let inputs = self
+            .routes
+            .iter()
+            .flat_map(|r| r.exemplars.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let mut embeddings = self
+            .ollama
+            .generate_embeddings_batch(self.model_name.clone(), inputs)
+            .await?
+            .into_iter();
+
+        let routes = self
+            .routes
+            .into_iter()
+            .map(|route| {
+                let exemplars = embeddings.by_ref().take(route.exemplars.len()).collect();
+                (route, exemplars)
+            })
+            .collect();
+
+        Ok(SemanticRouter {
+            ollama: self.ollama,
+            model_name: self.model_name,
+            routes,
+            threshold: self.threshold,
+        })
+    }
+}
+
+/// Maps incoming messages to one of several routes by embedding similarity to the exemplars of each route,
+/// typically to pick a model, a toolset or a prompt before answering.
+///
+/// ```no_run
+/// # async fn example() -> ollama_rs::error::Result<()> {
+/// use ollama_rs::{generation::embeddings::router::SemanticRouter, Ollama};
+///
+/// let router = SemanticRouter::builder(Ollama::default(), "nomic-embed-text:latest".to_string())
+///     .route("code", vec!["Fix this function".to_string()], "qwen2.5-coder:latest")
+///     .route("chat", vec!["How are you today?".to_string()], "llama3.2:latest")
+///     .threshold(0.5)
+///     .build()
+///     .await?;
+///
+/// if let Some(route) = router.route("Why does my loop never end?").await? {
+///     println!("Using {}", route.target);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SemanticRouter<T> {
+    ollama: Ollama,
+    model_name: String,
+    routes: Vec<(Route<T>, Vec<Vec<f32>>)>,
+    threshold: Option<f32>,
+}
+
+impl<T> SemanticRouter<T> {
+    pub fn builder(ollama: Ollama, model_name: String) -> SemanticRouterBuilder<T> {
+        SemanticRouterBuilder {
+            ollama,
+            model_name,
+            routes: vec![],
+            threshold: None,
+        }
+    }
+
+    /// Returns the routes of the router, in the order they were added
+    pub fn routes(&self) -> impl Iterator<Item = &Route<T>> {
+        self.routes.iter().map(|(route, _)| route)
+    }
+
+    /// Returns the route closest to the message, or `None` if its similarity is below the threshold
+    pub async fn route(&self, message: &str) -> crate::error::Result<Option<RouteMatch<'_, T>>> {
+        let scores = self.scores(message).await?;
+
+        Ok(scores
+            .into_iter()
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+            .filter(|m| match self.threshold {
+                Some(threshold) => m.score >= threshold,
+                None => true,
+            }))
+    }
+
+    /// Returns the similarity of the message to every route, in the order the routes were added
+    pub async fn scores(&self, message: &str) -> crate::error::Result<Vec<RouteMatch<'_, T>>> {
+        let query = self
+            .ollama
+            .generate_embeddings_batch(self.model_name.clone(), vec![message.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| OllamaError::Other("No embedding returned".to_string()))?;
+
+        Ok(self.scores_for_embedding(&query))
+    }
+
+    /// Returns the similarity of an already embedded message to every route
+    pub fn scores_for_embedding(&self, query: &[f32]) -> Vec<RouteMatch<'_, T>> {
+        self.routes
+            .iter()
+            .map(|(route, exemplars)| RouteMatch {
+                name: &route.name,
+                target: &route.target,
+                score: exemplars
+                    .iter()
+                    .map(|e| math::cosine_similarity(query, e))
+                    .fold(f32::NEG_INFINITY, f32::max),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,219 @@
+//! Adapters pushing embeddings into vector databases, so that they can be searched by retrieval backends.
+//!
+//! Every backend implements [`VectorStore`]. An in-memory store is always available; the
+//! [`qdrant`] and [`pgvector`] adapters are behind the features of the same name.
+
+use std::{collections::HashMap, future::Future, sync::RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::OllamaError, Ollama};
+
+use super::{
+    quantize::{Precision, QuantizedEmbedding},
+    request::GenerateEmbeddingsRequest,
+};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pgvector")))]
+#[cfg(feature = "pgvector")]
+pub mod pgvector;
+#[cfg_attr(docsrs, doc(cfg(feature = "qdrant")))]
+#[cfg(feature = "qdrant")]
+pub mod qdrant;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// An embedding to store, with an identifier and arbitrary JSON metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingRecord {
+    pub id: String,
+    pub vector: Vec<f32>,
+    pub payload: Value,
+}
+
+impl EmbeddingRecord {
+    pub fn new(id: impl Into<String>, vector: Vec<f32>) -> Self {
+        Self {
+            id: id.into(),
+            vector,
+            payload: Value::Null,
+        }
+    }
+
+    /// Metadata stored along with the embedding and returned by searches, such as the embedded text
+    pub fn payload(mut self, payload: Value) -> Self {
+        self.payload = payload;
+        self
+    }
+}
+
+/// A record found by a search, with its similarity to the query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredRecord {
+    pub id: String,
+    /// Cosine similarity to the query, higher is more similar
+    pub score: f32,
+    pub payload: Value,
+}
+
+/// A backend storing embeddings and searching them by cosine similarity
+pub trait VectorStore: Send + Sync {
+    /// Creates the collection or table holding vectors of the given dimension, if it doesn't exist yet
+    fn ensure_collection(&self, dimension: usize) -> impl Future<Output = Result<()>> + Send;
+
+    /// Inserts records, replacing the ones with the same id
+    fn upsert(&self, records: Vec<EmbeddingRecord>) -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns the `limit` records most similar to the query, from the most to the least similar
+    fn search(
+        &self,
+        vector: &[f32],
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<ScoredRecord>>> + Send;
+}
+
+/// A vector store kept in memory, searched exhaustively.
+///
+/// Vectors can be kept at a lower [`Precision`] to save memory, see [`quantize`](super::quantize).
+#[derive(Debug, Default)]
+pub struct InMemoryVectorStore {
+    precision: Precision,
+    records: RwLock<StoredRecords>,
+}
+
+/// The records, with the position of each id so that upserts don't scan them
+#[derive(Debug, Default)]
+struct StoredRecords {
+    records: Vec<StoredRecord>,
+    index: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct StoredRecord {
+    id: String,
+    vector: QuantizedEmbedding,
+    payload: Value,
+}
+
+impl InMemoryVectorStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a store keeping vectors at the given precision
+    pub fn with_precision(precision: Precision) -> Self {
+        Self {
+            precision,
+            ..Default::default()
+        }
+    }
+
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// The number of records stored
+    pub fn len(&self) -> usize {
+        self.records.read().unwrap().records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl VectorStore for InMemoryVectorStore {
+    async fn ensure_collection(&self, _dimension: usize) -> Result<()> {
+        Ok(())
+    }
+
+    async fn upsert(&self, records: Vec<EmbeddingRecord>) -> Result<()> {
+        let mut stored = self.records.write().unwrap();
+        let StoredRecords {
+            records: stored,
+            index,
+        } = &mut *stored;
+        for record in records {
+            let record = StoredRecord {
+                vector: QuantizedEmbedding::new(&record.vector, self.precision),
+                id: record.id,
+                payload: record.payload,
+            };
+            match index.get(&record.id) {
+                Some(&i) => stored[i] = record,
+                None => {
+                    index.insert(record.id.clone(), stored.len());
+                    stored.push(record);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<ScoredRecord>> {
+        let stored = self.records.read().unwrap();
+        let mut scored = stored
+            .records
+            .iter()
+            .map(|r| (r, r.vector.cosine_similarity(vector)))
+            .collect::<Vec<_>>();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+
+        Ok(scored
+            .into_iter()
+            .map(|(r, score)| ScoredRecord {
+                id: r.id.clone(),
+                score,
+                payload: r.payload.clone(),
+            })
+            .collect())
+    }
+}
+
+impl Ollama {
+    /// Embeds texts and upserts them into a vector store, creating the collection if needed
+    /// * `store` - The vector store to write to
+    /// * `model_name` - Name of model to generate embeddings from
+    /// * `documents` - `(id, text)` pairs, the text is stored in the payload under `"text"`
+    pub async fn embed_into<S: VectorStore>(
+        &self,
+        store: &S,
+        model_name: String,
+        documents: Vec<(String, String)>,
+    ) -> crate::error::Result<()> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        let (ids, texts): (Vec<_>, Vec<_>) = documents.into_iter().unzip();
+        let res = self
+            .generate_embeddings(GenerateEmbeddingsRequest::new(
+                model_name,
+                texts.clone().into(),
+            ))
+            .await?;
+
+        let dimension = res.embeddings.first().map_or(0, Vec::len);
+        store
+            .ensure_collection(dimension)
+            .await
+            .map_err(OllamaError::VectorStoreError)?;
+
+        let records = ids
+            .into_iter()
+            .zip(texts)
+            .zip(res.embeddings)
+            .map(|((id, text), vector)| {
+                EmbeddingRecord::new(id, vector).payload(serde_json::json!({ "text": text }))
+            })
+            .collect();
+
+        store
+            .upsert(records)
+            .await
+            .map_err(OllamaError::VectorStoreError)
+    }
+}
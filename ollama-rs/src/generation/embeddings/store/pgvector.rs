@@ -0,0 +1,95 @@
+use serde_json::Value;
+use tokio_postgres::Client;
+
+use super::{EmbeddingRecord, Result, ScoredRecord, VectorStore};
+
+/// A [`VectorStore`] backed by a PostgreSQL table using the [pgvector](https://github.com/pgvector/pgvector) extension.
+///
+/// The table has the columns `id TEXT PRIMARY KEY`, `embedding vector(dimension)` and `payload JSONB`.
+pub struct PgVectorStore {
+    client: Client,
+    table: String,
+}
+
+impl PgVectorStore {
+    /// * `client` - A connected client, whose connection task is driven by the caller
+    /// * `table` - The name of the table holding the embeddings
+    pub fn new(client: Client, table: impl AsRef<str>) -> Self {
+        Self {
+            client,
+            table: quote_identifier(table.as_ref()),
+        }
+    }
+
+    /// Returns the underlying client, for instance to create indexes on the table
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Formats a vector as a pgvector literal, such as `[1,2,3]`
+fn vector_literal(vector: &[f32]) -> String {
+    let components = vector
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("[{components}]")
+}
+
+impl VectorStore for PgVectorStore {
+    async fn ensure_collection(&self, dimension: usize) -> Result<()> {
+        let query = format!(
+            "CREATE EXTENSION IF NOT EXISTS vector;
+            CREATE TABLE IF NOT EXISTS {} (id TEXT PRIMARY KEY, embedding vector({dimension}) NOT NULL, payload JSONB)",
+            self.table
+        );
+        self.client.batch_execute(&query).await?;
+
+        Ok(())
+    }
+
+    async fn upsert(&self, records: Vec<EmbeddingRecord>) -> Result<()> {
+        let query = format!(
+            "INSERT INTO {} (id, embedding, payload) VALUES ($1, $2::text::vector, $3)
+            ON CONFLICT (id) DO UPDATE SET embedding = EXCLUDED.embedding, payload = EXCLUDED.payload",
+            self.table
+        );
+        let statement = self.client.prepare(&query).await?;
+
+        for record in records {
+            let vector = vector_literal(&record.vector);
+            self.client
+                .execute(&statement, &[&record.id, &vector, &record.payload])
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<ScoredRecord>> {
+        let query = format!(
+            "SELECT id, payload, 1 - (embedding <=> $1::text::vector) AS score FROM {}
+            ORDER BY embedding <=> $1::text::vector LIMIT $2",
+            self.table
+        );
+        let vector = vector_literal(vector);
+        let rows = self
+            .client
+            .query(&query, &[&vector, &(limit as i64)])
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ScoredRecord {
+                id: row.get("id"),
+                score: row.get::<_, f64>("score") as f32,
+                payload: row.get::<_, Option<Value>>("payload").unwrap_or_default(),
+            })
+            .collect())
+    }
+}
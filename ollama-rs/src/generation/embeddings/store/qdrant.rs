@@ -0,0 +1,141 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{EmbeddingRecord, Result, ScoredRecord, VectorStore};
+
+/// A [`VectorStore`] backed by a [Qdrant](https://qdrant.tech) collection, through its REST API.
+///
+/// Qdrant only accepts unsigned integers and UUIDs as point ids, so record ids must be one of those.
+#[derive(Debug, Clone)]
+pub struct QdrantStore {
+    client: reqwest::Client,
+    url: String,
+    collection: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct QdrantResponse<T> {
+    result: T,
+}
+
+#[derive(Deserialize)]
+struct CollectionExists {
+    exists: bool,
+}
+
+#[derive(Deserialize)]
+struct ScoredPoint {
+    id: Value,
+    score: f32,
+    #[serde(default)]
+    payload: Value,
+}
+
+impl QdrantStore {
+    /// * `url` - The URL of the Qdrant REST API, such as `http://localhost:6333`
+    /// * `collection` - The name of the collection holding the embeddings
+    pub fn new(url: impl Into<String>, collection: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into().trim_end_matches('/').to_string(),
+            collection: collection.into(),
+            api_key: None,
+        }
+    }
+
+    /// The API key sent in the `api-key` header, required by Qdrant Cloud
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Uses a custom HTTP client, for instance with timeouts
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/collections/{}{}", self.url, self.collection, path);
+        let builder = self.client.request(method, url);
+
+        match &self.api_key {
+            Some(api_key) => builder.header("api-key", api_key),
+            None => builder,
+        }
+    }
+
+    async fn send<T: for<'de> Deserialize<'de>>(builder: reqwest::RequestBuilder) -> Result<T> {
+        let res = builder.send().await?;
+
+        if !res.status().is_success() {
+            let text = res.text().await.unwrap_or_else(|e| e.to_string());
+            return Err(text.into());
+        }
+
+        Ok(res.json::<QdrantResponse<T>>().await?.result)
+    }
+}
+
+fn point_id(id: &str) -> Value {
+    match id.parse::<u64>() {
+        Ok(n) => Value::from(n),
+        Err(_) => Value::from(id),
+    }
+}
+
+impl VectorStore for QdrantStore {
+    async fn ensure_collection(&self, dimension: usize) -> Result<()> {
+        let exists: CollectionExists =
+            Self::send(self.request(reqwest::Method::GET, "/exists")).await?;
+
+        if !exists.exists {
+            let body = json!({ "vectors": { "size": dimension, "distance": "Cosine" } });
+            Self::send::<Value>(self.request(reqwest::Method::PUT, "").json(&body)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn upsert(&self, records: Vec<EmbeddingRecord>) -> Result<()> {
+        let points = records
+            .into_iter()
+            .map(|r| {
+                let payload = match r.payload {
+                    Value::Null => json!({}),
+                    payload => payload,
+                };
+                json!({ "id": point_id(&r.id), "vector": r.vector, "payload": payload })
+            })
+            .collect::<Vec<_>>();
+
+        let builder = self
+            .request(reqwest::Method::PUT, "/points?wait=true")
+            .json(&json!({ "points": points }));
+        Self::send::<Value>(builder).await?;
+
+        Ok(())
+    }
+
+    async fn search(&self, vector: &[f32], limit: usize) -> Result<Vec<ScoredRecord>> {
+        let body = json!({ "vector": vector, "limit": limit, "with_payload": true });
+        let points: Vec<ScoredPoint> = Self::send(
+            self.request(reqwest::Method::POST, "/points/search")
+                .json(&body),
+        )
+        .await?;
+
+        Ok(points
+            .into_iter()
+            .map(|p| ScoredRecord {
+                id: match p.id {
+                    Value::String(s) => s,
+                    id => id.to_string(),
+                },
+                score: p.score,
+                payload: p.payload,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+const JSON_GRAMMAR: &str = r#"root   ::= object
+value  ::= object | array | string | number | ("true" | "false" | "null") ws
+
+object ::=
+  "{" ws (
+            string ":" ws value
+    ("," ws string ":" ws value)*
+  )? "}" ws
+
+array  ::=
+  "[" ws (
+            value
+    ("," ws value)*
+  )? "]" ws
+
+string ::=
+  "\"" (
+    [^"\\\x7F\x00-\x1F] |
+    "\\" (["\\bfnrt] | "u" [0-9a-fA-F]{4})
+  )* "\"" ws
+
+number ::= ("-"? ([0-9] | [1-9] [0-9]{0,15})) ("." [0-9]+)? ([eE] [-+]? [0-9] [1-9]{0,15})? ws
+
+ws ::= | " " | "\n" [ \t]{0,20}
+"#;
+
+/// A GBNF grammar constraining the output of the model.
+///
+/// Grammars are passed through unchanged in the `grammar` model option, see [`ModelOptions::grammar`](crate::models::ModelOptions::grammar).
+/// Ollama ignores this option, it is only honored by compatible servers that implement it.
+///
+/// ```
+/// use ollama_rs::generation::grammar::Grammar;
+///
+/// let grammar = Grammar::one_of(["red", "green", "blue"]);
+/// assert_eq!(grammar.as_str(), r#"root ::= "red" | "green" | "blue""#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Grammar(String);
+
+impl Grammar {
+    /// Creates a grammar from its GBNF source
+    pub fn new(gbnf: impl Into<String>) -> Self {
+        Self(gbnf.into())
+    }
+
+    /// A grammar accepting any JSON object
+    pub fn json() -> Self {
+        Self::new(JSON_GRAMMAR)
+    }
+
+    /// A grammar accepting only `yes` or `no`
+    pub fn yes_no() -> Self {
+        Self::one_of(["yes", "no"])
+    }
+
+    /// A grammar accepting exactly one of the given strings
+    pub fn one_of<I, S>(choices: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let choices = choices
+            .into_iter()
+            .map(|c| format!("\"{}\"", escape_literal(c.as_ref())))
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        Self(format!("root ::= {choices}"))
+    }
+
+    /// Returns the GBNF source of the grammar
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn escape_literal(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,14 +1,118 @@
-use serde::{Deserialize, Serialize};
+use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Image(String);
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::OllamaError;
+
+/// A base64-encoded image.
+///
+/// Images are serialized as plain base64 strings, the optional [`ImageHints`] stay on the client side.
+#[derive(Debug, Clone)]
+pub struct Image {
+    data: String,
+    hints: ImageHints,
+}
+
+/// Optional client-side hints attached to an image.
+///
+/// Hints are never sent to Ollama, they let the code preparing a request (e.g. a preprocessing step)
+/// know how each image should be treated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageHints {
+    /// The size, in pixels, the longest edge of the image should be downscaled to
+    pub downscale_to: Option<u32>,
+    /// A short label identifying the image, e.g. its file name
+    pub label: Option<String>,
+}
+
+impl ImageHints {
+    pub fn downscale_to(mut self, max_edge: u32) -> Self {
+        self.downscale_to = Some(max_edge);
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl Serialize for Image {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.data)
+    }
+}
+
+impl<'de> Deserialize<'de> for Image {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from_base64)
+    }
+}
 
 impl Image {
     pub fn from_base64(base64: impl Into<String>) -> Self {
-        Self(base64.into())
+        Self {
+            data: base64.into(),
+            hints: ImageHints::default(),
+        }
+    }
+
+    /// Attaches client-side hints to the image
+    pub fn with_hints(mut self, hints: ImageHints) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    /// Returns the client-side hints of the image
+    pub fn hints(&self) -> &ImageHints {
+        &self.hints
+    }
+
+    /// Encodes raw image bytes to base64.
+    /// Returns [`OllamaError::UnsupportedImageFormat`] if the bytes are not in one of the formats listed in [`ImageFormat`].
+    pub fn from_bytes(bytes: &[u8]) -> crate::error::Result<Self> {
+        if ImageFormat::detect(bytes).is_none() {
+            return Err(OllamaError::UnsupportedImageFormat);
+        }
+
+        Ok(Self::from_base64(
+            base64::engine::general_purpose::STANDARD.encode(bytes),
+        ))
+    }
+
+    /// Reads an image file and encodes it to base64.
+    /// Returns an error if the file can't be read, or [`OllamaError::UnsupportedImageFormat`] if it is not in one of
+    /// the formats listed in [`ImageFormat`].
+    pub fn from_path(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
     }
 
     pub fn to_base64(&self) -> &str {
-        &self.0
+        &self.data
+    }
+}
+
+/// The image formats accepted by [`Image::from_bytes`] and [`Image::from_path`], the ones Ollama decodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl ImageFormat {
+    /// Detects the format of an image from its magic bytes
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            Some(Self::WebP)
+        } else {
+            None
+        }
     }
 }
@@ -1,6 +1,7 @@
 use schemars::{generate::SchemaSettings, Schema};
 pub use schemars::{schema_for, JsonSchema};
-use serde::{Serialize, Serializer};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
 /// The format to return a response in
 #[derive(Debug, Clone)]
@@ -23,6 +24,23 @@ impl Serialize for FormatType {
     }
 }
 
+impl<'de> Deserialize<'de> for FormatType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::String(s) if s == "json" => Ok(FormatType::Json),
+            value @ Value::Object(_) => serde_json::from_value::<Schema>(value)
+                .map(|schema| {
+                    FormatType::StructuredJson(Box::new(JsonStructure::new_for_schema(schema)))
+                })
+                .map_err(D::Error::custom),
+            other => Err(D::Error::custom(format!("invalid format: {other}"))),
+        }
+    }
+}
+
 /// Represents a serialized JSON schema. You can create this by converting
 /// a JsonSchema:
 /// ```rust
@@ -51,6 +69,60 @@ impl JsonStructure {
     }
 }
 
+/// Used to control whether thinking/reasoning models will think before responding.
+/// Some models (e.g. gpt-oss) accept a thinking level instead of a boolean.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Think {
+    Enabled,
+    Disabled,
+    Low,
+    Medium,
+    High,
+}
+
+impl From<bool> for Think {
+    fn from(think: bool) -> Self {
+        if think {
+            Think::Enabled
+        } else {
+            Think::Disabled
+        }
+    }
+}
+
+impl Serialize for Think {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Think::Enabled => serializer.serialize_bool(true),
+            Think::Disabled => serializer.serialize_bool(false),
+            Think::Low => serializer.serialize_str("low"),
+            Think::Medium => serializer.serialize_str("medium"),
+            Think::High => serializer.serialize_str("high"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Think {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::Bool(think) => Ok(think.into()),
+            Value::String(s) => match s.as_str() {
+                "low" => Ok(Think::Low),
+                "medium" => Ok(Think::Medium),
+                "high" => Ok(Think::High),
+                _ => Err(D::Error::custom(format!("invalid think level: {s}"))),
+            },
+            other => Err(D::Error::custom(format!("invalid think: {other}"))),
+        }
+    }
+}
+
 /// Used to control how long a model stays loaded in memory, by default models are unloaded after 5 minutes of inactivity
 #[derive(Debug, Clone)]
 pub enum KeepAlive {
@@ -77,6 +149,95 @@ impl Serialize for KeepAlive {
     }
 }
 
+/// Accepts what Ollama accepts: a number of seconds (integer or float, possibly as a string) or a Go duration
+/// such as `"5m"` or `"1h30m"`. Negative values keep the model loaded indefinitely. Durations are rounded up
+/// to the second.
+impl<'de> Deserialize<'de> for KeepAlive {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = match Value::deserialize(deserializer)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => parse_duration(&s),
+            _ => None,
+        };
+        let Some(seconds) = seconds.filter(|seconds| seconds.is_finite()) else {
+            return Err(D::Error::custom("invalid keep_alive"));
+        };
+
+        Ok(KeepAlive::from_seconds(seconds))
+    }
+}
+
+impl KeepAlive {
+    fn from_seconds(seconds: f64) -> Self {
+        if seconds < 0.0 {
+            return KeepAlive::Indefinitely;
+        }
+
+        // Round to the nanosecond first so that e.g. `0.1h` doesn't become 361 seconds
+        let seconds = ((seconds * 1e9).round() / 1e9).ceil() as u64;
+        match seconds {
+            0 => KeepAlive::UnloadOnCompletion,
+            s if s % 3600 == 0 => KeepAlive::Until {
+                time: s / 3600,
+                unit: TimeUnit::Hours,
+            },
+            s if s % 60 == 0 => KeepAlive::Until {
+                time: s / 60,
+                unit: TimeUnit::Minutes,
+            },
+            s => KeepAlive::Until {
+                time: s,
+                unit: TimeUnit::Seconds,
+            },
+        }
+    }
+}
+
+/// Parses a number of seconds or a Go duration (e.g. `"1h30m"`, `"-1m"`, `"1.5s"`) to seconds
+fn parse_duration(s: &str) -> Option<f64> {
+    let (sign, mut rest) = match s.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, s.strip_prefix('+').unwrap_or(s)),
+    };
+
+    // Ollama reads a bare number as seconds
+    if let Ok(seconds) = rest.parse::<f64>() {
+        return Some(sign * seconds);
+    }
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let unit_end = rest[number_end..]
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .map_or(rest.len(), |end| number_end + end);
+
+        let number = rest[..number_end].parse::<f64>().ok()?;
+        let unit = match &rest[number_end..unit_end] {
+            "ns" => 1e-9,
+            "us" | "µs" | "μs" => 1e-6,
+            "ms" => 1e-3,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            _ => return None,
+        };
+
+        seconds += number * unit;
+        rest = &rest[unit_end..];
+    }
+
+    Some(sign * seconds)
+}
+
 #[derive(Debug, Clone)]
 pub enum TimeUnit {
     Seconds,
@@ -104,6 +104,9 @@ pub struct ToolFunctionInfo {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ToolCall {
+    /// An identifier for the call, echoed back on the tool message answering it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub function: ToolCallFunction,
 }
 
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+/// Timings and token counts of a completed generation, as reported in the final response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Usage {
+    /// Time spent generating the response, loading and prompt evaluation included
+    pub total_duration: Duration,
+    /// Time spent loading the model
+    pub load_duration: Duration,
+    /// Number of tokens in the prompt
+    pub prompt_eval_count: u64,
+    /// Time spent evaluating the prompt
+    pub prompt_eval_duration: Duration,
+    /// Number of tokens in the response
+    pub eval_count: u64,
+    /// Time spent generating the response tokens
+    pub eval_duration: Duration,
+}
+
+impl Usage {
+    /// The total number of tokens processed, prompt and response included
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_eval_count + self.eval_count
+    }
+
+    /// The response generation speed, in tokens per second
+    pub fn tokens_per_second(&self) -> f64 {
+        rate(self.eval_count, self.eval_duration)
+    }
+
+    /// The prompt evaluation speed, in tokens per second
+    pub fn prompt_tokens_per_second(&self) -> f64 {
+        rate(self.prompt_eval_count, self.prompt_eval_duration)
+    }
+}
+
+fn rate(tokens: u64, duration: Duration) -> f64 {
+    let secs = duration.as_secs_f64();
+    if secs == 0.0 {
+        0.0
+    } else {
+        tokens as f64 / secs
+    }
+}
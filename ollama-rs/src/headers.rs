@@ -25,6 +25,50 @@ impl Ollama {
         ollama
     }
 
+    /// Sets the request headers sent with every request, such as a bearer token or the headers
+    /// required by a reverse proxy in front of Ollama.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The request headers to be used.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.set_headers(Some(headers));
+        self
+    }
+
+    /// Returns a client sending additional headers, for the requests made through it only.
+    /// Headers with the same name as the headers of this client replace them.
+    ///
+    /// The returned client shares the connection pool and caches of this client, so it is cheap
+    /// to create one per request, for instance to route a request to a tenant:
+    ///
+    /// ```no_run
+    /// # async fn example(ollama: ollama_rs::Ollama) -> ollama_rs::error::Result<()> {
+    /// use ollama_rs::headers::{HeaderMap, HeaderValue};
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("X-Tenant", HeaderValue::from_static("acme"));
+    ///
+    /// let models = ollama.override_headers(headers).list_local_models().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn override_headers(&self, headers: HeaderMap) -> Self {
+        let mut ollama = self.clone();
+        for name in headers.keys() {
+            ollama.request_headers.remove(name);
+        }
+        for (name, value) in headers.iter() {
+            ollama.request_headers.append(name.clone(), value.clone());
+        }
+        ollama
+    }
+
+    /// Returns the request headers sent with every request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.request_headers
+    }
+
     /// Sets the request headers for the `Ollama` instance.
     ///
     /// # Arguments
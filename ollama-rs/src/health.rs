@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crate::{
+    error::OllamaError,
+    generation::parameters::{KeepAlive, TimeUnit},
+    models::copy::with_default_tag,
+    transport::EndpointClass,
+    Instant, Ollama,
+};
+
+/// The health of the Ollama server, returned by [`Ollama::health`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// The server answered, in `latency`
+    Healthy { latency: Duration },
+    /// The server answered with an error status, such as a reverse proxy answering `502 Bad Gateway`
+    Unhealthy { status: u16 },
+    /// The server couldn't be reached
+    Unreachable { error: String },
+}
+
+impl HealthStatus {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy { .. })
+    }
+}
+
+/// Whether the Ollama server can serve a model, returned by [`Ollama::ready`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadinessStatus {
+    /// The model is loaded. `already_loaded` is false if the probe loaded it, in `latency`.
+    Ready {
+        already_loaded: bool,
+        latency: Duration,
+    },
+    /// The server isn't healthy, see [`HealthStatus`]
+    NotHealthy(HealthStatus),
+    /// The model isn't pulled on the server
+    ModelNotFound,
+    /// The model failed to load, such as when it doesn't fit in memory
+    LoadFailed { error: String },
+}
+
+impl ReadinessStatus {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready { .. })
+    }
+}
+
+impl Ollama {
+    /// Checks that the server is up, with its heartbeat endpoint at the root of the URL of the service.
+    /// Use [`Ollama::set_request_timeout`] to bound the time of the probe.
+    pub async fn health(&self) -> HealthStatus {
+        let url = self.endpoint_url("");
+        let builder = self.reqwest_client.head(url);
+        let builder = self.with_request_timeout(builder);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let start = Instant::now();
+        match self.send(builder, EndpointClass::Metadata).await {
+            Ok(res) if res.status().is_success() => HealthStatus::Healthy {
+                latency: start.elapsed(),
+            },
+            Ok(res) => HealthStatus::Unhealthy {
+                status: res.status().as_u16(),
+            },
+            Err(e) => HealthStatus::Unreachable {
+                error: e.to_string(),
+            },
+        }
+    }
+
+    /// Checks that the server is up and can serve a model, loading it if it isn't in memory,
+    /// such as for the readiness probe of a service depending on the model.
+    /// A model loaded by the probe stays in memory for 5 minutes, the default of Ollama.
+    pub async fn ready(&self, model_name: String) -> ReadinessStatus {
+        let health = self.health().await;
+        if !health.is_healthy() {
+            return ReadinessStatus::NotHealthy(health);
+        }
+
+        let model_name = self.resolve_model_alias(model_name);
+        let tagged = with_default_tag(&model_name);
+
+        let start = Instant::now();
+        match self.list_running_models().await {
+            Ok(models) if models.iter().any(|m| with_default_tag(&m.name) == tagged) => {
+                return ReadinessStatus::Ready {
+                    already_loaded: true,
+                    latency: start.elapsed(),
+                };
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return ReadinessStatus::NotHealthy(HealthStatus::Unreachable {
+                    error: e.to_string(),
+                })
+            }
+        }
+
+        let keep_alive = KeepAlive::Until {
+            time: 5,
+            unit: TimeUnit::Minutes,
+        };
+        match self.warm_up(model_name, keep_alive).await {
+            Ok(_) => ReadinessStatus::Ready {
+                already_loaded: false,
+                latency: start.elapsed(),
+            },
+            Err(e) if matches!(e.inner(), OllamaError::ModelNotFound(_)) => {
+                ReadinessStatus::ModelNotFound
+            }
+            Err(e) => ReadinessStatus::LoadFailed {
+                error: e.to_string(),
+            },
+        }
+    }
+}
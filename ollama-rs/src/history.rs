@@ -1,6 +1,22 @@
 use std::borrow::Cow;
 
-use crate::generation::chat::ChatMessage;
+use crate::{
+    generation::chat::{
+        idempotency::fingerprint_bytes, request::ChatMessageRequest, ChatMessage, MessageRole,
+    },
+    models::ModelOptions,
+    Ollama,
+};
+
+/// The instruction given to the model by [`Ollama::generate_title`].
+const TITLE_INSTRUCTION: &str = "Write a short title for the conversation below. \
+Reply with the title only, in at most 6 words, without quotes or punctuation at the end.";
+
+/// The maximum number of characters of each message included in the title prompt.
+const TITLE_MAX_MESSAGE_CHARS: usize = 500;
+
+/// The maximum number of messages included in the title prompt, starting from the first one.
+const TITLE_MAX_MESSAGES: usize = 8;
 
 /// A trait for managing chat message history.
 ///
@@ -18,6 +34,78 @@ pub trait ChatHistory {
     /// The messages are returned as a `Cow` (Clone on Write) to allow for
     /// efficient borrowing or cloning as needed.
     fn messages(&self) -> Cow<'_, [ChatMessage]>;
+    /// Shortens the history, keeping the first `len` messages.
+    ///
+    /// The default implementation leaves the history unchanged, for histories that can't drop messages.
+    /// [`drop_last_response`] then reports that there is no response to drop.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of messages to keep. Has no effect if greater than the history length.
+    fn truncate(&mut self, len: usize) {
+        let _ = len;
+    }
+    /// Returns a stable digest of the first `len` messages of the history.
+    ///
+    /// Servers can reuse their prompt cache as long as the beginning of the conversation is unchanged,
+    /// comparing digests tells whether that prefix was edited (e.g. trimmed or rewritten) since the last request.
+    /// See [`messages_digest`] and [`PrefixTracker`].
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of leading messages to include, capped to the history length.
+    fn prefix_digest(&self, len: usize) -> String {
+        let messages = self.messages();
+        messages_digest(&messages[..len.min(messages.len())])
+    }
+}
+
+/// Computes a stable digest of a list of messages.
+///
+/// The digest only depends on the serialized messages, so it is identical across runs and processes.
+pub fn messages_digest(messages: &[ChatMessage]) -> String {
+    let mut bytes = Vec::new();
+    for message in messages {
+        // Serializing a `ChatMessage` can't fail, it only contains strings and JSON values
+        bytes.extend(serde_json::to_vec(message).unwrap_or_default());
+        bytes.push(b'\n');
+    }
+    fingerprint_bytes(&bytes)
+}
+
+/// Tracks the digest of the immutable prefix of a conversation across requests.
+///
+/// The prefix is the conversation as it was at the previous request, i.e. what the server may have cached.
+#[derive(Debug, Clone, Default)]
+pub struct PrefixTracker {
+    last_digest: Option<String>,
+    last_len: usize,
+}
+
+impl PrefixTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the current state of the history and returns whether the previously recorded
+    /// conversation is still an unchanged prefix of it. Always returns `false` the first time.
+    pub fn update<C: ChatHistory + ?Sized>(&mut self, history: &C) -> bool {
+        let unchanged = self
+            .last_digest
+            .as_ref()
+            .is_some_and(|digest| *digest == history.prefix_digest(self.last_len));
+
+        let len = history.messages().len();
+        self.last_digest = Some(history.prefix_digest(len));
+        self.last_len = len;
+
+        unchanged
+    }
+
+    /// The digest recorded by the last call to [`PrefixTracker::update`]
+    pub fn digest(&self) -> Option<&str> {
+        self.last_digest.as_deref()
+    }
 }
 
 impl ChatHistory for Vec<ChatMessage> {
@@ -37,4 +125,85 @@ impl ChatHistory for Vec<ChatMessage> {
     fn messages(&self) -> Cow<'_, [ChatMessage]> {
         Cow::Borrowed(self)
     }
+
+    /// Shortens the history, keeping the first `len` messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of messages to keep. Has no effect if greater than the history length.
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+}
+
+/// Removes the response to the last user message, i.e. every assistant and tool message after it,
+/// so the turn can be generated again.
+///
+/// Returns `false` and leaves the history untouched if it contains no user message,
+/// or if the history doesn't implement [`ChatHistory::truncate`].
+pub fn drop_last_response<C: ChatHistory + ?Sized>(history: &mut C) -> bool {
+    let last_user = history
+        .messages()
+        .iter()
+        .rposition(|m| m.role == MessageRole::User);
+
+    match last_user {
+        Some(index) => {
+            history.truncate(index + 1);
+            history.messages().len() <= index + 1
+        }
+        None => false,
+    }
+}
+
+impl Ollama {
+    /// Generates a short title for a conversation, as commonly shown in chat UIs.
+    ///
+    /// Only the first user and assistant messages are summarized, each truncated, so a small model is enough.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The conversation to give a title to.
+    /// * `model` - The model used to generate the title.
+    pub async fn generate_title<C: ChatHistory>(
+        &self,
+        history: &C,
+        model: String,
+    ) -> crate::error::Result<String> {
+        let transcript = history
+            .messages()
+            .iter()
+            .filter(|m| matches!(m.role, MessageRole::User | MessageRole::Assistant))
+            .take(TITLE_MAX_MESSAGES)
+            .map(|m| {
+                let content: String = m.content.chars().take(TITLE_MAX_MESSAGE_CHARS).collect();
+                format!("{}: {}", m.role.as_str(), content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let request = ChatMessageRequest::new(
+            model,
+            vec![
+                ChatMessage::system(TITLE_INSTRUCTION.to_string()),
+                ChatMessage::user(transcript),
+            ],
+        )
+        .options(ModelOptions::default().temperature(0.2).num_predict(24));
+
+        let res = self.send_chat_messages(request).await?;
+
+        let title = res
+            .message
+            .content
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .unwrap_or_default()
+            .trim_matches(|c: char| c == '"' || c == '\'' || c == '.' || c == '*')
+            .trim()
+            .to_string();
+
+        Ok(title)
+    }
 }
@@ -0,0 +1,79 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{Method, StatusCode};
+use url::Url;
+
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
+
+/// A hook invoked around every request of a client, see [`Ollama::add_interceptor`].
+///
+/// An interceptor can modify the requests, such as adding a refreshed authentication token,
+/// observe their outcome and duration, such as for logging or metrics, or answer them without
+/// sending them, such as for injecting failures in tests.
+///
+/// The hooks are called once per request, around its retries and failovers, in the order
+/// the interceptors were added.
+///
+/// ```
+/// use ollama_rs::{interceptor::{Exchange, Interceptor}, Ollama};
+///
+/// struct Logger;
+///
+/// impl Interceptor for Logger {
+///     fn after_response(&self, exchange: &Exchange<'_>) {
+///         println!("{} {} {:?} in {:?}", exchange.method, exchange.url, exchange.result, exchange.duration);
+///     }
+/// }
+///
+/// let mut ollama = Ollama::default();
+/// ollama.add_interceptor(Logger);
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Called before a request is sent, which can be modified.
+    /// Returning a response skips sending the request and the next interceptors, returning an error aborts it.
+    fn before_request(
+        &self,
+        _request: &mut reqwest::Request,
+        _class: EndpointClass,
+    ) -> crate::error::Result<Option<reqwest::Response>> {
+        Ok(None)
+    }
+
+    /// Called once the headers of the response are received, or the request failed.
+    /// Not called for the requests answered by [`Interceptor::before_request`].
+    fn after_response(&self, _exchange: &Exchange<'_>) {}
+}
+
+impl std::fmt::Debug for dyn Interceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Interceptor")
+    }
+}
+
+/// A request and its outcome, passed to [`Interceptor::after_response`]
+#[derive(Debug)]
+pub struct Exchange<'a> {
+    pub class: EndpointClass,
+    pub method: &'a Method,
+    /// The URL of the request, before any failover to another host
+    pub url: &'a Url,
+    /// The ID of the request, see [`Ollama::set_request_id_header`]
+    pub request_id: Option<&'a str>,
+    /// The status of the response, or the error of the request
+    pub result: Result<StatusCode, &'a OllamaError>,
+    /// The time until the headers of the response were received, including the retries and
+    /// the wait in the limiter
+    pub duration: Duration,
+}
+
+impl Ollama {
+    /// Adds an interceptor invoked around every request, see [`Interceptor`]
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(Arc::new(interceptor));
+    }
+
+    /// Removes all the interceptors
+    pub fn clear_interceptors(&mut self) {
+        self.interceptors.clear();
+    }
+}
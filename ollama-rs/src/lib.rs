@@ -1,7 +1,10 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 use error::OllamaError;
+use generation::chat::idempotency::IdempotencyCache;
+use models::{auto_pull::AutoPullPolicy, cache::ModelCache, registry::RegistryCredentials};
 use url::Url;
+use version::VersionCache;
 
 #[cfg(feature = "macros")]
 pub use ollama_rs_macros::function;
@@ -12,14 +15,75 @@ pub mod re_exports {
     pub use serde;
 }
 
+mod abort;
+pub mod auth;
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub mod backpressure;
+pub mod balance;
+#[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
+#[cfg(all(feature = "sync", not(target_arch = "wasm32")))]
+pub mod blocking;
+pub mod builder;
 pub mod coordinator;
+pub mod defaults;
 pub mod error;
 pub mod generation;
 #[cfg_attr(docsrs, doc(cfg(feature = "headers")))]
 #[cfg(feature = "headers")]
 pub mod headers;
+pub mod health;
 pub mod history;
+pub mod interceptor;
+#[cfg_attr(docsrs, doc(cfg(feature = "rate-limit")))]
+#[cfg(feature = "rate-limit")]
+pub mod limit;
 pub mod models;
+pub mod openai;
+#[cfg(all(feature = "rustls", not(target_arch = "wasm32")))]
+mod pinning;
+mod request_id;
+#[cfg_attr(docsrs, doc(cfg(feature = "retry")))]
+#[cfg(feature = "retry")]
+pub mod retry;
+#[cfg(feature = "tokio")]
+mod runtime;
+#[cfg_attr(docsrs, doc(cfg(feature = "stub")))]
+#[cfg(feature = "stub")]
+pub mod stub;
+mod telemetry;
+mod timeout;
+pub mod transport;
+mod user_agent;
+pub mod version;
+
+pub use user_agent::DEFAULT_USER_AGENT;
+
+// `std::time::Instant` panics in browsers, where the clock comes from `performance.now()`
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// A boxed stream of the crate. The streams are `Send`, except in browsers where the responses
+/// are JavaScript objects tied to the thread that created them.
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub type BoxStream<'a, T> = std::pin::Pin<Box<dyn tokio_stream::Stream<Item = T> + Send + 'a>>;
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(all(feature = "stream", target_arch = "wasm32"))]
+pub type BoxStream<'a, T> = std::pin::Pin<Box<dyn tokio_stream::Stream<Item = T> + 'a>>;
+
+/// `Send` on native targets, and implemented by every type in browsers, see [`BoxStream`]
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+/// `Send` on native targets, and implemented by every type in browsers, see [`BoxStream`]
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
 
 /// A trait to try to convert some type into a [`Url`].
 ///
@@ -93,6 +157,33 @@ pub struct Ollama {
     pub(crate) reqwest_client: reqwest::Client,
     #[cfg(feature = "headers")]
     pub(crate) request_headers: reqwest::header::HeaderMap,
+    pub(crate) idempotency_cache: Option<std::sync::Arc<IdempotencyCache>>,
+    pub(crate) server_version: std::sync::Arc<VersionCache>,
+    pub(crate) auto_pull: Option<std::sync::Arc<AutoPullPolicy>>,
+    pub(crate) model_aliases: std::collections::HashMap<String, String>,
+    pub(crate) defaults: Option<std::sync::Arc<defaults::DefaultParams>>,
+    pub(crate) model_cache: Option<std::sync::Arc<ModelCache>>,
+    pub(crate) registry_credentials: Option<RegistryCredentials>,
+    pub(crate) request_timeout: Option<std::time::Duration>,
+    pub(crate) stream_idle_timeout: Option<std::time::Duration>,
+    #[cfg(feature = "retry")]
+    pub(crate) retry_policies:
+        std::collections::HashMap<transport::EndpointClass, retry::RetryPolicy>,
+    #[cfg(feature = "retry")]
+    pub(crate) retry_budget: Option<std::sync::Arc<retry::RetryBudget>>,
+    #[cfg(feature = "rate-limit")]
+    pub(crate) request_limiter: Option<std::sync::Arc<limit::RequestLimiter>>,
+    #[cfg(feature = "rate-limit")]
+    pub(crate) request_priority: Option<limit::Priority>,
+    pub(crate) host_pool: Option<std::sync::Arc<balance::HostPool>>,
+    pub(crate) interceptors: Vec<std::sync::Arc<dyn interceptor::Interceptor>>,
+    pub(crate) transport: Option<std::sync::Arc<dyn transport::Transport>>,
+    pub(crate) request_id_header: Option<reqwest::header::HeaderName>,
+    pub(crate) user_agent: Option<reqwest::header::HeaderValue>,
+    pub(crate) auth_provider: Option<std::sync::Arc<dyn auth::AuthProvider>>,
+    #[cfg(feature = "stream")]
+    pub(crate) stream_buffer: backpressure::StreamBuffer,
+    pub(crate) abort_registry: std::sync::Arc<abort::AbortRegistry>,
 }
 
 /// The main struct representing an Ollama client.
@@ -146,13 +237,72 @@ impl Ollama {
         url.set_port(Some(port)).unwrap();
 
         Self {
-            url,
+            url: base_url(url),
             reqwest_client,
             #[cfg(feature = "headers")]
             request_headers: reqwest::header::HeaderMap::new(),
+            idempotency_cache: None,
+            server_version: Default::default(),
+            auto_pull: None,
+            model_aliases: Default::default(),
+            defaults: None,
+            model_cache: None,
+            registry_credentials: None,
+            request_timeout: None,
+            stream_idle_timeout: None,
+            #[cfg(feature = "retry")]
+            retry_policies: Default::default(),
+            #[cfg(feature = "retry")]
+            retry_budget: None,
+            #[cfg(feature = "rate-limit")]
+            request_limiter: None,
+            #[cfg(feature = "rate-limit")]
+            request_priority: None,
+            host_pool: None,
+            interceptors: vec![],
+            transport: None,
+            request_id_header: None,
+            user_agent: None,
+            auth_provider: None,
+            #[cfg(feature = "stream")]
+            stream_buffer: Default::default(),
+            abort_registry: Default::default(),
         }
     }
 
+    /// Replaces the HTTP client, for instance with a client sharing the proxy, TLS and connection pool
+    /// settings of the rest of the application.
+    ///
+    /// # Arguments
+    ///
+    /// * `reqwest_client` - The `reqwest` client instance.
+    pub fn with_client(mut self, reqwest_client: reqwest::Client) -> Self {
+        self.reqwest_client = reqwest_client;
+        self
+    }
+
+    /// Replaces the HTTP client with one built from a configured `reqwest` client builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `configure` - Configures the builder, such as `|b| b.pool_max_idle_per_host(4)`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Ollama` instance, or the error of the builder.
+    pub fn with_client_builder(
+        self,
+        configure: impl FnOnce(reqwest::ClientBuilder) -> reqwest::ClientBuilder,
+    ) -> Result<Self, OllamaError> {
+        let reqwest_client = configure(user_agent::client_builder()).build()?;
+        Ok(self.with_client(reqwest_client))
+    }
+
+    /// Returns the HTTP client used for requests.
+    pub fn reqwest_client(&self) -> &reqwest::Client {
+        &self.reqwest_client
+    }
+
     /// Attempts to create a new `Ollama` instance from a URL.
     ///
     /// # Arguments
@@ -167,11 +317,33 @@ impl Ollama {
         Ok(Self::from_url(url.into_url()?))
     }
 
+    /// Creates a new `Ollama` instance connecting over a Unix domain socket, for daemons (or proxies
+    /// in front of them) that don't expose a TCP port.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the socket, such as `/run/ollama/ollama.sock`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new `Ollama` instance or a `OllamaError`.
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "unix-socket"))))]
+    #[cfg(all(unix, feature = "unix-socket"))]
+    pub fn new_unix_socket(path: impl AsRef<std::path::Path>) -> Result<Self, OllamaError> {
+        // The host is only used for the `Host` header, every connection goes through the socket
+        let url = Url::parse("http://localhost")?;
+        let reqwest_client = user_agent::client_builder()
+            .unix_socket(path.as_ref().to_path_buf())
+            .build()?;
+
+        Ok(Self::from_url(url).with_client(reqwest_client))
+    }
+
     /// Create new instance from a [`Url`].
     #[inline]
     pub fn from_url(url: Url) -> Self {
         Self {
-            url,
+            url: base_url(url),
             ..Default::default()
         }
     }
@@ -208,6 +380,25 @@ impl Ollama {
     pub fn url_str(&self) -> &str {
         self.url.as_str()
     }
+
+    /// The URL of an endpoint, such as `api/chat`, under the path of the URL of the service
+    pub(crate) fn endpoint_url(&self, path: &str) -> String {
+        // `base_url` ends the path with a slash
+        format!("{}{path}", self.url_str())
+    }
+}
+
+/// Ends the path of the URL of the service with a slash, so that the endpoints are joined under
+/// its path prefix, such as `https://gateway.example.com/ollama/api/chat` for a reverse proxy
+/// serving Ollama under `/ollama`. The query and the fragment are dropped.
+pub(crate) fn base_url(mut url: Url) -> Url {
+    url.set_query(None);
+    url.set_fragment(None);
+    if !url.cannot_be_a_base() && !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
 }
 
 impl From<Url> for Ollama {
@@ -221,9 +412,37 @@ impl Default for Ollama {
     fn default() -> Self {
         Self {
             url: Url::parse("http://127.0.0.1:11434").unwrap(),
-            reqwest_client: reqwest::Client::new(),
+            reqwest_client: user_agent::client_builder()
+                .build()
+                .expect("Failed to build the HTTP client"),
             #[cfg(feature = "headers")]
             request_headers: reqwest::header::HeaderMap::new(),
+            idempotency_cache: None,
+            server_version: Default::default(),
+            auto_pull: None,
+            model_aliases: Default::default(),
+            defaults: None,
+            model_cache: None,
+            registry_credentials: None,
+            request_timeout: None,
+            stream_idle_timeout: None,
+            #[cfg(feature = "retry")]
+            retry_policies: Default::default(),
+            #[cfg(feature = "retry")]
+            retry_budget: None,
+            #[cfg(feature = "rate-limit")]
+            request_limiter: None,
+            #[cfg(feature = "rate-limit")]
+            request_priority: None,
+            host_pool: None,
+            interceptors: vec![],
+            transport: None,
+            request_id_header: None,
+            user_agent: None,
+            auth_provider: None,
+            #[cfg(feature = "stream")]
+            stream_buffer: Default::default(),
+            abort_registry: Default::default(),
         }
     }
 }
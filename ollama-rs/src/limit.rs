@@ -0,0 +1,280 @@
+use std::{
+    collections::{BinaryHeap, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::oneshot;
+
+use crate::{transport::EndpointClass, Instant, Ollama};
+
+/// Limits the requests sent by a client, so that a burst of requests, such as a web server embedding
+/// or chatting for all its users at once, queues in the client instead of overloading the server.
+///
+/// Waiting requests are served by [`Priority`], then in their order of arrival. A streamed response
+/// keeps its slot of [`RequestLimiter::max_in_flight`] until the stream ends or is dropped.
+/// The limiter is shared by the clones of the client.
+///
+/// ```
+/// use ollama_rs::{limit::{Priority, RequestLimiter}, transport::EndpointClass, Ollama};
+///
+/// let mut ollama = Ollama::default();
+/// ollama.set_request_limiter(Some(
+///     RequestLimiter::new()
+///         .max_in_flight(2)
+///         .max_requests_per_second(10.0)
+///         .priority(EndpointClass::Generation, Priority::Interactive)
+///         .priority(EndpointClass::Embeddings, Priority::Background),
+/// ));
+/// ```
+#[derive(Debug)]
+pub struct RequestLimiter {
+    slots: Option<Arc<Slots>>,
+    interval: Option<Duration>,
+    burst: u32,
+    next_slot: Mutex<Option<Instant>>,
+    priorities: HashMap<EndpointClass, Priority>,
+}
+
+impl Default for RequestLimiter {
+    fn default() -> Self {
+        Self {
+            slots: None,
+            interval: None,
+            burst: 1,
+            next_slot: Mutex::new(None),
+            priorities: HashMap::new(),
+        }
+    }
+}
+
+/// The priority of a request waiting for a slot of [`RequestLimiter::max_in_flight`], so that the
+/// requests of users aren't held back by batch jobs sharing the client.
+///
+/// A waiting request is served before the requests of lower priority, even those that waited longer,
+/// so a steady flow of requests of higher priority holds back the others until it slows down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// Batch work, such as indexing documents
+    Background,
+    #[default]
+    Normal,
+    /// Requests a user is waiting for, such as a chat
+    Interactive,
+}
+
+/// The slots of [`RequestLimiter::max_in_flight`], handed to the waiting requests by priority
+#[derive(Debug)]
+struct Slots {
+    max: usize,
+    state: Mutex<SlotsState>,
+}
+
+#[derive(Debug, Default)]
+struct SlotsState {
+    in_flight: usize,
+    arrivals: u64,
+    waiting: BinaryHeap<Waiter>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    priority: Priority,
+    arrival: u64,
+    slot: oneshot::Sender<Slot>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    // The greatest waiter is served first: the highest priority, then the earliest arrival
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then(other.arrival.cmp(&self.arrival))
+    }
+}
+
+/// A slot of [`RequestLimiter::max_in_flight`], handed to the next waiting request when dropped
+#[derive(Debug)]
+pub(crate) struct Slot(Option<Arc<Slots>>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if let Some(slots) = self.0.take() {
+            slots.release();
+        }
+    }
+}
+
+impl Slots {
+    async fn acquire(self: &Arc<Self>, priority: Priority) -> Option<Slot> {
+        let slot = {
+            let mut state = self.state.lock().unwrap();
+            if state.in_flight < self.max {
+                state.in_flight += 1;
+                return Some(Slot(Some(self.clone())));
+            }
+
+            let (tx, rx) = oneshot::channel();
+            let arrival = state.arrivals;
+            state.arrivals += 1;
+            state.waiting.push(Waiter {
+                priority,
+                arrival,
+                slot: tx,
+            });
+            rx
+        };
+        // A slot handed to a request that stopped waiting is dropped with the channel, and handed on
+        slot.await.ok()
+    }
+
+    fn release(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        while let Some(waiter) = state.waiting.pop() {
+            match waiter.slot.send(Slot(Some(self.clone()))) {
+                Ok(()) => return,
+                // The request stopped waiting, such as when it timed out or was aborted
+                Err(mut slot) => {
+                    slot.0 = None;
+                }
+            }
+        }
+        state.in_flight -= 1;
+    }
+}
+
+impl RequestLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The largest number of requests in flight at once
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.slots = Some(Arc::new(Slots {
+            max: max_in_flight.max(1),
+            state: Default::default(),
+        }));
+        self
+    }
+
+    /// The largest number of requests started per second, evenly spaced
+    pub fn max_requests_per_second(mut self, requests_per_second: f64) -> Self {
+        self.interval = Some(Duration::from_secs_f64(
+            1.0 / requests_per_second.max(f64::EPSILON),
+        ));
+        self
+    }
+
+    /// The number of requests that can start at once after an idle period, within the rate
+    /// of [`RequestLimiter::max_requests_per_second`] (Default: 1)
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// The priority of the requests of an endpoint class waiting for a slot of
+    /// [`RequestLimiter::max_in_flight`] (Default: [`Priority::Normal`]), unless their client sets one
+    /// with [`Ollama::with_priority`]. The requests start at the rate of
+    /// [`RequestLimiter::max_requests_per_second`] in their order of arrival, whatever their priority.
+    pub fn priority(mut self, class: EndpointClass, priority: Priority) -> Self {
+        self.priorities.insert(class, priority);
+        self
+    }
+
+    /// Returns the priority of the requests of an endpoint class
+    pub fn priority_of(&self, class: EndpointClass) -> Priority {
+        self.priorities.get(&class).copied().unwrap_or_default()
+    }
+
+    /// The number of requests currently in flight, if [`RequestLimiter::max_in_flight`] is set
+    pub fn in_flight(&self) -> Option<usize> {
+        let slots = self.slots.as_ref()?;
+        let in_flight = slots.state.lock().unwrap().in_flight;
+        Some(in_flight)
+    }
+
+    /// The number of requests waiting for a slot of [`RequestLimiter::max_in_flight`]
+    pub fn queued(&self) -> usize {
+        self.slots.as_ref().map_or(0, |slots| {
+            let state = slots.state.lock().unwrap();
+            state
+                .waiting
+                .iter()
+                .filter(|waiter| !waiter.slot.is_closed())
+                .count()
+        })
+    }
+
+    /// Waits for the turn of a request, returning its slot of [`RequestLimiter::max_in_flight`] if set
+    pub(crate) async fn acquire(&self, priority: Priority) -> Option<Slot> {
+        if let Some(interval) = self.interval {
+            crate::runtime::sleep_until(self.reserve(interval)).await;
+        }
+
+        match &self.slots {
+            Some(slots) => slots.acquire(priority).await,
+            None => None,
+        }
+    }
+
+    /// Reserves the next slot of the rate, in the order of the calls
+    fn reserve(&self, interval: Duration) -> Instant {
+        let mut next_slot = self.next_slot.lock().unwrap();
+        let now = Instant::now();
+
+        // Up to `burst` slots can be taken right away after an idle period
+        let earliest = now.checked_sub(interval * (self.burst - 1)).unwrap_or(now);
+        let slot = next_slot.map_or(earliest, |next| next.max(earliest));
+        *next_slot = Some(slot + interval);
+
+        slot.max(now)
+    }
+}
+
+impl Ollama {
+    /// Limits the requests of the client, see [`RequestLimiter`]. `None` (the default) disables the limits.
+    pub fn set_request_limiter(&mut self, limiter: Option<RequestLimiter>) {
+        self.request_limiter = limiter.map(Arc::new);
+    }
+
+    /// Returns the request limiter, if any
+    pub fn request_limiter(&self) -> Option<&RequestLimiter> {
+        self.request_limiter.as_deref()
+    }
+
+    /// Returns a clone of the client whose requests wait for the slots of the limiter with a priority,
+    /// instead of the priority of their endpoint class, such as a client handed to a batch job.
+    /// The clone shares the limiter of the client.
+    ///
+    /// ```
+    /// use ollama_rs::{limit::{Priority, RequestLimiter}, Ollama};
+    ///
+    /// let mut ollama = Ollama::default();
+    /// ollama.set_request_limiter(Some(RequestLimiter::new().max_in_flight(2)));
+    /// let indexer = ollama.with_priority(Priority::Background);
+    /// ```
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        let mut ollama = self.clone();
+        ollama.request_priority = Some(priority);
+        ollama
+    }
+
+    /// Returns the priority of the requests of the client, if set with [`Ollama::with_priority`]
+    pub fn request_priority(&self) -> Option<Priority> {
+        self.request_priority
+    }
+}
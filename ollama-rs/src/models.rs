@@ -1,14 +1,30 @@
 /// Modules related to model operations.
 ///
-/// These modules provide functionality for copying, creating, deleting,
+/// These modules provide functionality for uploading blobs, copying, creating, deleting,
 /// listing, pulling, pushing, and showing information about models.
+pub mod alias;
+pub mod auto_pull;
+pub mod blobs;
+pub mod cache;
+pub mod capabilities;
+pub mod cleanup;
+pub mod compare;
 pub mod copy;
 pub mod create;
 pub mod delete;
+pub mod disk_usage;
+pub mod keep_alive;
 pub mod list_local;
+pub mod list_running;
+pub mod modelfile_builder;
 pub mod pull;
 pub mod push;
+pub mod registry;
 pub mod show_info;
+pub mod verify;
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub mod watch;
 
 #[cfg(feature = "modelfile")]
 use modelfile::modelfile::Modelfile;
@@ -16,8 +32,12 @@ use modelfile::modelfile::Modelfile;
 #[cfg(feature = "modelfile")]
 use serde_with;
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::generation::grammar::Grammar;
+
 /// Represents a local model pulled from Ollama.
 ///
 /// This struct contains information about a model that has been pulled
@@ -27,6 +47,28 @@ pub struct LocalModel {
     pub name: String,
     pub modified_at: String,
     pub size: u64,
+    /// The digest of the manifest of the model, which changes when the model is updated
+    #[serde(default)]
+    pub digest: String,
+    #[serde(default)]
+    pub details: ModelDetails,
+}
+
+/// Details about the format and size of a model, as reported by the show, tags and ps endpoints.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelDetails {
+    #[serde(default)]
+    pub parent_model: String,
+    #[serde(default)]
+    pub format: String,
+    #[serde(default)]
+    pub family: String,
+    #[serde(default)]
+    pub families: Option<Vec<String>>,
+    #[serde(default)]
+    pub parameter_size: String,
+    #[serde(default)]
+    pub quantization_level: String,
 }
 
 /// Represents information about a model.
@@ -52,10 +94,15 @@ pub struct ModelInfo {
     pub parameters: String,
     #[serde(default = "String::new")]
     pub template: String,
+    /// The system prompt baked into the model
+    #[serde(default = "String::new")]
+    pub system: String,
     #[serde(default = "serde_json::Map::new")]
     pub model_info: serde_json::Map<String, serde_json::Value>,
     #[serde(default = "Vec::new")]
     pub capabilities: Vec<String>,
+    #[serde(default)]
+    pub details: ModelDetails,
 }
 
 // Options for generation requests to Ollama.
@@ -93,8 +140,15 @@ pub struct ModelOptions {
     pub(super) top_k: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) grammar: Option<Grammar>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) logit_bias: Option<HashMap<u32, f32>>,
 }
 
+/// The bias applied by [`ModelOptions::ban_token`], low enough to never sample the token.
+pub const BANNED_TOKEN_BIAS: f32 = -100.0;
+
 impl ModelOptions {
     /// Enable Mirostat sampling for controlling perplexity. (default: 0, 0 = disabled, 1 = Mirostat, 2 = Mirostat 2.0)
     pub fn mirostat(mut self, mirostat: u8) -> Self {
@@ -191,4 +245,43 @@ impl ModelOptions {
         self.top_p = Some(top_p);
         self
     }
+
+    /// Adds a bias to the likelihood of a token being sampled, from -100 (banned) to 100 (exclusive selection).
+    /// Only honored by servers that accept the `logit_bias` option.
+    pub fn logit_bias(mut self, token_id: u32, bias: f32) -> Self {
+        self.logit_bias
+            .get_or_insert_with(HashMap::new)
+            .insert(token_id, bias);
+        self
+    }
+
+    /// Prevents a token from being sampled. Only honored by servers that accept the `logit_bias` option.
+    pub fn ban_token(self, token_id: u32) -> Self {
+        self.logit_bias(token_id, BANNED_TOKEN_BIAS)
+    }
+
+    /// Prevents the given strings from appearing in the output, by adding them to the stop sequences.
+    /// Generation stops right before a banned string would be produced, which works with every server.
+    pub fn ban_strings<I, S>(mut self, strings: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let stop = self.stop.get_or_insert_with(Vec::new);
+        for s in strings {
+            let s = s.into();
+            if !s.is_empty() && !stop.contains(&s) {
+                stop.push(s);
+            }
+        }
+        self
+    }
+
+    /// A GBNF grammar the output should conform to, sent as is in the `grammar` option.
+    /// Ollama ignores this option, it is only honored by compatible servers that implement it.
+    /// Use [`FormatType`](crate::generation::parameters::FormatType) to constrain the output of Ollama to a JSON schema.
+    pub fn grammar(mut self, grammar: Grammar) -> Self {
+        self.grammar = Some(grammar);
+        self
+    }
 }
@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use crate::Ollama;
+
+impl Ollama {
+    /// Registers an alias for a model, such as `"fast"` for `"llama3.2:3b"`.
+    ///
+    /// Aliases are resolved by every request naming an existing model (generation, chat, embeddings,
+    /// show, pull, push, copy, delete and the OpenAI-compatible endpoints), so the models behind them
+    /// can be swapped through configuration. Names that aren't aliases are sent unchanged.
+    pub fn set_model_alias(&mut self, alias: impl Into<String>, model_name: impl Into<String>) {
+        self.model_aliases.insert(alias.into(), model_name.into());
+    }
+
+    /// Removes an alias, returning the model it resolved to
+    pub fn remove_model_alias(&mut self, alias: &str) -> Option<String> {
+        self.model_aliases.remove(alias)
+    }
+
+    /// Returns the registered aliases and the models they resolve to
+    pub fn model_aliases(&self) -> &HashMap<String, String> {
+        &self.model_aliases
+    }
+
+    /// Returns the model an alias resolves to, or the name unchanged if it isn't an alias.
+    /// Aliases are resolved once, an alias of an alias isn't followed.
+    pub fn resolve_model_alias(&self, model_name: String) -> String {
+        match self.model_aliases.get(&model_name) {
+            Some(resolved) => resolved.clone(),
+            None => model_name,
+        }
+    }
+}
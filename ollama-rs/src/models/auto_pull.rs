@@ -0,0 +1,113 @@
+#[cfg(feature = "stream")]
+use std::fmt;
+use std::{future::Future, sync::Arc};
+
+use crate::{error::OllamaError, Ollama};
+
+#[cfg(feature = "stream")]
+use super::pull::PullProgress;
+
+#[cfg(feature = "stream")]
+#[derive(Clone)]
+struct PullProgressCallback(Arc<dyn Fn(&PullProgress) + Send + Sync>);
+
+#[cfg(feature = "stream")]
+impl fmt::Debug for PullProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PullProgressCallback")
+    }
+}
+
+/// Pulls missing models on demand, see [`Ollama::set_auto_pull`].
+///
+/// When [`Ollama::generate`] or [`Ollama::send_chat_messages`] fails because the model isn't
+/// installed, the model is pulled and the request is retried once.
+#[derive(Debug, Clone, Default)]
+pub struct AutoPullPolicy {
+    allow_insecure: bool,
+    #[cfg(feature = "stream")]
+    on_progress: Option<PullProgressCallback>,
+}
+
+impl AutoPullPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow insecure connections to the library. Only use this if you are pulling from your own library during development.
+    pub fn allow_insecure(mut self, allow_insecure: bool) -> Self {
+        self.allow_insecure = allow_insecure;
+        self
+    }
+
+    /// Calls `callback` with the progress of every pull, for instance to log it
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub fn on_progress(mut self, callback: impl Fn(&PullProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(PullProgressCallback(Arc::new(callback)));
+        self
+    }
+}
+
+/// Whether an error returned by the server means that the model isn't installed,
+/// such as `model "llama3.2" not found, try pulling it first`
+pub(crate) fn is_model_not_found(error: &OllamaError) -> bool {
+    matches!(error.inner(), OllamaError::ModelNotFound(_))
+}
+
+impl Ollama {
+    /// Pulls missing models when a generation or chat request fails because the model isn't installed,
+    /// then retries the request once. `None` disables the policy, which is the default.
+    ///
+    /// Streaming requests are not retried.
+    pub fn set_auto_pull(&mut self, policy: Option<AutoPullPolicy>) {
+        self.auto_pull = policy.map(Arc::new);
+    }
+
+    /// Returns the auto-pull policy, if enabled
+    pub fn auto_pull(&self) -> Option<&AutoPullPolicy> {
+        self.auto_pull.as_deref()
+    }
+
+    /// Runs `request`, pulling `model_name` and running it again if it fails because the model is missing
+    pub(crate) async fn with_auto_pull<T, F, Fut>(
+        &self,
+        model_name: &str,
+        request: F,
+    ) -> crate::error::Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = crate::error::Result<T>>,
+    {
+        let policy = match (request().await, &self.auto_pull) {
+            (Err(e), Some(policy)) if is_model_not_found(&e) => policy.clone(),
+            (res, _) => return res,
+        };
+
+        self.pull_with_policy(model_name, &policy).await?;
+        request().await
+    }
+
+    async fn pull_with_policy(
+        &self,
+        model_name: &str,
+        policy: &AutoPullPolicy,
+    ) -> crate::error::Result<()> {
+        #[cfg(feature = "stream")]
+        if let Some(PullProgressCallback(callback)) = &policy.on_progress {
+            use tokio_stream::StreamExt;
+
+            let mut stream = self
+                .pull_model_progress_stream(model_name.to_string(), policy.allow_insecure)
+                .await?;
+            while let Some(progress) = stream.next().await {
+                callback(&progress?);
+            }
+            return Ok(());
+        }
+
+        self.pull_model(model_name.to_string(), policy.allow_insecure)
+            .await?;
+        Ok(())
+    }
+}
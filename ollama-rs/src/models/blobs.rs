@@ -0,0 +1,129 @@
+use sha2::{Digest, Sha256};
+
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
+
+/// Computes the digest of some data in the form expected by the blob API, `sha256:<hex>`
+pub fn sha256_digest(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+impl Ollama {
+    /// Check whether a blob exists on the server.
+    /// - `digest` - The SHA256 digest of the blob, in the form `sha256:<hex>`.
+    pub async fn blob_exists(&self, digest: &str) -> crate::error::Result<bool> {
+        let url = self.endpoint_url(&format!("api/blobs/{digest}"));
+        let builder = self.reqwest_client.head(url);
+        let builder = self.with_request_timeout(builder);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self.send(builder, EndpointClass::Metadata).await?;
+
+        match res.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => Err(OllamaError::from_response_with(res, |_| {
+                OllamaError::Other(format!("Failed to check blob {digest}: {status}"))
+            })
+            .await),
+        }
+    }
+
+    /// Upload a blob held in memory, to create a model from it with [`CreateModelRequest::files`](super::create::CreateModelRequest::files).
+    /// Returns the digest of the blob.
+    pub async fn create_blob(&self, bytes: Vec<u8>) -> crate::error::Result<String> {
+        let digest = sha256_digest(&bytes);
+
+        if !self.blob_exists(&digest).await? {
+            self.upload_blob(&digest, reqwest::Body::from(bytes))
+                .await?;
+        }
+
+        Ok(digest)
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+    /// Upload a file as a blob, such as a GGUF or safetensors file, without loading it in memory.
+    /// The file is read twice: once to compute its digest, then to upload it if the server doesn't have it yet.
+    /// Returns the digest of the blob.
+    pub async fn create_blob_from_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> crate::error::Result<String> {
+        use tokio::io::AsyncReadExt;
+
+        let path = path.as_ref();
+
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0; 1 << 20];
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        let digest = format!("sha256:{:x}", hasher.finalize());
+
+        if !self.blob_exists(&digest).await? {
+            let file = tokio::fs::File::open(path).await?;
+            self.create_blob_from_reader(&digest, file).await?;
+        }
+
+        Ok(digest)
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+    /// Upload a blob from a reader, streaming it to the server.
+    /// - `digest` - The SHA256 digest of the data, in the form `sha256:<hex>`. The server rejects the blob if it doesn't match.
+    /// - `reader` - The data to upload.
+    pub async fn create_blob_from_reader<R>(
+        &self,
+        digest: &str,
+        mut reader: R,
+    ) -> crate::error::Result<()>
+    where
+        R: tokio::io::AsyncRead + Unpin + Send + 'static,
+    {
+        use tokio::io::AsyncReadExt;
+
+        let stream = async_stream::stream! {
+            let mut buf = vec![0; 1 << 20];
+            loop {
+                match reader.read(&mut buf).await {
+                    Ok(0) => break,
+                    Ok(n) => yield Ok::<_, std::io::Error>(buf[..n].to_vec()),
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                }
+            }
+        };
+
+        self.upload_blob(digest, reqwest::Body::wrap_stream(stream))
+            .await
+    }
+
+    async fn upload_blob(&self, digest: &str, body: reqwest::Body) -> crate::error::Result<()> {
+        let url = self.endpoint_url(&format!("api/blobs/{digest}"));
+        let builder = self.reqwest_client.post(url);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self
+            .send(builder.body(body), EndpointClass::Management)
+            .await?;
+
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(OllamaError::from_response(res).await)
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use crate::{Instant, Ollama};
+
+use super::{LocalModel, ModelInfo};
+
+/// A cache of the responses of the show and list endpoints, so that frequent checks such as
+/// [`Ollama::model_capabilities`] don't query the server every time.
+///
+/// Enabled with [`Ollama::set_model_cache_ttl`]. Entries expire after `ttl`, and the whole cache is
+/// invalidated when this client creates, copies, pulls or deletes a model. Changes made by other
+/// clients are only seen once the entries expire.
+#[derive(Debug)]
+pub struct ModelCache {
+    ttl: Duration,
+    info: Mutex<HashMap<String, (Instant, ModelInfo)>>,
+    local_models: Mutex<Option<(Instant, Vec<LocalModel>)>>,
+}
+
+impl ModelCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            info: Mutex::new(HashMap::new()),
+            local_models: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached show response for the model, if it hasn't expired
+    pub fn model_info(&self, model_name: &str) -> Option<ModelInfo> {
+        let info = self.info.lock().unwrap();
+        info.get(model_name)
+            .filter(|(cached_at, _)| cached_at.elapsed() < self.ttl)
+            .map(|(_, info)| info.clone())
+    }
+
+    /// Returns the cached list of local models, if it hasn't expired
+    pub fn local_models(&self) -> Option<Vec<LocalModel>> {
+        let local_models = self.local_models.lock().unwrap();
+        local_models
+            .as_ref()
+            .filter(|(cached_at, _)| cached_at.elapsed() < self.ttl)
+            .map(|(_, models)| models.clone())
+    }
+
+    pub fn insert_model_info(&self, model_name: String, info: ModelInfo) {
+        let mut cached = self.info.lock().unwrap();
+        cached.retain(|_, (cached_at, _)| cached_at.elapsed() < self.ttl);
+        cached.insert(model_name, (Instant::now(), info));
+    }
+
+    pub fn insert_local_models(&self, models: Vec<LocalModel>) {
+        *self.local_models.lock().unwrap() = Some((Instant::now(), models));
+    }
+
+    /// Removes every cached response
+    pub fn clear(&self) {
+        self.info.lock().unwrap().clear();
+        *self.local_models.lock().unwrap() = None;
+    }
+}
+
+impl Ollama {
+    /// Enables the client-side cache of model metadata, see [`ModelCache`].
+    /// Responses are reused for `ttl`. `None` disables the cache.
+    pub fn set_model_cache_ttl(&mut self, ttl: Option<Duration>) {
+        self.model_cache = ttl.map(|ttl| std::sync::Arc::new(ModelCache::new(ttl)));
+    }
+
+    /// Returns the model metadata cache, if enabled
+    pub fn model_cache(&self) -> Option<&ModelCache> {
+        self.model_cache.as_deref()
+    }
+
+    /// Show details about a model like [`Ollama::show_model_info`], reusing a cached response if the cache is enabled.
+    pub async fn cached_model_info(&self, model_name: String) -> crate::error::Result<ModelInfo> {
+        let model_name = self.resolve_model_alias(model_name);
+        let Some(cache) = &self.model_cache else {
+            return self.show_model_info(model_name).await;
+        };
+
+        if let Some(info) = cache.model_info(&model_name) {
+            return Ok(info);
+        }
+
+        let info = self.show_model_info(model_name.clone()).await?;
+        cache.insert_model_info(model_name, info.clone());
+        Ok(info)
+    }
+
+    /// List the local models like [`Ollama::list_local_models`], reusing a cached response if the cache is enabled.
+    pub async fn cached_local_models(&self) -> crate::error::Result<Vec<LocalModel>> {
+        let Some(cache) = &self.model_cache else {
+            return self.list_local_models().await;
+        };
+
+        if let Some(models) = cache.local_models() {
+            return Ok(models);
+        }
+
+        let models = self.list_local_models().await?;
+        cache.insert_local_models(models.clone());
+        Ok(models)
+    }
+
+    /// Invalidates the cached model metadata after a change to the local models
+    pub(crate) fn invalidate_model_cache(&self) {
+        if let Some(cache) = &self.model_cache {
+            cache.clear();
+        }
+    }
+}
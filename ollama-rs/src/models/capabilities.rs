@@ -0,0 +1,98 @@
+use crate::{error::OllamaError, Ollama};
+
+use super::ModelInfo;
+
+/// What a model can do, as reported by the show endpoint.
+///
+/// Servers too old to report capabilities get them inferred from the model families, architecture and template.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    pub completion: bool,
+    pub tools: bool,
+    pub vision: bool,
+    pub embedding: bool,
+    pub thinking: bool,
+    /// Fill-in-the-middle completion with a suffix
+    pub insert: bool,
+}
+
+impl ModelCapabilities {
+    pub fn supports_tools(&self) -> bool {
+        self.tools
+    }
+
+    pub fn supports_vision(&self) -> bool {
+        self.vision
+    }
+
+    pub fn supports_thinking(&self) -> bool {
+        self.thinking
+    }
+
+    pub fn is_embedding_model(&self) -> bool {
+        self.embedding
+    }
+
+    fn infer(info: &ModelInfo) -> Self {
+        let families = info.details.families.as_deref().unwrap_or_default();
+        let architecture = info
+            .info("general.architecture")
+            .and_then(|a| a.as_str())
+            .unwrap_or_default();
+
+        let embedding = architecture.contains("bert");
+        let vision = families.iter().any(|f| f == "clip" || f == "mllama")
+            || info.model_info.keys().any(|k| k.contains(".vision."));
+
+        Self {
+            completion: !embedding,
+            tools: info.template.contains(".Tools"),
+            vision,
+            embedding,
+            thinking: info.template.contains(".Think"),
+            insert: info.template.contains(".Suffix"),
+        }
+    }
+}
+
+impl From<&ModelInfo> for ModelCapabilities {
+    fn from(info: &ModelInfo) -> Self {
+        if info.capabilities.is_empty() {
+            return Self::infer(info);
+        }
+
+        let has = |capability: &str| info.capabilities.iter().any(|c| c == capability);
+        Self {
+            completion: has("completion"),
+            tools: has("tools"),
+            vision: has("vision"),
+            embedding: has("embedding"),
+            thinking: has("thinking"),
+            insert: has("insert"),
+        }
+    }
+}
+
+impl Ollama {
+    /// Fetch what a model can do, see [`ModelCapabilities`].
+    /// The show response is reused if the [model cache](crate::models::cache::ModelCache) is enabled.
+    pub async fn model_capabilities(
+        &self,
+        model_name: String,
+    ) -> crate::error::Result<ModelCapabilities> {
+        let info = self.cached_model_info(model_name).await?;
+        Ok(ModelCapabilities::from(&info))
+    }
+
+    /// Fail with [`OllamaError::MissingCapability`] unless the model supports tool calling.
+    pub async fn require_tools(&self, model_name: String) -> crate::error::Result<()> {
+        if self.model_capabilities(model_name.clone()).await?.tools {
+            Ok(())
+        } else {
+            Err(OllamaError::MissingCapability {
+                model: model_name,
+                capability: "tools".to_string(),
+            })
+        }
+    }
+}
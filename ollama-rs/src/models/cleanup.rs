@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::Ollama;
+
+use super::LocalModel;
+
+/// Matches a model name against a glob pattern, where `*` matches any sequence of characters
+/// and `?` matches a single character, such as `llama3*:*-q4_*`
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` in the pattern, and of the name when it was reached
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` match one more character
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl LocalModel {
+    /// Parses the modification date of the model, `None` if the server returned an invalid date
+    pub fn modified_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.modified_at)
+            .ok()
+            .map(|d| d.to_utc())
+    }
+}
+
+impl Ollama {
+    /// Delete every local model whose name matches a glob pattern, see [`glob_match`].
+    /// - `pattern` - The pattern, such as `testmodel-*` or `*:*-fp16`.
+    /// - `dry_run` - Only return the models that would be deleted, without deleting them.
+    ///
+    /// Returns the deleted models.
+    pub async fn delete_models_matching(
+        &self,
+        pattern: &str,
+        dry_run: bool,
+    ) -> crate::error::Result<Vec<LocalModel>> {
+        let models = self
+            .list_local_models()
+            .await?
+            .into_iter()
+            .filter(|m| glob_match(pattern, &m.name))
+            .collect::<Vec<_>>();
+
+        self.delete_models(models, dry_run).await
+    }
+
+    /// Delete every local model not modified for `older_than`, except the models currently loaded.
+    /// Models with an invalid modification date are kept.
+    /// - `dry_run` - Only return the models that would be deleted, without deleting them.
+    ///
+    /// Returns the deleted models.
+    pub async fn prune_unused(
+        &self,
+        older_than: Duration,
+        dry_run: bool,
+    ) -> crate::error::Result<Vec<LocalModel>> {
+        let cutoff = TimeDelta::from_std(older_than)
+            .ok()
+            .and_then(|age| Utc::now().checked_sub_signed(age));
+        let Some(cutoff) = cutoff else {
+            return Ok(vec![]);
+        };
+        let running = self.list_running_models().await?;
+
+        let models = self
+            .list_local_models()
+            .await?
+            .into_iter()
+            .filter(|m| m.modified_at().is_some_and(|d| d < cutoff))
+            .filter(|m| !running.iter().any(|r| r.name == m.name))
+            .collect::<Vec<_>>();
+
+        self.delete_models(models, dry_run).await
+    }
+
+    async fn delete_models(
+        &self,
+        models: Vec<LocalModel>,
+        dry_run: bool,
+    ) -> crate::error::Result<Vec<LocalModel>> {
+        if !dry_run {
+            for model in &models {
+                self.delete_model(model.name.clone()).await?;
+            }
+        }
+
+        Ok(models)
+    }
+}
@@ -0,0 +1,90 @@
+use crate::Ollama;
+
+/// A property of two compared models
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference<T> {
+    pub a: T,
+    pub b: T,
+}
+
+impl<T: PartialEq> Difference<T> {
+    fn new(a: T, b: T) -> Self {
+        Self { a, b }
+    }
+
+    pub fn is_different(&self) -> bool {
+        self.a != self.b
+    }
+}
+
+/// The differences between two models, see [`Ollama::compare_models`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelComparison {
+    pub a: String,
+    pub b: String,
+    pub architecture: Difference<Option<String>>,
+    pub parameter_count: Difference<Option<u64>>,
+    /// The quantization of the weights, such as `Q4_K_M`
+    pub quantization_level: Difference<Option<String>>,
+    pub context_length: Difference<Option<u64>>,
+    pub embedding_length: Difference<Option<u64>>,
+    pub template: Difference<String>,
+    pub system: Difference<String>,
+    /// The parameters baked into the models, as returned by show
+    pub parameters: Difference<String>,
+}
+
+impl ModelComparison {
+    /// The names of the properties that differ, such as `quantization_level`
+    pub fn differences(&self) -> Vec<&'static str> {
+        [
+            ("architecture", self.architecture.is_different()),
+            ("parameter_count", self.parameter_count.is_different()),
+            ("quantization_level", self.quantization_level.is_different()),
+            ("context_length", self.context_length.is_different()),
+            ("embedding_length", self.embedding_length.is_different()),
+            ("template", self.template.is_different()),
+            ("system", self.system.is_different()),
+            ("parameters", self.parameters.is_different()),
+        ]
+        .into_iter()
+        .filter(|(_, different)| *different)
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    /// Whether the models only differ by the quantization of their weights, such as two tags of the same model
+    pub fn only_quantization_differs(&self) -> bool {
+        self.differences() == ["quantization_level"]
+    }
+}
+
+impl Ollama {
+    /// Compare two local models, for instance two quantizations of the same model, from their show responses.
+    pub async fn compare_models(
+        &self,
+        a: String,
+        b: String,
+    ) -> crate::error::Result<ModelComparison> {
+        let info_a = self.cached_model_info(a.clone()).await?;
+        let info_b = self.cached_model_info(b.clone()).await?;
+
+        let (meta_a, meta_b) = (info_a.metadata(), info_b.metadata());
+
+        Ok(ModelComparison {
+            a,
+            b,
+            architecture: Difference::new(meta_a.architecture, meta_b.architecture),
+            parameter_count: Difference::new(meta_a.parameter_count, meta_b.parameter_count),
+            quantization_level: Difference::new(
+                meta_a.quantization_level,
+                meta_b.quantization_level,
+            ),
+            context_length: Difference::new(meta_a.context_length, meta_b.context_length),
+            embedding_length: Difference::new(meta_a.embedding_length, meta_b.embedding_length),
+            template: Difference::new(info_a.template, info_b.template),
+            system: Difference::new(info_a.system, info_b.system),
+            parameters: Difference::new(info_a.parameters, info_b.parameters),
+        })
+    }
+}
@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
 
 impl Ollama {
     /// Copy a model. Creates a model with another name from an existing model.
@@ -10,24 +10,61 @@ impl Ollama {
         destination: String,
     ) -> crate::error::Result<()> {
         let request = CopyModelRequest {
-            source,
+            source: self.resolve_model_alias(source),
             destination,
         };
 
-        let url = format!("{}api/copy", self.url_str());
+        let url = self.endpoint_url("api/copy");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(&request), EndpointClass::Management)
+            .await?;
 
         if res.status().is_success() {
+            self.invalidate_model_cache();
             Ok(())
         } else {
-            Err(OllamaError::Other(res.text().await?))
+            Err(OllamaError::from_response(res).await)
         }
     }
+
+    /// Copy a model, refusing to replace an existing model unless `overwrite` is set.
+    ///
+    /// Returns [`OllamaError::ModelAlreadyExists`] if the destination exists and `overwrite` is false,
+    /// and [`OllamaError::ModelNotFound`] if the source doesn't exist.
+    pub async fn copy_model_safe(
+        &self,
+        source: String,
+        destination: String,
+        overwrite: bool,
+    ) -> crate::error::Result<()> {
+        let source = self.resolve_model_alias(source);
+        if !self.model_exists(&source).await? {
+            return Err(OllamaError::ModelNotFound(source));
+        }
+        if !overwrite && self.model_exists(&destination).await? {
+            return Err(OllamaError::ModelAlreadyExists(destination));
+        }
+
+        self.copy_model(source, destination).await
+    }
+
+    /// Check whether a model is installed, as listed by [`Ollama::list_local_models`].
+    /// Names without a tag refer to the `latest` tag.
+    pub async fn model_exists(&self, model_name: &str) -> crate::error::Result<bool> {
+        let model_name = with_default_tag(&self.resolve_model_alias(model_name.to_string()));
+
+        Ok(self
+            .list_local_models()
+            .await?
+            .iter()
+            .any(|m| with_default_tag(&m.name) == model_name))
+    }
 }
 
 /// A copy model request to Ollama.
@@ -36,3 +73,13 @@ struct CopyModelRequest {
     source: String,
     destination: String,
 }
+
+/// Appends the `latest` tag to a model name without a tag, such as `host:port/model`
+pub(crate) fn with_default_tag(model_name: &str) -> String {
+    let name = model_name.rsplit('/').next().unwrap_or(model_name);
+    if name.contains(':') {
+        model_name.to_string()
+    } else {
+        format!("{model_name}:latest")
+    }
+}
@@ -1,15 +1,14 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OllamaError, generation::chat::ChatMessage, Ollama};
+use crate::{error::OllamaError, generation::chat::ChatMessage, transport::EndpointClass, Ollama};
 
 use super::ModelOptions;
 
 /// A stream of `CreateModelStatus` objects
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
-pub type CreateModelStatusStream = std::pin::Pin<
-    Box<dyn tokio_stream::Stream<Item = crate::error::Result<CreateModelStatus>> + Send>,
->;
+pub type CreateModelStatusStream =
+    crate::BoxStream<'static, crate::error::Result<CreateModelStatus>>;
 
 impl Ollama {
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
@@ -24,65 +23,188 @@ impl Ollama {
         use crate::error::OllamaError;
 
         request.stream = true;
+        request.from_model = request.from_model.map(|m| self.resolve_model_alias(m));
 
-        let url = format!("{}api/create", self.url_str());
+        self.require_version(crate::version::STRUCTURED_CREATE_VERSION, "Model creation")
+            .await?;
+
+        let url = self.endpoint_url("api/create");
         let builder = self.reqwest_client.post(url);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send_stream(builder.json(&request), EndpointClass::Management)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response(res).await);
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
+        let model_cache = self.model_cache.clone();
+        let chunks =
+            crate::timeout::response_chunks(res, self.stream_idle_timeout, self.stream_buffer);
+        let stream = Box::new(chunks.map(move |res| match res {
             Ok(bytes) => {
                 let res = serde_json::from_slice::<CreateModelStatus>(&bytes);
                 match res {
-                    Ok(res) => Ok(res),
-                    Err(e) => {
-                        let err =
-                            serde_json::from_slice::<crate::error::InternalOllamaError>(&bytes);
-                        match err {
-                            Ok(err) => Err(OllamaError::InternalError(err)),
-                            Err(_) => Err(OllamaError::from(e)),
+                    Ok(res) => {
+                        if let (Some(cache), true) = (&model_cache, res.is_success()) {
+                            cache.clear();
                         }
+                        Ok(res)
                     }
+                    Err(e) => Err(OllamaError::from_stream_line(&bytes, e)),
                 }
             }
-            Err(e) => Err(OllamaError::Other(format!("Failed to read response: {e}"))),
+            Err(e) => Err(e),
         }));
 
         Ok(std::pin::Pin::from(stream))
     }
 
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+    /// Create a model from a local GGUF file or safetensors directory, streaming the creation statuses.
+    /// The files are uploaded as blobs unless the server already has them, then added to the request.
+    /// - `request` - The create request, with the name of the model and any other options such as a template or quantization.
+    /// - `path` - A GGUF file, or a directory. Only the model files of the directory are uploaded (the GGUF and safetensors
+    ///   weights and the config and tokenizer JSON files, but no hidden file), along with the paths referenced by the `FROM`
+    ///   and `ADAPTER` instructions of its `Modelfile`, if any.
+    pub async fn create_model_from_file(
+        &self,
+        mut request: CreateModelRequest,
+        path: impl AsRef<std::path::Path>,
+    ) -> crate::error::Result<CreateModelStatusStream> {
+        let path = path.as_ref();
+
+        // Keyed by file name, the name the server sees
+        let mut files = std::collections::BTreeMap::new();
+        let mut adapters = std::collections::BTreeMap::new();
+        if tokio::fs::metadata(path).await?.is_dir() {
+            files.extend(model_files(path).await?);
+
+            if let Ok(modelfile) = tokio::fs::read_to_string(path.join("Modelfile")).await {
+                for from in super::show_info::modelfile_instructions(&modelfile, "FROM") {
+                    files.extend(referenced_files(&path.join(from.trim_matches('"'))).await?);
+                }
+                for adapter in super::show_info::modelfile_instructions(&modelfile, "ADAPTER") {
+                    adapters.extend(referenced_files(&path.join(adapter.trim_matches('"'))).await?);
+                }
+                files.retain(|name, _| !adapters.contains_key(name));
+            }
+        } else {
+            files.extend(file_name(path));
+        }
+
+        for (name, path) in files {
+            let digest = self.create_blob_from_file(&path).await?;
+            request = request.file(name, digest);
+        }
+        for (name, path) in adapters {
+            let digest = self.create_blob_from_file(&path).await?;
+            request = request.adapter(name, digest);
+        }
+
+        self.create_model_stream(request).await
+    }
+
     /// Create a model with a single response, only the final status will be returned.
     pub async fn create_model(
         &self,
-        request: CreateModelRequest,
+        mut request: CreateModelRequest,
     ) -> crate::error::Result<CreateModelStatus> {
-        let url = format!("{}api/create", self.url_str());
+        request.from_model = request.from_model.map(|m| self.resolve_model_alias(m));
+
+        self.require_version(crate::version::STRUCTURED_CREATE_VERSION, "Model creation")
+            .await?;
+
+        let url = self.endpoint_url("api/create");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(&request), EndpointClass::Management)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response(res).await);
         }
 
         let res = res.bytes().await?;
         let res = serde_json::from_slice::<CreateModelStatus>(&res)?;
+        self.invalidate_model_cache();
 
         Ok(res)
     }
 }
 
-#[derive(Serialize)]
+/// The files Ollama reads from a safetensors directory besides the weights
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+const MODEL_CONFIG_FILES: &[&str] = &[
+    "config.json",
+    "generation_config.json",
+    "tokenizer.json",
+    "tokenizer_config.json",
+    "tokenizer.model",
+    "special_tokens_map.json",
+    "added_tokens.json",
+];
+
+/// Whether a file of a model directory is uploaded by [`Ollama::create_model_from_file`]:
+/// the GGUF and safetensors weights and the config and tokenizer files, but no hidden file
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+fn is_model_file(path: &std::path::Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let extension = path.extension().and_then(|e| e.to_str());
+
+    !name.starts_with('.')
+        && (MODEL_CONFIG_FILES.contains(&name) || matches!(extension, Some("gguf" | "safetensors")))
+}
+
+/// The model files of a directory, by file name
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+async fn model_files(
+    dir: &std::path::Path,
+) -> crate::error::Result<Vec<(String, std::path::PathBuf)>> {
+    let mut files = vec![];
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if entry.file_type().await?.is_file() && is_model_file(&path) {
+            files.extend(file_name(&path));
+        }
+    }
+    Ok(files)
+}
+
+/// The files of a path referenced by a modelfile: the file itself, or the model files of a directory.
+/// Paths that don't exist, such as the name of a model, have none.
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+async fn referenced_files(
+    path: &std::path::Path,
+) -> crate::error::Result<Vec<(String, std::path::PathBuf)>> {
+    match tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.is_dir() => model_files(path).await,
+        Ok(_) => Ok(file_name(path).into_iter().collect()),
+        Err(_) => Ok(vec![]),
+    }
+}
+
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+fn file_name(path: &std::path::Path) -> Option<(String, std::path::PathBuf)> {
+    let name = path.file_name()?.to_str()?;
+    Some((name.to_string(), path.to_path_buf()))
+}
+
+/// A quantization level for [`CreateModelRequest::quantize`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum QuantizationType {
     #[serde(rename = "q2_K")]
     Q2K,
@@ -115,13 +237,16 @@ pub enum QuantizationType {
 }
 
 /// A create model request to Ollama.
-#[derive(Serialize)]
+///
+/// A model is created either from an existing model with [`CreateModelRequest::from_model`],
+/// or from uploaded blobs (GGUF or safetensors files) with [`CreateModelRequest::files`].
+#[derive(Debug, Clone, Serialize)]
 pub struct CreateModelRequest {
     /// Name of the model to create
     #[serde(rename = "model")]
     model_name: String,
     /// Name of an existing model to create the new model from
-    #[serde(rename = "from")]
+    #[serde(rename = "from", skip_serializing_if = "Option::is_none")]
     from_model: Option<String>,
     /// A dictionary of file names to SHA256 digests of blobs to create the model from
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -177,11 +302,31 @@ impl CreateModelRequest {
         self
     }
 
+    /// Adds a single file to create the model from, see [`CreateModelRequest::files`]
+    /// * `name` - The file name, such as `model.gguf`
+    /// * `digest` - The SHA256 digest of the uploaded blob, such as `sha256:...`
+    pub fn file(mut self, name: String, digest: String) -> Self {
+        self.files
+            .get_or_insert_with(Default::default)
+            .insert(name, digest);
+        self
+    }
+
     pub fn adapters(mut self, adapters: std::collections::HashMap<String, String>) -> Self {
         self.adapters = Some(adapters);
         self
     }
 
+    /// Adds a single LORA adapter, see [`CreateModelRequest::adapters`]
+    /// * `name` - The file name, such as `adapter.safetensors`
+    /// * `digest` - The SHA256 digest of the uploaded blob, such as `sha256:...`
+    pub fn adapter(mut self, name: String, digest: String) -> Self {
+        self.adapters
+            .get_or_insert_with(Default::default)
+            .insert(name, digest);
+        self
+    }
+
     pub fn template(mut self, template: String) -> Self {
         self.template = Some(template);
         self
@@ -219,8 +364,31 @@ impl CreateModelRequest {
 }
 
 /// A create model status response from Ollama.
-#[derive(Deserialize, Debug)]
+///
+/// Long steps such as quantization also report their progress in bytes.
+#[derive(Deserialize, Debug, Clone)]
 pub struct CreateModelStatus {
     #[serde(rename = "status")]
     pub message: String,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+}
+
+impl CreateModelStatus {
+    /// Whether the model was created successfully, which is the last status of a successful creation
+    pub fn is_success(&self) -> bool {
+        self.message == "success"
+    }
+
+    /// The progress of the current step between 0 and 1, if it reports one
+    pub fn progress(&self) -> Option<f64> {
+        match (self.completed, self.total) {
+            (Some(completed), Some(total)) if total > 0 => Some(completed as f64 / total as f64),
+            _ => None,
+        }
+    }
 }
@@ -1,24 +1,30 @@
 use serde::Serialize;
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
 
 impl Ollama {
     /// Delete a model and its data.
     pub async fn delete_model(&self, model_name: String) -> crate::error::Result<()> {
-        let request = DeleteModelRequest { model_name };
+        let request = DeleteModelRequest {
+            model_name: self.resolve_model_alias(model_name),
+        };
 
-        let url = format!("{}api/delete", self.url_str());
+        let url = self.endpoint_url("api/delete");
         let builder = self.reqwest_client.delete(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(&request), EndpointClass::Management)
+            .await?;
 
         if res.status().is_success() {
+            self.invalidate_model_cache();
             Ok(())
         } else {
-            Err(OllamaError::Other(res.text().await?))
+            Err(OllamaError::from_response(res).await)
         }
     }
 }
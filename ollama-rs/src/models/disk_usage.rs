@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use crate::Ollama;
+
+use super::ModelInfo;
+
+/// The disk usage of a local model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelDiskUsage {
+    pub name: String,
+    /// The size of all the layers of the model, in bytes, as reported by the server
+    pub size: u64,
+    /// The digests of the weights and adapter blobs of the model, parsed from its modelfile
+    pub blobs: Vec<String>,
+    /// The other local models using the same weights blob, such as other tags of the same model
+    pub shared_with: Vec<String>,
+}
+
+/// The disk usage of all the local models, see [`Ollama::disk_usage`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskUsageReport {
+    /// The local models, from the largest to the smallest
+    pub models: Vec<ModelDiskUsage>,
+}
+
+impl DiskUsageReport {
+    /// The sum of the sizes of the models, counting shared layers once per model
+    pub fn total_size(&self) -> u64 {
+        self.models.iter().map(|m| m.size).sum()
+    }
+
+    /// An estimate of the space actually used on disk, counting shared weights once.
+    ///
+    /// The server doesn't report the size of each blob, so the size of weights shared by several models
+    /// is estimated as the size of the smallest of these models. Small layers, such as templates and
+    /// parameters, are still counted once per model.
+    pub fn deduplicated_size(&self) -> u64 {
+        let mut shared = HashMap::<&str, (u64, u64)>::new();
+        let mut size = 0;
+
+        for model in &self.models {
+            match model.blobs.first() {
+                Some(weights) if !model.shared_with.is_empty() => {
+                    let (count, smallest) = shared.entry(weights).or_insert((0, u64::MAX));
+                    *count += 1;
+                    *smallest = (*smallest).min(model.size);
+                    size += model.size;
+                }
+                _ => size += model.size,
+            }
+        }
+
+        let duplicates = shared
+            .values()
+            .map(|(count, smallest)| (count - 1) * smallest)
+            .sum::<u64>();
+        size - duplicates
+    }
+}
+
+/// Extracts the digests of the blobs referenced by the `FROM` and `ADAPTER` instructions of the modelfile
+/// of a model, whose paths end with `sha256-<hex>`
+fn blob_digests(info: &ModelInfo) -> Vec<String> {
+    let mut paths = info.modelfile_instructions("FROM");
+    paths.extend(info.modelfile_instructions("ADAPTER"));
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            let hex = path.rsplit_once("sha256-")?.1;
+            (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| format!("sha256:{hex}"))
+        })
+        .collect()
+}
+
+impl Ollama {
+    /// Reports the disk usage of every local model, combining [`Ollama::list_local_models`]
+    /// with [`Ollama::show_model_info`] to find the models sharing the same weights.
+    pub async fn disk_usage(&self) -> crate::error::Result<DiskUsageReport> {
+        let mut models = vec![];
+        for model in self.list_local_models().await? {
+            let info = self.show_model_info(model.name.clone()).await?;
+            models.push(ModelDiskUsage {
+                name: model.name,
+                size: model.size,
+                blobs: blob_digests(&info),
+                shared_with: vec![],
+            });
+        }
+
+        let mut by_weights = HashMap::<String, Vec<String>>::new();
+        for model in &models {
+            if let Some(weights) = model.blobs.first() {
+                by_weights
+                    .entry(weights.clone())
+                    .or_default()
+                    .push(model.name.clone());
+            }
+        }
+
+        for model in &mut models {
+            if let Some(names) = model.blobs.first().and_then(|w| by_weights.get(w)) {
+                model.shared_with = names
+                    .iter()
+                    .filter(|name| **name != model.name)
+                    .cloned()
+                    .collect();
+            }
+        }
+
+        models.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(DiskUsageReport { models })
+    }
+}
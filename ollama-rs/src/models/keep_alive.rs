@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{
+    error::OllamaError,
+    generation::parameters::{KeepAlive, TimeUnit},
+    transport::EndpointClass,
+    Ollama,
+};
+
+use super::{copy::with_default_tag, list_running::RunningModel};
+
+impl Ollama {
+    /// Load a model if needed and set how long it stays in memory, without generating anything.
+    /// - `model_name` - The name of the model.
+    /// - `keep_alive` - How long the model stays loaded, [`KeepAlive::UnloadOnCompletion`] unloads it right away.
+    pub async fn set_keep_alive(
+        &self,
+        model_name: String,
+        keep_alive: KeepAlive,
+    ) -> crate::error::Result<()> {
+        let request = KeepAliveRequest {
+            model_name: self.resolve_model_alias(model_name),
+            keep_alive,
+        };
+
+        // Embedding models don't support the generate endpoint, the embed endpoint is used for them instead
+        let res = self.post_keep_alive("api/generate", &request).await;
+        match res {
+            Err(OllamaError::UnsupportedOption(message))
+                if message.contains("does not support generate") =>
+            {
+                self.post_keep_alive("api/embed", &request).await
+            }
+            res => res,
+        }
+    }
+
+    /// Keep a model loaded for `duration` from now, whatever its current expiry.
+    pub async fn extend_keep_alive(
+        &self,
+        model_name: String,
+        duration: Duration,
+    ) -> crate::error::Result<()> {
+        let keep_alive = KeepAlive::Until {
+            time: duration.as_secs().max(1),
+            unit: TimeUnit::Seconds,
+        };
+        self.set_keep_alive(model_name, keep_alive).await
+    }
+
+    /// Unload a model from memory, freeing the RAM and VRAM it uses.
+    pub async fn unload_model(&self, model_name: String) -> crate::error::Result<()> {
+        self.set_keep_alive(model_name, KeepAlive::UnloadOnCompletion)
+            .await
+    }
+
+    /// Unload every model currently in memory, as listed by [`Ollama::list_running_models`].
+    /// Returns the names of the unloaded models.
+    pub async fn unload_all_models(&self) -> crate::error::Result<Vec<String>> {
+        let mut unloaded = vec![];
+
+        for model in self.list_running_models().await? {
+            self.unload_model(model.name.clone()).await?;
+            unloaded.push(model.name);
+        }
+
+        Ok(unloaded)
+    }
+
+    /// Load a model and wait until it is resident in memory, to hide the cold-start latency of the first request.
+    /// - `model_name` - The name of the model.
+    /// - `keep_alive` - How long the model stays loaded once warm.
+    ///
+    /// Returns the model as listed by [`Ollama::list_running_models`], which confirms it is loaded.
+    pub async fn warm_up(
+        &self,
+        model_name: String,
+        keep_alive: KeepAlive,
+    ) -> crate::error::Result<RunningModel> {
+        let model_name = self.resolve_model_alias(model_name);
+        // The request only returns once the model is loaded
+        self.set_keep_alive(model_name.clone(), keep_alive).await?;
+
+        let tagged = with_default_tag(&model_name);
+
+        self.list_running_models()
+            .await?
+            .into_iter()
+            .find(|m| with_default_tag(&m.name) == tagged)
+            .ok_or_else(|| {
+                OllamaError::Other(format!(
+                    "Model {model_name} is not resident in memory after loading it"
+                ))
+            })
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+    /// Warm up several models in parallel, see [`Ollama::warm_up`].
+    /// The server may unload some of them if they don't all fit in memory.
+    ///
+    /// Returns one result per model, in the same order as `model_names`.
+    pub async fn warm_up_models(
+        &self,
+        model_names: Vec<String>,
+        keep_alive: KeepAlive,
+    ) -> Vec<crate::error::Result<RunningModel>> {
+        let mut results = model_names.iter().map(|_| None).collect::<Vec<_>>();
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for (index, model_name) in model_names.into_iter().enumerate() {
+            let ollama = self.clone();
+            let keep_alive = keep_alive.clone();
+            tasks.spawn(async move { (index, ollama.warm_up(model_name, keep_alive).await) });
+        }
+
+        while let Some(res) = tasks.join_next().await {
+            if let Ok((index, res)) = res {
+                results[index] = Some(res);
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|res| {
+                res.unwrap_or_else(|| Err(OllamaError::Other("Warm-up task failed".to_string())))
+            })
+            .collect()
+    }
+
+    async fn post_keep_alive(
+        &self,
+        endpoint: &str,
+        request: &KeepAliveRequest,
+    ) -> crate::error::Result<()> {
+        let url = self.endpoint_url(endpoint);
+        let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self
+            .send(builder.json(request), EndpointClass::Generation)
+            .await?;
+
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(OllamaError::from_response(res).await)
+        }
+    }
+}
+
+/// A request without any prompt or input, which only loads or unloads the model.
+#[derive(Serialize)]
+struct KeepAliveRequest {
+    #[serde(rename = "model")]
+    model_name: String,
+    keep_alive: KeepAlive,
+}
@@ -1,21 +1,23 @@
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
 
-use super::LocalModel;
+use super::{LocalModel, ModelDetails};
 
 impl Ollama {
     pub async fn list_local_models(&self) -> crate::error::Result<Vec<LocalModel>> {
-        let url = format!("{}api/tags", self.url_str());
+        let url = self.endpoint_url("api/tags");
         let builder = self.reqwest_client.get(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.send().await?;
+        let res = self.send(builder, EndpointClass::Metadata).await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response(res).await);
         }
 
         let res = res.bytes().await?;
@@ -23,6 +25,14 @@ impl Ollama {
 
         Ok(res.models)
     }
+
+    /// List the local models matching a query, filtered and sorted on the client
+    pub async fn list_local_models_with(
+        &self,
+        query: &LocalModelQuery,
+    ) -> crate::error::Result<Vec<LocalModel>> {
+        Ok(query.apply(self.list_local_models().await?))
+    }
 }
 
 /// A response from Ollama containing a list of local models.
@@ -30,3 +40,143 @@ impl Ollama {
 struct ListLocalModelsResponse {
     models: Vec<LocalModel>,
 }
+
+impl ModelDetails {
+    /// Parses the parameter size, such as `8.0B` or `135M`, into a number of parameters
+    pub fn parameter_count(&self) -> Option<u64> {
+        let size = self.parameter_size.trim();
+        let (number, multiplier) = match size.chars().last()?.to_ascii_uppercase() {
+            'K' => (&size[..size.len() - 1], 1e3),
+            'M' => (&size[..size.len() - 1], 1e6),
+            'B' => (&size[..size.len() - 1], 1e9),
+            'T' => (&size[..size.len() - 1], 1e12),
+            _ => (size, 1.0),
+        };
+
+        let count = number.trim().parse::<f64>().ok()? * multiplier;
+        Some(count.round() as u64)
+    }
+}
+
+/// The order of the models returned by a [`LocalModelQuery`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalModelSort {
+    Name,
+    Size,
+    ModifiedAt,
+}
+
+/// Filters and sorts the local models on the client, for instance to build a model picker.
+///
+/// ```
+/// use ollama_rs::models::list_local::{LocalModelQuery, LocalModelSort};
+///
+/// let query = LocalModelQuery::new()
+///     .by_family("llama")
+///     .by_parameter_size_range(1_000_000_000, 10_000_000_000)
+///     .sort_by(LocalModelSort::Size)
+///     .descending(true);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LocalModelQuery {
+    family: Option<String>,
+    min_parameters: Option<u64>,
+    max_parameters: Option<u64>,
+    modified_since: Option<DateTime<Utc>>,
+    sort: Option<LocalModelSort>,
+    descending: bool,
+}
+
+impl LocalModelQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only the models of a family, such as `llama`, matched against every family of the model
+    pub fn by_family(mut self, family: impl Into<String>) -> Self {
+        self.family = Some(family.into());
+        self
+    }
+
+    /// Only the models with a number of parameters in `min..=max`.
+    /// Models whose parameter size is unknown are excluded.
+    pub fn by_parameter_size_range(mut self, min: u64, max: u64) -> Self {
+        self.min_parameters = Some(min);
+        self.max_parameters = Some(max);
+        self
+    }
+
+    /// Only the models modified after `date`. Models with an invalid modification date are excluded.
+    pub fn modified_since(mut self, date: DateTime<Utc>) -> Self {
+        self.modified_since = Some(date);
+        self
+    }
+
+    /// Sorts the models, in ascending order unless [`LocalModelQuery::descending`] is set.
+    /// Without sorting, the models keep the order of the server.
+    pub fn sort_by(mut self, sort: LocalModelSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
+    /// Whether a model passes the filters of the query
+    pub fn matches(&self, model: &LocalModel) -> bool {
+        if let Some(family) = &self.family {
+            let details = &model.details;
+            let families = details.families.as_deref().unwrap_or_default();
+            if details.family != *family && !families.contains(family) {
+                return false;
+            }
+        }
+
+        if self.min_parameters.is_some() || self.max_parameters.is_some() {
+            let Some(count) = model.details.parameter_count() else {
+                return false;
+            };
+            if self.min_parameters.is_some_and(|min| count < min)
+                || self.max_parameters.is_some_and(|max| count > max)
+            {
+                return false;
+            }
+        }
+
+        if let Some(since) = self.modified_since {
+            match model.modified_at() {
+                Some(modified_at) if modified_at >= since => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Filters and sorts a list of models
+    pub fn apply(&self, models: Vec<LocalModel>) -> Vec<LocalModel> {
+        let mut models = models
+            .into_iter()
+            .filter(|m| self.matches(m))
+            .collect::<Vec<_>>();
+
+        if let Some(sort) = self.sort {
+            models.sort_by(|a, b| {
+                let ordering = match sort {
+                    LocalModelSort::Name => a.name.cmp(&b.name),
+                    LocalModelSort::Size => a.size.cmp(&b.size),
+                    LocalModelSort::ModifiedAt => a.modified_at().cmp(&b.modified_at()),
+                };
+                if self.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        models
+    }
+}
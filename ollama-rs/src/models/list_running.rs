@@ -0,0 +1,75 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
+
+use super::ModelDetails;
+
+impl Ollama {
+    /// List the models currently loaded in memory.
+    pub async fn list_running_models(&self) -> crate::error::Result<Vec<RunningModel>> {
+        let url = self.endpoint_url("api/ps");
+        let builder = self.reqwest_client.get(url);
+        let builder = self.with_request_timeout(builder);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self.send(builder, EndpointClass::Metadata).await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_response(res).await);
+        }
+
+        let res = res.bytes().await?;
+        let res = serde_json::from_slice::<ListRunningModelsResponse>(&res)?;
+
+        Ok(res.models)
+    }
+}
+
+/// A model loaded in memory by Ollama.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningModel {
+    pub name: String,
+    #[serde(default)]
+    pub model: String,
+    /// Memory used by the model in bytes
+    pub size: u64,
+    /// Part of the memory used by the model that is in VRAM, in bytes
+    #[serde(default)]
+    pub size_vram: u64,
+    #[serde(default)]
+    pub digest: String,
+    #[serde(default)]
+    pub details: ModelDetails,
+    /// When the model will be unloaded if it isn't used
+    pub expires_at: DateTime<FixedOffset>,
+    /// The context length the model was loaded with, if reported
+    #[serde(default)]
+    pub context_length: Option<u64>,
+}
+
+impl RunningModel {
+    /// The share of the model held in VRAM, between 0 and 1
+    pub fn vram_ratio(&self) -> f64 {
+        if self.size == 0 {
+            0.0
+        } else {
+            self.size_vram as f64 / self.size as f64
+        }
+    }
+
+    /// Time left until the model is unloaded, or `None` if it is past its expiry
+    pub fn expires_in(&self) -> Option<std::time::Duration> {
+        (self.expires_at.to_utc() - chrono::Utc::now())
+            .to_std()
+            .ok()
+    }
+}
+
+/// A response from Ollama containing a list of running models.
+#[derive(Debug, Clone, Deserialize)]
+struct ListRunningModelsResponse {
+    models: Vec<RunningModel>,
+}
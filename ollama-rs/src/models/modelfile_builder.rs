@@ -0,0 +1,200 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    error::OllamaError,
+    generation::chat::{ChatMessage, MessageRole},
+};
+
+use super::{create::CreateModelRequest, ModelOptions};
+
+/// Builds a Modelfile with correct syntax, instead of assembling the string by hand.
+///
+/// The rendered Modelfile can be used with `ollama create -f`, and [`ModelfileBuilder::create_request`]
+/// turns it into a structured [`CreateModelRequest`] for the create API.
+///
+/// ```
+/// use ollama_rs::models::{modelfile_builder::ModelfileBuilder, ModelOptions};
+///
+/// let modelfile = ModelfileBuilder::new("llama3.2")
+///     .system("You are Mario from Super Mario Bros.")
+///     .parameters(&ModelOptions::default().temperature(0.7))
+///     .parameter("stop", "<|eot_id|>");
+///
+/// assert_eq!(
+///     modelfile.render().unwrap(),
+///     "FROM llama3.2\nSYSTEM \"\"\"You are Mario from Super Mario Bros.\"\"\"\nPARAMETER temperature 0.7\nPARAMETER stop \"<|eot_id|>\"\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ModelfileBuilder {
+    from: String,
+    system: Option<String>,
+    template: Option<String>,
+    parameters: Vec<(String, Value)>,
+    adapters: Vec<String>,
+    licenses: Vec<String>,
+    messages: Vec<ChatMessage>,
+}
+
+impl ModelfileBuilder {
+    /// `FROM`: the base model, or the path of a GGUF file or safetensors directory
+    pub fn new(from: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            ..Default::default()
+        }
+    }
+
+    /// `SYSTEM`: the system prompt
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// `TEMPLATE`: the full prompt template, in Go template syntax
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// `PARAMETER`: a single parameter. Repeat it for parameters accepting several values, such as `stop`.
+    pub fn parameter(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.parameters.push((name.into(), value.into()));
+        self
+    }
+
+    /// `PARAMETER`: every option set in `options`
+    pub fn parameters(mut self, options: &ModelOptions) -> Self {
+        let Ok(Value::Object(options)) = serde_json::to_value(options) else {
+            return self;
+        };
+
+        for (name, value) in options {
+            match value {
+                Value::Array(values) => self
+                    .parameters
+                    .extend(values.into_iter().map(|v| (name.clone(), v))),
+                // Options such as the grammar or logit biases can't be written as parameters
+                Value::Object(_) | Value::Null => {}
+                value => self.parameters.push((name, value)),
+            }
+        }
+
+        self
+    }
+
+    /// `ADAPTER`: the path of a LoRA adapter to apply to the base model
+    pub fn adapter(mut self, adapter: impl Into<String>) -> Self {
+        self.adapters.push(adapter.into());
+        self
+    }
+
+    /// `LICENSE`: a license of the model, can be repeated
+    pub fn license(mut self, license: impl Into<String>) -> Self {
+        self.licenses.push(license.into());
+        self
+    }
+
+    /// `MESSAGE`: a message of the conversation history the model starts with
+    pub fn message(mut self, role: MessageRole, content: impl Into<String>) -> Self {
+        self.messages.push(ChatMessage::new(role, content.into()));
+        self
+    }
+
+    /// Renders the Modelfile.
+    ///
+    /// The Modelfile syntax has no escape for `"""`, so this fails if the system prompt, the template,
+    /// a license or a message contains it.
+    pub fn render(&self) -> crate::error::Result<String> {
+        let mut modelfile = format!("FROM {}\n", self.from);
+
+        for adapter in &self.adapters {
+            modelfile += &format!("ADAPTER {adapter}\n");
+        }
+        if let Some(template) = &self.template {
+            modelfile += &format!("TEMPLATE {}\n", quote("TEMPLATE", template)?);
+        }
+        if let Some(system) = &self.system {
+            modelfile += &format!("SYSTEM {}\n", quote("SYSTEM", system)?);
+        }
+        for (name, value) in &self.parameters {
+            modelfile += &format!("PARAMETER {name} {}\n", parameter_value(value));
+        }
+        for license in &self.licenses {
+            modelfile += &format!("LICENSE {}\n", quote("LICENSE", license)?);
+        }
+        for message in &self.messages {
+            modelfile += &format!(
+                "MESSAGE {} {}\n",
+                message.role.as_str(),
+                quote("MESSAGE", &message.content)?
+            );
+        }
+
+        Ok(modelfile)
+    }
+
+    /// Builds a structured create request for the create API.
+    ///
+    /// Adapters are not included, since the create API expects the digests of uploaded blobs:
+    /// upload them with [`Ollama::create_blob_from_file`](crate::Ollama::create_blob_from_file)
+    /// and pass them to [`CreateModelRequest::adapters`]. Parameters unknown to [`ModelOptions`] are dropped,
+    /// and a parameter with a value of an unexpected type fails the request.
+    pub fn create_request(&self, model_name: String) -> crate::error::Result<CreateModelRequest> {
+        let mut request = CreateModelRequest::new(model_name).from_model(self.from.clone());
+
+        if let Some(system) = &self.system {
+            request = request.system(system.clone());
+        }
+        if let Some(template) = &self.template {
+            request = request.template(template.clone());
+        }
+        if !self.licenses.is_empty() {
+            request = request.licenses(self.licenses.clone());
+        }
+        if !self.messages.is_empty() {
+            request = request.messages(self.messages.clone());
+        }
+
+        if !self.parameters.is_empty() {
+            let mut parameters = Map::new();
+            for (name, value) in &self.parameters {
+                if let Some(Value::Array(values)) = parameters.get_mut(name) {
+                    values.push(value.clone());
+                    continue;
+                }
+
+                let value = match name.as_str() {
+                    "stop" => Value::Array(vec![value.clone()]),
+                    _ => value.clone(),
+                };
+                parameters.insert(name.clone(), value);
+            }
+
+            let options = serde_json::from_value::<ModelOptions>(Value::Object(parameters))?;
+            request = request.parameters(options);
+        }
+
+        Ok(request)
+    }
+}
+
+/// Quotes the value of an instruction with triple quotes, so that it can span several lines
+fn quote(instruction: &str, value: &str) -> crate::error::Result<String> {
+    if value.contains(r#"""""#) {
+        return Err(OllamaError::Other(format!(
+            r#"The {instruction} of a Modelfile can't contain """"#
+        )));
+    }
+
+    Ok(format!(r#""""{value}""""#))
+}
+
+fn parameter_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{s:?}"),
+        // Options are `f32`s, printed without the noise of their `f64` conversion
+        Value::Number(n) if n.is_f64() => (n.as_f64().unwrap_or_default() as f32).to_string(),
+        value => value.to_string(),
+    }
+}
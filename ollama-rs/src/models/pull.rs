@@ -1,20 +1,188 @@
+use std::{collections::HashMap, time::Duration};
+
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OllamaError, Ollama};
+use crate::error::OllamaError;
+use crate::transport::EndpointClass;
+use crate::Instant;
+use crate::Ollama;
+
+use super::registry::registry_error;
 
 /// A stream of `PullModelStatus` objects.
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
-pub type PullModelStatusStream = std::pin::Pin<
-    Box<dyn tokio_stream::Stream<Item = crate::error::Result<PullModelStatus>> + Send>,
->;
+pub type PullModelStatusStream = crate::BoxStream<'static, crate::error::Result<PullModelStatus>>;
+
+/// A stream of [`PullProgress`] snapshots, one per status received.
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub type PullProgressStream = crate::BoxStream<'static, crate::error::Result<PullProgress>>;
+
+/// A merged stream of `(model_name, progress)` items produced by several pulls at once, see [`Ollama::pull_models`].
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub type MultiPullProgressStream =
+    crate::BoxStream<'static, (String, crate::error::Result<PullProgress>)>;
 
 impl Ollama {
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// Pull several models, such as the whole suite used by a service, with a single progress stream.
+    /// Returns a stream of `(model_name, progress)` items, see [`Ollama::pull_model_progress_stream`].
+    /// At most `max_concurrency` pulls are in flight at any time, the next model is pulled as soon as one finishes.
+    /// A failed pull yields an error for its model, and the other pulls continue.
+    /// - `model_names` - The models to pull, duplicates are pulled once.
+    /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pulling from your own library during development.
+    pub fn pull_models(
+        &self,
+        model_names: Vec<String>,
+        allow_insecure: bool,
+        max_concurrency: usize,
+    ) -> MultiPullProgressStream {
+        use async_stream::stream;
+        use tokio_stream::{StreamExt, StreamMap};
+
+        let ollama = self.clone();
+        let max_concurrency = max_concurrency.max(1);
+
+        let mut unique = Vec::with_capacity(model_names.len());
+        for model_name in model_names {
+            if !unique.contains(&model_name) {
+                unique.push(model_name);
+            }
+        }
+
+        let s = stream! {
+            let mut pending = unique.into_iter();
+            let mut active = StreamMap::new();
+
+            loop {
+                // Fill the free slots with the next pending models
+                while active.len() < max_concurrency {
+                    let Some(model_name) = pending.next() else {
+                        break;
+                    };
+
+                    match ollama.pull_model_progress_stream(model_name.clone(), allow_insecure).await {
+                        Ok(stream) => {
+                            active.insert(model_name, stream);
+                        }
+                        Err(e) => yield (model_name, Err(e)),
+                    }
+                }
+
+                // The pending models are exhausted once no slot could be filled
+                if active.is_empty() {
+                    break;
+                }
+
+                // `None` when the last active pull finished, the next ones are started above
+                if let Some(item) = active.next().await {
+                    yield item;
+                }
+            }
+        };
+
+        Box::pin(s)
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// Pull a model with streaming, aggregating the statuses into an overall [`PullProgress`] for progress bars.
+    /// - `model_name` - The name of the model to pull.
+    /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pulling from your own library during development.
+    pub async fn pull_model_progress_stream(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> crate::error::Result<PullProgressStream> {
+        use tokio_stream::StreamExt;
+
+        let mut progress = PullProgress::new();
+        let stream = self
+            .pull_model_stream(model_name, allow_insecure)
+            .await?
+            .map(move |status| {
+                progress.update(&status?);
+                Ok(progress.clone())
+            });
+
+        Ok(Box::pin(stream))
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// Pull a model with streaming like [`Ollama::pull_model_progress_stream`], restarting the pull when the connection
+    /// drops or the download fails, with an exponential backoff. The server resumes the partially downloaded layers,
+    /// so the progress continues where it stopped.
+    /// - `model_name` - The name of the model to pull.
+    /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pulling from your own library during development.
+    /// - `max_attempts` - The maximum number of attempts, including the first one.
+    ///
+    /// Errors that retrying can't fix, such as a missing model or an authentication failure, end the stream right away.
+    pub fn pull_model_resumable(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+        max_attempts: u32,
+    ) -> PullProgressStream {
+        use tokio_stream::StreamExt;
+
+        let ollama = self.clone();
+
+        let s = async_stream::stream! {
+            let mut progress = PullProgress::new();
+            let mut attempt = 0;
+
+            loop {
+                attempt += 1;
+
+                let error = match ollama.pull_model_stream(model_name.clone(), allow_insecure).await {
+                    Ok(mut stream) => {
+                        let mut error = None;
+                        while let Some(status) = stream.next().await {
+                            match status {
+                                Ok(status) => {
+                                    progress.update(&status);
+                                    yield Ok(progress.clone());
+                                }
+                                Err(e) => {
+                                    error = Some(e);
+                                    break;
+                                }
+                            }
+                        }
+
+                        match error {
+                            Some(e) => e,
+                            None if progress.phase() == &PullPhase::Success => break,
+                            None => OllamaError::Other("The pull ended before completing".to_string()),
+                        }
+                    }
+                    Err(e) => e,
+                };
+
+                if attempt >= max_attempts.max(1) || !is_retryable_pull_error(&error) {
+                    yield Err(error);
+                    break;
+                }
+
+                log::warn!("Pull of {model_name} failed, retrying (attempt {attempt}): {error}");
+                crate::runtime::sleep(Duration::from_millis(500) * 2u32.saturating_pow(attempt - 1)).await;
+            }
+        };
+
+        Box::pin(s)
+    }
+
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
     #[cfg(feature = "stream")]
     /// Pull a model with streaming, meaning that each new status will be streamed.
     /// - `model_name` - The name of the model to pull.
     /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pulling from your own library during development.
+    ///
+    /// The server authenticates to the registry with its own key, see [`Ollama::pull_model`].
     pub async fn pull_model_stream(
         &self,
         model_name: String,
@@ -22,41 +190,52 @@ impl Ollama {
     ) -> crate::error::Result<PullModelStatusStream> {
         use tokio_stream::StreamExt;
 
-        use crate::error::{InternalOllamaError, OllamaError};
+        use super::registry::registry_error_from_internal;
+        use crate::error::InternalOllamaError;
 
         let request = PullModelRequest {
-            model_name,
+            model_name: self.resolve_model_alias(model_name),
             allow_insecure,
             stream: true,
         };
 
-        let url = format!("{}api/pull", self.url_str());
+        let url = self.endpoint_url("api/pull");
         let builder = self.reqwest_client.post(url);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send_stream(builder.json(&request), EndpointClass::Pull)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response_with(res, registry_error).await);
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
+        let model_cache = self.model_cache.clone();
+        let chunks =
+            crate::timeout::response_chunks(res, self.stream_idle_timeout, self.stream_buffer);
+        let stream = Box::new(chunks.map(move |res| match res {
             Ok(bytes) => {
                 let res = serde_json::from_slice::<PullModelStatus>(&bytes);
                 match res {
-                    Ok(res) => Ok(res),
+                    Ok(res) => {
+                        if let (Some(cache), "success") = (&model_cache, res.message.as_str()) {
+                            cache.clear();
+                        }
+                        Ok(res)
+                    }
                     Err(e) => {
                         let err = serde_json::from_slice::<InternalOllamaError>(&bytes);
                         match err {
-                            Ok(err) => Err(OllamaError::InternalError(err)),
+                            Ok(err) => Err(registry_error_from_internal(err)),
                             Err(_) => Err(e.into()),
                         }
                     }
                 }
             }
-            Err(e) => Err(e.into()),
+            Err(e) => Err(e),
         }));
 
         Ok(std::pin::Pin::from(stream))
@@ -65,31 +244,43 @@ impl Ollama {
     /// Pull a model with a single response, only the final status will be returned.
     /// - `model_name` - The name of the model to pull.
     /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pulling from your own library during development.
+    ///
+    /// The server pulls the model itself and authenticates to the registry with its own key (`~/.ollama/id_ed25519`
+    /// on the server), no credentials are sent with the request. Private registries must be set up on the server;
+    /// [`RegistryCredentials`](super::registry::RegistryCredentials) only apply to the requests this client sends
+    /// directly to a registry, such as [`Ollama::verify_pulled_model`].
+    ///
+    /// Registries rejecting the credentials of the server fail with [`OllamaError::RegistryUnauthorized`]
+    /// or [`OllamaError::RegistryPermissionDenied`].
     pub async fn pull_model(
         &self,
         model_name: String,
         allow_insecure: bool,
     ) -> crate::error::Result<PullModelStatus> {
         let request = PullModelRequest {
-            model_name,
+            model_name: self.resolve_model_alias(model_name),
             allow_insecure,
             stream: false,
         };
 
-        let url = format!("{}api/pull", self.url_str());
+        let url = self.endpoint_url("api/pull");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(&request), EndpointClass::Pull)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response_with(res, registry_error).await);
         }
 
         let res = res.bytes().await?;
         let res = serde_json::from_slice::<PullModelStatus>(&res)?;
+        self.invalidate_model_cache();
 
         Ok(res)
     }
@@ -114,3 +305,139 @@ pub struct PullModelStatus {
     pub total: Option<u64>,
     pub completed: Option<u64>,
 }
+
+/// Whether a failed pull may succeed when restarted: network failures and the unrecognized failures
+/// of the download are retried, but not a missing model or a rejected authentication
+#[cfg(feature = "stream")]
+fn is_retryable_pull_error(error: &OllamaError) -> bool {
+    matches!(
+        error.inner(),
+        OllamaError::ReqwestError(_)
+            | OllamaError::ServerUnavailable { .. }
+            | OllamaError::StreamIdleTimeout(_)
+            | OllamaError::Other(_)
+    )
+}
+
+/// The phase of a model pull, parsed from the status messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PullPhase {
+    /// Fetching the manifest listing the layers of the model
+    Manifest,
+    /// Downloading the layers
+    Downloading,
+    /// Verifying the digests of the downloaded layers
+    Verifying,
+    /// Writing the manifest and cleaning up unused layers
+    Finalizing,
+    Success,
+    /// A status this version of the library doesn't know
+    Other(String),
+}
+
+impl PullPhase {
+    pub fn from_status(status: &str) -> Self {
+        match status {
+            "pulling manifest" => Self::Manifest,
+            "success" => Self::Success,
+            s if s.starts_with("pulling ") => Self::Downloading,
+            s if s.starts_with("verifying") => Self::Verifying,
+            s if s.starts_with("writing") || s.starts_with("removing") => Self::Finalizing,
+            s => Self::Other(s.to_string()),
+        }
+    }
+}
+
+/// The overall progress of a model pull, aggregated over all layers.
+///
+/// Feed it every [`PullModelStatus`] with [`PullProgress::update`], or use [`Ollama::pull_model_progress_stream`].
+#[derive(Debug, Clone)]
+pub struct PullProgress {
+    phase: PullPhase,
+    layers: HashMap<String, (u64, u64)>,
+    started: Instant,
+}
+
+impl Default for PullProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PullProgress {
+    pub fn new() -> Self {
+        Self {
+            phase: PullPhase::Manifest,
+            layers: HashMap::new(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Updates the progress with a new status
+    pub fn update(&mut self, status: &PullModelStatus) {
+        self.phase = PullPhase::from_status(&status.message);
+
+        if let (Some(digest), Some(total)) = (&status.digest, status.total) {
+            let completed = status.completed.unwrap_or(0).min(total);
+            self.layers.insert(digest.clone(), (completed, total));
+        }
+    }
+
+    pub fn phase(&self) -> &PullPhase {
+        &self.phase
+    }
+
+    /// The number of layers seen so far
+    pub fn layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Bytes downloaded over all layers
+    pub fn completed_bytes(&self) -> u64 {
+        self.layers.values().map(|(completed, _)| completed).sum()
+    }
+
+    /// Size of all layers seen so far. Layers are announced one after the other,
+    /// so the total grows during the download.
+    pub fn total_bytes(&self) -> u64 {
+        self.layers.values().map(|(_, total)| total).sum()
+    }
+
+    /// The overall progress between 0 and 100, if any layer is known
+    pub fn percent(&self) -> Option<f64> {
+        if self.phase == PullPhase::Success {
+            return Some(100.0);
+        }
+
+        match self.total_bytes() {
+            0 => None,
+            total => Some(self.completed_bytes() as f64 * 100.0 / total as f64),
+        }
+    }
+
+    /// Time elapsed since the pull started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// The average transfer rate in bytes per second
+    pub fn rate(&self) -> f64 {
+        let secs = self.elapsed().as_secs_f64();
+        if secs > 0.0 {
+            self.completed_bytes() as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// The estimated time until the known layers are downloaded, at the average transfer rate
+    pub fn eta(&self) -> Option<Duration> {
+        let rate = self.rate();
+        if rate <= 0.0 {
+            return None;
+        }
+
+        let remaining = self.total_bytes().saturating_sub(self.completed_bytes());
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
@@ -1,13 +1,17 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OllamaError, Ollama};
+use crate::{
+    error::{InternalOllamaError, OllamaError},
+    transport::EndpointClass,
+    Ollama,
+};
+
+use super::registry::{registry_error, registry_error_from_internal};
 
 /// A stream of `PushModelStatus` objects.
 #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
 #[cfg(feature = "stream")]
-pub type PushModelStatusStream = std::pin::Pin<
-    Box<dyn tokio_stream::Stream<Item = crate::error::Result<PushModelStatus>> + Send>,
->;
+pub type PushModelStatusStream = crate::BoxStream<'static, crate::error::Result<PushModelStatus>>;
 
 impl Ollama {
     #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
@@ -16,48 +20,52 @@ impl Ollama {
     /// Push a model with streaming, meaning that each new status will be streamed.
     /// - `model_name` - The name of the model to push in the form of `<namespace>/<model>:<tag>`.
     /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pushing to your library during development.
+    ///
+    /// The server authenticates to the registry with its own key, see [`Ollama::push_model`].
     pub async fn push_model_stream(
         &self,
         model_name: String,
         allow_insecure: bool,
     ) -> crate::error::Result<PushModelStatusStream> {
-        use crate::error::OllamaError;
         use tokio_stream::StreamExt;
 
         let request = PushModelRequest {
-            model_name,
+            model_name: self.resolve_model_alias(model_name),
             allow_insecure,
             stream: true,
         };
 
-        let url = format!("{}api/push", self.url_str());
+        let url = self.endpoint_url("api/push");
         let builder = self.reqwest_client.post(url);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send_stream(builder.json(&request), EndpointClass::Management)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response_with(res, registry_error).await);
         }
 
-        let stream = Box::new(res.bytes_stream().map(|res| match res {
+        let chunks =
+            crate::timeout::response_chunks(res, self.stream_idle_timeout, self.stream_buffer);
+        let stream = Box::new(chunks.map(|res| match res {
             Ok(bytes) => {
                 let res = serde_json::from_slice::<PushModelStatus>(&bytes);
                 match res {
                     Ok(res) => Ok(res),
                     Err(e) => {
-                        let err =
-                            serde_json::from_slice::<crate::error::InternalOllamaError>(&bytes);
+                        let err = serde_json::from_slice::<InternalOllamaError>(&bytes);
                         match err {
-                            Ok(err) => Err(OllamaError::InternalError(err)),
+                            Ok(err) => Err(registry_error_from_internal(err)),
                             Err(_) => Err(e.into()),
                         }
                     }
                 }
             }
-            Err(e) => Err(OllamaError::Other(format!("Failed to read response: {e}"))),
+            Err(e) => Err(e),
         }));
 
         Ok(std::pin::Pin::from(stream))
@@ -67,31 +75,44 @@ impl Ollama {
     /// Push a model with a single response, only the final status will be returned.
     /// - `model_name` - The name of the model to push in the form of `<namespace>/<model>:<tag>`.
     /// - `allow_insecure` - Allow insecure connections to the library. Only use this if you are pushing to your library during development.
+    ///
+    /// The server pushes the model itself and authenticates to the registry with its own key (`~/.ollama/id_ed25519`
+    /// on the server), no credentials are sent with the request. Private registries must be set up on the server;
+    /// [`RegistryCredentials`](super::registry::RegistryCredentials) only apply to the requests this client sends
+    /// directly to a registry, such as [`Ollama::verify_pulled_model`].
     pub async fn push_model(
         &self,
         model_name: String,
         allow_insecure: bool,
     ) -> crate::error::Result<PushModelStatus> {
         let request = PushModelRequest {
-            model_name,
+            model_name: self.resolve_model_alias(model_name),
             allow_insecure,
             stream: false,
         };
 
-        let url = format!("{}api/push", self.url_str());
+        let url = self.endpoint_url("api/push");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder.json(&request).send().await?;
+        let res = self
+            .send(builder.json(&request), EndpointClass::Management)
+            .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response_with(res, registry_error).await);
         }
 
         let res = res.bytes().await?;
-        let res = serde_json::from_slice::<PushModelStatus>(&res)?;
+        let res = serde_json::from_slice::<PushModelStatus>(&res).map_err(|e| {
+            match serde_json::from_slice::<InternalOllamaError>(&res) {
+                Ok(err) => registry_error_from_internal(err),
+                Err(_) => e.into(),
+            }
+        })?;
 
         Ok(res)
     }
@@ -114,4 +135,41 @@ pub struct PushModelStatus {
     pub message: String,
     pub digest: Option<String>,
     pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+}
+
+impl PushModelStatus {
+    /// Parses the status message into a typed event
+    pub fn event(&self) -> PushEvent {
+        match self.message.as_str() {
+            "retrieving manifest" => PushEvent::RetrievingManifest,
+            "starting upload" => PushEvent::StartingUpload,
+            "pushing manifest" => PushEvent::PushingManifest,
+            "success" => PushEvent::Success,
+            s if s.starts_with("pushing ") || s.starts_with("uploading ") => PushEvent::Uploading {
+                digest: self.digest.clone().unwrap_or_default(),
+                completed: self.completed.unwrap_or(0),
+                total: self.total.unwrap_or(0),
+            },
+            s => PushEvent::Other(s.to_string()),
+        }
+    }
+}
+
+/// A step of a model push, see [`PushModelStatus::event`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushEvent {
+    RetrievingManifest,
+    StartingUpload,
+    /// A layer is being uploaded, sizes are in bytes
+    Uploading {
+        digest: String,
+        completed: u64,
+        total: u64,
+    },
+    PushingManifest,
+    Success,
+    /// A status this version of the library doesn't know
+    Other(String),
 }
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use crate::{
+    error::{InternalOllamaError, OllamaError},
+    Ollama,
+};
+
+/// Credentials for the requests this client sends directly to a registry,
+/// such as the manifest fetched by [`Ollama::verify_pulled_model`].
+///
+/// Pulls and pushes are performed by the server, which authenticates to the registry with its own key
+/// (see `~/.ollama/id_ed25519`); the pull and push endpoints can't forward credentials.
+#[derive(Clone)]
+pub enum RegistryCredentials {
+    Basic { username: String, password: String },
+    Bearer(String),
+}
+
+impl fmt::Debug for RegistryCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .finish_non_exhaustive(),
+            Self::Bearer(_) => f.write_str("Bearer(..)"),
+        }
+    }
+}
+
+impl RegistryCredentials {
+    pub(crate) fn apply(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            Self::Basic { username, password } => builder.basic_auth(username, Some(password)),
+            Self::Bearer(token) => builder.bearer_auth(token),
+        }
+    }
+}
+
+impl Ollama {
+    /// Sets the credentials used for the requests sent directly to a registry, see [`RegistryCredentials`].
+    /// `None` removes them.
+    pub fn set_registry_credentials(&mut self, credentials: Option<RegistryCredentials>) {
+        self.registry_credentials = credentials;
+    }
+}
+
+/// Maps an error response of a pull or push to a registry error variant if it is about authentication or permissions
+pub(crate) fn registry_error(text: String) -> OllamaError {
+    match serde_json::from_str::<InternalOllamaError>(&text) {
+        Ok(err) => registry_error_from_internal(err),
+        Err(_) => OllamaError::from_response_body(text),
+    }
+}
+
+pub(crate) fn registry_error_from_internal(err: InternalOllamaError) -> OllamaError {
+    let message = err.message.to_lowercase();
+
+    if message.contains("unauthorized") || message.contains("authentication required") {
+        OllamaError::RegistryUnauthorized(err.message)
+    } else if message.contains("not authorized")
+        || message.contains("denied")
+        || message.contains("forbidden")
+    {
+        OllamaError::RegistryPermissionDenied(err.message)
+    } else {
+        OllamaError::from_response_body(err.message)
+    }
+}
+
+/// Maps the status of a response from a registry to a registry error variant
+pub(crate) fn registry_status_error(status: reqwest::StatusCode, text: String) -> OllamaError {
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED => OllamaError::RegistryUnauthorized(text),
+        reqwest::StatusCode::FORBIDDEN => OllamaError::RegistryPermissionDenied(text),
+        _ => OllamaError::Other(text),
+    }
+}
@@ -1,25 +1,49 @@
 use serde::Serialize;
+use serde_json::{Map, Value};
 
-use crate::{error::OllamaError, Ollama};
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
 
-use super::ModelInfo;
+use super::{ModelInfo, ModelOptions};
 
 impl Ollama {
     /// Show details about a model including modelfile, template, parameters, license, and system prompt.
     pub async fn show_model_info(&self, model_name: String) -> crate::error::Result<ModelInfo> {
-        let url = format!("{}api/show", self.url_str());
+        self.show_model_info_inner(model_name, false).await
+    }
+
+    /// Show details about a model, including the large `model_info` entries such as the tokenizer vocabulary,
+    /// which are omitted by [`Ollama::show_model_info`].
+    pub async fn show_model_info_verbose(
+        &self,
+        model_name: String,
+    ) -> crate::error::Result<ModelInfo> {
+        self.show_model_info_inner(model_name, true).await
+    }
+
+    async fn show_model_info_inner(
+        &self,
+        model_name: String,
+        verbose: bool,
+    ) -> crate::error::Result<ModelInfo> {
+        let url = self.endpoint_url("api/show");
         let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
 
         #[cfg(feature = "headers")]
         let builder = builder.headers(self.request_headers.clone());
 
-        let res = builder
-            .json(&ModelInfoRequest { model_name })
-            .send()
+        let res = self
+            .send(
+                builder.json(&ModelInfoRequest {
+                    model_name: self.resolve_model_alias(model_name),
+                    verbose,
+                }),
+                EndpointClass::Metadata,
+            )
             .await?;
 
         if !res.status().is_success() {
-            return Err(OllamaError::Other(res.text().await?));
+            return Err(OllamaError::from_response(res).await);
         }
 
         let res = res.bytes().await?;
@@ -34,4 +58,154 @@ impl Ollama {
 struct ModelInfoRequest {
     #[serde(rename = "name")]
     model_name: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    verbose: bool,
+}
+
+/// The main properties of a model, parsed from [`ModelInfo::model_info`] and [`ModelInfo::details`].
+/// Fields are `None` when the model doesn't report them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelMetadata {
+    /// The architecture of the model, such as `llama`
+    pub architecture: Option<String>,
+    pub parameter_count: Option<u64>,
+    /// The maximum context length the model was trained with, in tokens
+    pub context_length: Option<u64>,
+    /// The dimension of the embeddings produced by the model
+    pub embedding_length: Option<u64>,
+    /// The number of layers
+    pub block_count: Option<u64>,
+    pub head_count: Option<u64>,
+    /// The quantization of the weights, such as `Q4_K_M`
+    pub quantization_level: Option<String>,
+}
+
+impl ModelInfo {
+    /// Returns a `model_info` entry, such as `general.architecture`
+    pub fn info(&self, key: &str) -> Option<&Value> {
+        self.model_info.get(key)
+    }
+
+    /// Returns an architecture-specific `model_info` entry, such as `context_length` for `llama.context_length`
+    pub fn architecture_info(&self, key: &str) -> Option<&Value> {
+        let architecture = self.info("general.architecture")?.as_str()?;
+        self.info(&format!("{architecture}.{key}"))
+    }
+
+    /// Returns the `FROM` instruction of the modelfile: the path of the weights blob, or the base model
+    pub fn base_model(&self) -> Option<String> {
+        self.modelfile_instructions("FROM").into_iter().next()
+    }
+
+    /// Returns the paths of the LoRA adapters applied to the model, from the `ADAPTER` instructions of the modelfile
+    pub fn adapters(&self) -> Vec<String> {
+        self.modelfile_instructions("ADAPTER")
+    }
+
+    /// Returns the arguments of the single-line instructions of the modelfile with the given name
+    pub(super) fn modelfile_instructions(&self, instruction: &str) -> Vec<String> {
+        modelfile_instructions(&self.modelfile.to_string(), instruction)
+    }
+
+    /// Parses the `parameters` baked into the model, one `name value` pair per line, in order.
+    /// Quoted values are unquoted, numbers and booleans are parsed.
+    pub fn parameter_values(&self) -> Vec<(String, Value)> {
+        self.parameters
+            .lines()
+            .filter_map(|line| {
+                let (name, value) = line.trim().split_once(char::is_whitespace)?;
+                Some((name.to_string(), parse_parameter_value(value.trim())))
+            })
+            .collect()
+    }
+
+    /// Parses the `parameters` baked into the model into [`ModelOptions`], which can be tweaked and sent back
+    /// on requests. Parameters unknown to [`ModelOptions`], or with a value of an unexpected type, are skipped.
+    pub fn options(&self) -> ModelOptions {
+        let mut options = Map::new();
+        for (name, value) in self.parameter_values() {
+            if name == "stop" {
+                if let Value::Array(stop) =
+                    options.entry(name).or_insert_with(|| Value::Array(vec![]))
+                {
+                    stop.push(match value {
+                        Value::String(s) => Value::String(s),
+                        value => Value::String(value.to_string()),
+                    });
+                }
+                continue;
+            }
+
+            let single = Map::from_iter([(name.clone(), value.clone())]);
+            if serde_json::from_value::<ModelOptions>(Value::Object(single)).is_ok() {
+                options.insert(name, value);
+            }
+        }
+
+        serde_json::from_value(Value::Object(options)).unwrap_or_default()
+    }
+
+    /// Returns the stop sequences baked into the model
+    pub fn stop_sequences(&self) -> Vec<String> {
+        self.parameter_values()
+            .into_iter()
+            .filter(|(name, _)| name == "stop")
+            .map(|(_, value)| match value {
+                Value::String(s) => s,
+                value => value.to_string(),
+            })
+            .collect()
+    }
+
+    /// Parses the main properties of the model
+    pub fn metadata(&self) -> ModelMetadata {
+        let quantization_level =
+            Some(self.details.quantization_level.clone()).filter(|q| !q.is_empty());
+
+        ModelMetadata {
+            architecture: self
+                .info("general.architecture")
+                .and_then(Value::as_str)
+                .map(String::from),
+            parameter_count: self.info("general.parameter_count").and_then(Value::as_u64),
+            context_length: self
+                .architecture_info("context_length")
+                .and_then(Value::as_u64),
+            embedding_length: self
+                .architecture_info("embedding_length")
+                .and_then(Value::as_u64),
+            block_count: self
+                .architecture_info("block_count")
+                .and_then(Value::as_u64),
+            head_count: self
+                .architecture_info("attention.head_count")
+                .and_then(Value::as_u64),
+            quantization_level,
+        }
+    }
+}
+
+fn parse_parameter_value(value: &str) -> Value {
+    if value.starts_with('"') {
+        if let Ok(s) = serde_json::from_str::<String>(value) {
+            return Value::String(s);
+        }
+    }
+
+    match serde_json::from_str::<Value>(value) {
+        Ok(value @ (Value::Number(_) | Value::Bool(_))) => value,
+        _ => Value::String(value.to_string()),
+    }
+}
+
+/// Returns the arguments of the single-line instructions of a modelfile with the given name
+pub(super) fn modelfile_instructions(modelfile: &str, instruction: &str) -> Vec<String> {
+    modelfile
+        .lines()
+        .filter_map(|line| {
+            let (name, argument) = line.trim().split_once(char::is_whitespace)?;
+            name.eq_ignore_ascii_case(instruction)
+                .then(|| argument.trim().to_string())
+        })
+        .collect()
 }
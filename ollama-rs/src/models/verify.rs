@@ -0,0 +1,140 @@
+use serde::Deserialize;
+
+use crate::transport::EndpointClass;
+use crate::Ollama;
+
+use super::registry::registry_status_error;
+
+const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+const DEFAULT_NAMESPACE: &str = "library";
+const DEFAULT_TAG: &str = "latest";
+
+/// A layer of a model manifest, checked against the blobs of the local server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerVerification {
+    pub digest: String,
+    pub media_type: String,
+    pub size: u64,
+    /// Whether the server has a blob with this digest
+    pub present: bool,
+}
+
+/// The result of checking a pulled model against its manifest in the registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullVerificationReport {
+    pub model_name: String,
+    /// The config and the layers of the manifest
+    pub layers: Vec<LayerVerification>,
+}
+
+impl PullVerificationReport {
+    /// Whether every layer of the manifest is present on the server
+    pub fn is_ok(&self) -> bool {
+        self.layers.iter().all(|l| l.present)
+    }
+
+    /// The layers missing from the server
+    pub fn missing(&self) -> impl Iterator<Item = &LayerVerification> {
+        self.layers.iter().filter(|l| !l.present)
+    }
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    config: ManifestLayer,
+    layers: Vec<ManifestLayer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestLayer {
+    media_type: String,
+    digest: String,
+    size: u64,
+}
+
+/// Splits a model name such as `llama3.2`, `user/model:tag` or `host:port/user/model:tag` into
+/// the registry host, the repository and the tag
+fn parse_model_name(model_name: &str) -> (String, String, String) {
+    let (name, tag) = match model_name.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name, tag),
+        _ => (model_name, DEFAULT_TAG),
+    };
+
+    let parts = name.split('/').collect::<Vec<_>>();
+    let (host, repository) = match parts.as_slice() {
+        [model] => (
+            DEFAULT_REGISTRY.to_string(),
+            format!("{DEFAULT_NAMESPACE}/{model}"),
+        ),
+        [namespace, model] => (DEFAULT_REGISTRY.to_string(), format!("{namespace}/{model}")),
+        [host, rest @ ..] => (host.to_string(), rest.join("/")),
+        [] => (DEFAULT_REGISTRY.to_string(), name.to_string()),
+    };
+
+    (host, repository, tag.to_string())
+}
+
+impl Ollama {
+    /// Check that every layer of a pulled model is present on the server, by fetching the manifest
+    /// of the model from its registry and checking each digest with [`Ollama::blob_exists`].
+    /// The server verifies the digest of every blob when it is written, so a present blob is intact.
+    /// - `model_name` - The name of the model, as given to [`Ollama::pull_model`].
+    /// - `allow_insecure` - Fetch the manifest over HTTP instead of HTTPS.
+    pub async fn verify_pulled_model(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> crate::error::Result<PullVerificationReport> {
+        let model_name = self.resolve_model_alias(model_name);
+        let (host, repository, tag) = parse_model_name(&model_name);
+        let scheme = if allow_insecure { "http" } else { "https" };
+        let url = format!("{scheme}://{host}/v2/{repository}/manifests/{tag}");
+
+        let builder = self.reqwest_client.get(url).header(
+            reqwest::header::ACCEPT,
+            "application/vnd.docker.distribution.manifest.v2+json",
+        );
+        let builder = match &self.registry_credentials {
+            Some(credentials) => credentials.apply(builder),
+            None => builder,
+        };
+        let builder = self.with_request_timeout(builder);
+
+        let res = self.send(builder, EndpointClass::Metadata).await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = format!(
+                "Failed to fetch the manifest of {model_name}: {}",
+                res.text().await?
+            );
+            return Err(registry_status_error(status, text));
+        }
+
+        let manifest = serde_json::from_slice::<Manifest>(&res.bytes().await?)?;
+
+        let mut layers = vec![];
+        for layer in std::iter::once(manifest.config).chain(manifest.layers) {
+            let present = self.blob_exists(&layer.digest).await?;
+            layers.push(LayerVerification {
+                digest: layer.digest,
+                media_type: layer.media_type,
+                size: layer.size,
+                present,
+            });
+        }
+
+        Ok(PullVerificationReport { model_name, layers })
+    }
+
+    /// Pull a model, then verify it with [`Ollama::verify_pulled_model`].
+    pub async fn pull_model_verified(
+        &self,
+        model_name: String,
+        allow_insecure: bool,
+    ) -> crate::error::Result<PullVerificationReport> {
+        self.pull_model(model_name.clone(), allow_insecure).await?;
+        self.verify_pulled_model(model_name, allow_insecure).await
+    }
+}
@@ -0,0 +1,105 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_stream::stream;
+
+use crate::Ollama;
+
+use super::LocalModel;
+
+/// A stream of changes to the local models, see [`Ollama::watch_local_models`]
+pub type LocalModelEventStream = crate::BoxStream<'static, crate::error::Result<LocalModelEvent>>;
+
+/// A change to the local models
+#[derive(Debug, Clone)]
+pub enum LocalModelEvent {
+    /// A model was pulled or created
+    Added(LocalModel),
+    /// A model was deleted
+    Removed(LocalModel),
+    /// A model was replaced by another version with the same name, for instance by pulling it again
+    Updated {
+        old: Box<LocalModel>,
+        new: Box<LocalModel>,
+    },
+}
+
+impl LocalModelEvent {
+    /// The name of the model that changed
+    pub fn model_name(&self) -> &str {
+        match self {
+            Self::Added(model) | Self::Removed(model) => &model.name,
+            Self::Updated { new, .. } => &new.name,
+        }
+    }
+}
+
+/// Compares two lists of local models, returning the removed models first, then the added and updated ones
+/// in the order of `new`. Models are compared by digest, or by modification date for servers not reporting digests.
+pub fn diff_local_models(old: &[LocalModel], new: &[LocalModel]) -> Vec<LocalModelEvent> {
+    let old_by_name = old
+        .iter()
+        .map(|m| (m.name.as_str(), m))
+        .collect::<HashMap<_, _>>();
+    let new_by_name = new
+        .iter()
+        .map(|m| (m.name.as_str(), m))
+        .collect::<HashMap<_, _>>();
+
+    let mut events = old
+        .iter()
+        .filter(|m| !new_by_name.contains_key(m.name.as_str()))
+        .map(|m| LocalModelEvent::Removed(m.clone()))
+        .collect::<Vec<_>>();
+
+    for model in new {
+        match old_by_name.get(model.name.as_str()) {
+            None => events.push(LocalModelEvent::Added(model.clone())),
+            Some(old) if old.digest != model.digest || old.modified_at != model.modified_at => {
+                events.push(LocalModelEvent::Updated {
+                    old: Box::new((*old).clone()),
+                    new: Box::new(model.clone()),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    events
+}
+
+impl Ollama {
+    /// Watch the local models for changes made out-of-band, by polling [`Ollama::list_local_models`] every `interval`.
+    ///
+    /// The models present when the stream is first polled are not reported. Failed polls yield an error,
+    /// and the stream keeps polling.
+    pub fn watch_local_models(&self, interval: Duration) -> LocalModelEventStream {
+        let ollama = self.clone();
+
+        let s = stream! {
+            let mut known: Option<Vec<LocalModel>> = None;
+            let mut first_poll = true;
+            loop {
+                if !std::mem::take(&mut first_poll) {
+                    crate::runtime::sleep(interval).await;
+                }
+
+                let models = match ollama.list_local_models().await {
+                    Ok(models) => models,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                };
+
+                if let Some(known) = &known {
+                    for event in diff_local_models(known, &models) {
+                        yield Ok(event);
+                    }
+                }
+                known = Some(models);
+            }
+        };
+
+        Box::pin(s)
+    }
+}
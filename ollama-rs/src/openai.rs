@@ -0,0 +1,21 @@
+/// Modules targeting Ollama's OpenAI-compatible endpoints.
+///
+/// These modules provide OpenAI-shaped request and response types for the
+/// `/v1/chat/completions` and `/v1/embeddings` endpoints, easing the migration
+/// of code written against OpenAI SDKs.
+pub mod chat;
+pub mod embeddings;
+
+use serde::{Deserialize, Serialize};
+
+/// Token usage reported by the OpenAI-compatible endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Usage {
+    /// Number of tokens in the prompt
+    pub prompt_tokens: u64,
+    /// Number of tokens in the completion, absent for embeddings
+    #[serde(default)]
+    pub completion_tokens: u64,
+    /// Total number of tokens used by the request
+    pub total_tokens: u64,
+}
@@ -0,0 +1,595 @@
+use base64::Engine;
+use schemars::Schema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::OllamaError,
+    generation::{
+        chat::{ChatMessage, MessageRole},
+        images::{Image, ImageFormat},
+        tools::{ToolCall, ToolCallFunction, ToolInfo},
+    },
+    transport::EndpointClass,
+    Ollama,
+};
+
+use super::Usage;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+/// A stream of `ChatCompletionChunk` objects
+pub type ChatCompletionChunkStream =
+    crate::BoxStream<'static, crate::error::Result<ChatCompletionChunk>>;
+
+impl Ollama {
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    /// Chat completion with streaming through the OpenAI-compatible `/v1/chat/completions` endpoint.
+    /// Returns a stream of `ChatCompletionChunk` objects
+    pub async fn openai_chat_completion_stream(
+        &self,
+        mut request: ChatCompletionRequest,
+    ) -> crate::error::Result<ChatCompletionChunkStream> {
+        use async_stream::stream;
+        use tokio_stream::StreamExt;
+
+        request.stream = true;
+        request.model = self.resolve_model_alias(request.model);
+
+        let url = self.endpoint_url("v1/chat/completions");
+        let builder = self.reqwest_client.post(url);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self
+            .send_stream(builder.json(&request), EndpointClass::Generation)
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_response(res).await);
+        }
+
+        let lines =
+            crate::timeout::response_lines(res, self.stream_idle_timeout, self.stream_buffer);
+        let s = stream! {
+            let mut lines = lines;
+            while let Some(chunk_lines) = lines.next().await {
+                let chunk_lines = match chunk_lines {
+                    Ok(chunk_lines) => chunk_lines,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+
+                // Server-sent events are separated by new lines, only their data is read
+                for line in chunk_lines {
+                    let Some(data) = line.strip_prefix(b"data:") else {
+                        continue;
+                    };
+                    let data = data.trim_ascii();
+
+                    if data == b"[DONE]" {
+                        return;
+                    }
+
+                    yield serde_json::from_slice::<ChatCompletionChunk>(data).map_err(OllamaError::from);
+                }
+            }
+        };
+
+        Ok(Box::pin(s))
+    }
+
+    /// Chat completion through the OpenAI-compatible `/v1/chat/completions` endpoint.
+    /// Returns a `ChatCompletionResponse` object
+    pub async fn openai_chat_completion(
+        &self,
+        mut request: ChatCompletionRequest,
+    ) -> crate::error::Result<ChatCompletionResponse> {
+        request.stream = false;
+        request.model = self.resolve_model_alias(request.model);
+
+        let url = self.endpoint_url("v1/chat/completions");
+        let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self
+            .send(builder.json(&request), EndpointClass::Generation)
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_response(res).await);
+        }
+
+        let bytes = res.bytes().await?;
+        let res = serde_json::from_slice::<ChatCompletionResponse>(&bytes)?;
+
+        Ok(res)
+    }
+}
+
+/// An OpenAI-shaped chat completion request.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatCompletionMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ChatCompletionTool>,
+    pub(crate) stream: bool,
+}
+
+impl ChatCompletionRequest {
+    pub fn new(model: String, messages: Vec<ChatCompletionMessage>) -> Self {
+        Self {
+            model,
+            messages,
+            temperature: None,
+            top_p: None,
+            max_tokens: None,
+            seed: None,
+            stop: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            response_format: None,
+            tools: vec![],
+            // Stream value will be overwritten by Ollama::openai_chat_completion_stream() and Ollama::openai_chat_completion() methods
+            stream: false,
+        }
+    }
+
+    /// The sampling temperature, higher values make the output more random
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Nucleus sampling, only the tokens comprising the `top_p` probability mass are considered
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// The maximum number of tokens to generate
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// The random number seed to use for generation
+    pub fn seed(mut self, seed: i64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sequences where the model will stop generating
+    pub fn stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Penalizes tokens based on their frequency in the text so far
+    pub fn frequency_penalty(mut self, frequency_penalty: f32) -> Self {
+        self.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    /// Penalizes tokens based on whether they appear in the text so far
+    pub fn presence_penalty(mut self, presence_penalty: f32) -> Self {
+        self.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    /// The format to return a response in
+    pub fn response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    /// Tools that are available to the LLM
+    pub fn tools(mut self, tools: Vec<ChatCompletionTool>) -> Self {
+        self.tools = tools;
+        self
+    }
+}
+
+/// The format of an OpenAI-shaped chat completion response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    Text,
+    JsonObject,
+}
+
+/// The role of the author of an OpenAI-shaped message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatCompletionRole {
+    System,
+    User,
+    Assistant,
+    Tool,
+    /// Any other role, passed through as is
+    #[serde(untagged)]
+    Custom(String),
+}
+
+/// An OpenAI-shaped chat message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionMessage {
+    pub role: ChatCompletionRole,
+    #[serde(default)]
+    pub content: Option<ChatCompletionContent>,
+    /// The thinking of the model, only set on assistant messages of thinking models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ChatCompletionToolCall>,
+    /// The ID of the tool call this message answers, only set on tool messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// The content of an OpenAI-shaped message, either plain text or a list of parts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ChatCompletionContent {
+    Text(String),
+    Parts(Vec<ChatCompletionContentPart>),
+}
+
+impl ChatCompletionContent {
+    /// Returns the text of the content, the text parts are concatenated
+    pub fn text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ChatCompletionContentPart::Text { text } => Some(text.as_str()),
+                    ChatCompletionContentPart::ImageUrl { .. } => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<String> for ChatCompletionContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+/// A part of the content of an OpenAI-shaped message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatCompletionContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ChatCompletionImageUrl },
+}
+
+/// An image attached to an OpenAI-shaped message. Ollama only accepts base64 `data:` URLs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChatCompletionImageUrl {
+    pub url: String,
+}
+
+impl From<&Image> for ChatCompletionImageUrl {
+    fn from(image: &Image) -> Self {
+        let data = image.to_base64();
+        // Enough base64 characters to decode the magic bytes of the image
+        let head = &data[..data.len().min(16)];
+        let format = base64::engine::general_purpose::STANDARD
+            .decode(head)
+            .ok()
+            .and_then(|bytes| ImageFormat::detect(&bytes));
+        let mime_type = match format {
+            Some(ImageFormat::Jpeg) => "image/jpeg",
+            Some(ImageFormat::WebP) => "image/webp",
+            Some(ImageFormat::Png) | None => "image/png",
+        };
+
+        Self {
+            url: format!("data:{mime_type};base64,{data}"),
+        }
+    }
+}
+
+impl TryFrom<ChatCompletionImageUrl> for Image {
+    type Error = OllamaError;
+
+    fn try_from(image_url: ChatCompletionImageUrl) -> Result<Self, Self::Error> {
+        image_url
+            .url
+            .strip_prefix("data:")
+            .and_then(|url| url.split_once(";base64,"))
+            .map(|(_, data)| Image::from_base64(data))
+            .ok_or_else(|| {
+                OllamaError::Other(format!(
+                    "Only base64 data URLs can be converted to an image, got {}",
+                    image_url.url
+                ))
+            })
+    }
+}
+
+impl ChatCompletionMessage {
+    pub fn new(role: ChatCompletionRole, content: String) -> Self {
+        Self {
+            role,
+            content: Some(content.into()),
+            reasoning: None,
+            tool_calls: vec![],
+            tool_call_id: None,
+        }
+    }
+
+    pub fn system(content: String) -> Self {
+        Self::new(ChatCompletionRole::System, content)
+    }
+
+    pub fn user(content: String) -> Self {
+        Self::new(ChatCompletionRole::User, content)
+    }
+
+    pub fn assistant(content: String) -> Self {
+        Self::new(ChatCompletionRole::Assistant, content)
+    }
+
+    /// Creates a tool message answering the tool call with the given ID
+    pub fn tool(content: String, tool_call_id: String) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id),
+            ..Self::new(ChatCompletionRole::Tool, content)
+        }
+    }
+}
+
+impl From<MessageRole> for ChatCompletionRole {
+    fn from(role: MessageRole) -> Self {
+        match role {
+            MessageRole::System => Self::System,
+            MessageRole::User => Self::User,
+            MessageRole::Assistant => Self::Assistant,
+            MessageRole::Tool => Self::Tool,
+            MessageRole::Custom(role) => Self::Custom(role),
+        }
+    }
+}
+
+impl From<ChatCompletionRole> for MessageRole {
+    fn from(role: ChatCompletionRole) -> Self {
+        match role {
+            ChatCompletionRole::System => Self::System,
+            ChatCompletionRole::User => Self::User,
+            ChatCompletionRole::Assistant => Self::Assistant,
+            ChatCompletionRole::Tool => Self::Tool,
+            ChatCompletionRole::Custom(role) => Self::Custom(role),
+        }
+    }
+}
+
+impl From<ChatMessage> for ChatCompletionMessage {
+    fn from(message: ChatMessage) -> Self {
+        let images = message.images.unwrap_or_default();
+        let content = if images.is_empty() {
+            ChatCompletionContent::Text(message.content)
+        } else {
+            let text = (!message.content.is_empty()).then_some(ChatCompletionContentPart::Text {
+                text: message.content,
+            });
+            let images = images
+                .iter()
+                .map(|image| ChatCompletionContentPart::ImageUrl {
+                    image_url: image.into(),
+                });
+            ChatCompletionContent::Parts(text.into_iter().chain(images).collect())
+        };
+
+        Self {
+            role: message.role.into(),
+            content: Some(content),
+            reasoning: message.thinking,
+            tool_calls: message.tool_calls.into_iter().map(Into::into).collect(),
+            tool_call_id: message.tool_call_id,
+        }
+    }
+}
+
+/// Fails if an image isn't a base64 `data:` URL or if the arguments of a tool call aren't valid JSON.
+impl TryFrom<ChatCompletionMessage> for ChatMessage {
+    type Error = OllamaError;
+
+    fn try_from(message: ChatCompletionMessage) -> Result<Self, Self::Error> {
+        let mut images = vec![];
+        let content = match message.content {
+            None => String::new(),
+            Some(ChatCompletionContent::Text(text)) => text,
+            Some(ChatCompletionContent::Parts(parts)) => {
+                let mut text = String::new();
+                for part in parts {
+                    match part {
+                        ChatCompletionContentPart::Text { text: part } => text.push_str(&part),
+                        ChatCompletionContentPart::ImageUrl { image_url } => {
+                            images.push(image_url.try_into()?)
+                        }
+                    }
+                }
+                text
+            }
+        };
+
+        let mut out = ChatMessage::new(message.role.into(), content);
+        out.images = (!images.is_empty()).then_some(images);
+        out.thinking = message.reasoning;
+        out.tool_calls = message
+            .tool_calls
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+        out.tool_call_id = message.tool_call_id;
+        Ok(out)
+    }
+}
+
+/// A tool made available to the LLM, in the OpenAI shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionTool {
+    #[serde(rename = "type")]
+    pub tool_type: String,
+    pub function: ChatCompletionFunction,
+}
+
+/// The description of a function tool, in the OpenAI shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionFunction {
+    pub name: String,
+    pub description: String,
+    pub parameters: Schema,
+}
+
+impl From<ToolInfo> for ChatCompletionTool {
+    fn from(info: ToolInfo) -> Self {
+        Self {
+            tool_type: "function".to_string(),
+            function: ChatCompletionFunction {
+                name: info.function.name,
+                description: info.function.description,
+                parameters: info.function.parameters,
+            },
+        }
+    }
+}
+
+/// A tool call requested by the LLM, in the OpenAI shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionToolCall {
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub call_type: String,
+    pub function: ChatCompletionFunctionCall,
+}
+
+impl From<ToolCall> for ChatCompletionToolCall {
+    fn from(call: ToolCall) -> Self {
+        Self {
+            id: call.id.unwrap_or_default(),
+            call_type: function_type(),
+            function: ChatCompletionFunctionCall {
+                name: call.function.name,
+                arguments: call.function.arguments.to_string(),
+            },
+        }
+    }
+}
+
+/// Fails with [`OllamaError::JsonError`] if the arguments aren't valid JSON.
+impl TryFrom<ChatCompletionToolCall> for ToolCall {
+    type Error = OllamaError;
+
+    fn try_from(call: ChatCompletionToolCall) -> Result<Self, Self::Error> {
+        // OpenAI encodes the arguments as a string
+        let arguments = serde_json::from_str(&call.function.arguments)?;
+
+        Ok(Self {
+            id: (!call.id.is_empty()).then_some(call.id),
+            function: ToolCallFunction {
+                name: call.function.name,
+                arguments,
+            },
+        })
+    }
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+/// The function called by a tool call. The arguments are a JSON-encoded string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionFunctionCall {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub arguments: String,
+}
+
+/// An OpenAI-shaped chat completion response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionResponse {
+    pub id: String,
+    pub object: String,
+    /// The creation time of the completion, as a Unix timestamp in seconds
+    pub created: u64,
+    pub model: String,
+    #[serde(default)]
+    pub system_fingerprint: Option<String>,
+    pub choices: Vec<ChatCompletionChoice>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+/// A completion choice of an OpenAI-shaped response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChoice {
+    pub index: u32,
+    pub message: ChatCompletionMessage,
+    /// Why the model stopped generating, e.g. `stop`, `length` or `tool_calls`
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// A chunk of a streamed OpenAI-shaped chat completion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: String,
+    /// The creation time of the completion, as a Unix timestamp in seconds
+    pub created: u64,
+    pub model: String,
+    #[serde(default)]
+    pub system_fingerprint: Option<String>,
+    pub choices: Vec<ChatCompletionChunkChoice>,
+}
+
+/// A completion choice of a streamed OpenAI-shaped chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunkChoice {
+    pub index: u32,
+    pub delta: ChatCompletionDelta,
+    /// Why the model stopped generating, only set on the last chunk
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// The part of a message carried by a streamed chunk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionDelta {
+    #[serde(default)]
+    pub role: Option<ChatCompletionRole>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ChatCompletionToolCall>,
+}
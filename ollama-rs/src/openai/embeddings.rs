@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::OllamaError, generation::embeddings::request::EmbeddingsInput, transport::EndpointClass,
+    Ollama,
+};
+
+use super::Usage;
+
+impl Ollama {
+    /// Generate embeddings through the OpenAI-compatible `/v1/embeddings` endpoint.
+    pub async fn openai_embeddings(
+        &self,
+        mut request: EmbeddingRequest,
+    ) -> crate::error::Result<EmbeddingResponse> {
+        request.model = self.resolve_model_alias(request.model);
+
+        let url = self.endpoint_url("v1/embeddings");
+        let builder = self.reqwest_client.post(url);
+        let builder = self.with_request_timeout(builder);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self
+            .send(builder.json(&request), EndpointClass::Embeddings)
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_response(res).await);
+        }
+
+        let res = res.bytes().await?;
+        let res = serde_json::from_slice::<EmbeddingResponse>(&res)?;
+
+        Ok(res)
+    }
+}
+
+/// An OpenAI-shaped embeddings request.
+#[derive(Debug, Serialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub input: EmbeddingsInput,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+}
+
+impl EmbeddingRequest {
+    pub fn new(model: String, input: EmbeddingsInput) -> Self {
+        Self {
+            model,
+            input,
+            dimensions: None,
+        }
+    }
+
+    /// The number of dimensions the resulting embeddings should have, for models that support it
+    pub fn dimensions(mut self, dimensions: u32) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
+}
+
+/// An OpenAI-shaped embeddings response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingResponse {
+    pub object: String,
+    pub data: Vec<Embedding>,
+    pub model: String,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+/// A single embedding of an OpenAI-shaped embeddings response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Embedding {
+    pub object: String,
+    pub embedding: Vec<f32>,
+    /// The position of the matching input
+    pub index: usize,
+}
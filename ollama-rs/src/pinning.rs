@@ -0,0 +1,164 @@
+//! The verification of the certificates of the server against pins, see [`crate::builder::OllamaBuilder::pin_certificate_sha256`].
+
+use std::sync::Arc;
+
+use base64::Engine;
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::CryptoProvider,
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    DigitallySignedStruct, SignatureScheme,
+};
+use sha2::{Digest, Sha256};
+
+use crate::error::OllamaError;
+
+/// The SHA-256 digest of the certificate of the server, or of its public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Pin {
+    Certificate([u8; 32]),
+    PublicKey([u8; 32]),
+}
+
+impl Pin {
+    /// Parses a base64-encoded SHA-256 digest, the format of `pin-sha256`
+    pub(crate) fn parse(pin: &str, variant: fn([u8; 32]) -> Self) -> crate::error::Result<Self> {
+        let digest = base64::engine::general_purpose::STANDARD
+            .decode(pin.trim())
+            .ok()
+            .and_then(|digest| <[u8; 32]>::try_from(digest).ok())
+            .ok_or_else(|| {
+                OllamaError::Other(format!(
+                    "Invalid pin {pin:?}, expected a base64 SHA-256 digest"
+                ))
+            })?;
+        Ok(variant(digest))
+    }
+
+    fn matches(&self, certificate: &[u8]) -> bool {
+        match self {
+            Self::Certificate(digest) => Sha256::digest(certificate)[..] == digest[..],
+            Self::PublicKey(digest) => subject_public_key_info(certificate)
+                .is_some_and(|spki| Sha256::digest(spki)[..] == digest[..]),
+        }
+    }
+}
+
+/// Trusts the servers whose certificate matches one of the pins, instead of the certificate authorities
+#[derive(Debug)]
+struct PinnedVerifier {
+    pins: Vec<Pin>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.pins.iter().any(|pin| pin.matches(end_entity)) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "The certificate of the server doesn't match the pins".to_string(),
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// A TLS configuration trusting the servers matching the pins, offering the HTTP versions in `alpn`
+pub(crate) fn pinned_tls_config(
+    pins: Vec<Pin>,
+    alpn: Vec<Vec<u8>>,
+) -> crate::error::Result<rustls::ClientConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| OllamaError::Other(format!("Invalid TLS configuration: {e}")))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedVerifier { pins, provider }))
+        .with_no_client_auth();
+    config.alpn_protocols = alpn;
+    Ok(config)
+}
+
+/// Splits a DER element into its tag, the length of its header and its total length
+fn der_element(input: &[u8]) -> Option<(u8, usize, usize)> {
+    let tag = *input.first()?;
+    let first = *input.get(1)? as usize;
+    let (header, length) = if first < 0x80 {
+        (2, first)
+    } else {
+        let count = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let bytes = input.get(2..2 + count)?;
+        (
+            2 + count,
+            bytes
+                .iter()
+                .fold(0, |length, &b| (length << 8) | b as usize),
+        )
+    };
+    let total = header.checked_add(length)?;
+    (total <= input.len()).then_some((tag, header, total))
+}
+
+/// The `subjectPublicKeyInfo` of a DER certificate, whose digest is the pin of its public key
+fn subject_public_key_info(certificate: &[u8]) -> Option<&[u8]> {
+    let (_, header, total) = der_element(certificate)?;
+    let certificate = certificate.get(header..total)?;
+    let (_, header, total) = der_element(certificate)?;
+    let mut fields = certificate.get(header..total)?;
+
+    // The version is optional, then come the serial number, the signature algorithm, the issuer,
+    // the validity and the subject
+    if der_element(fields)?.0 == 0xa0 {
+        fields = &fields[der_element(fields)?.2..];
+    }
+    for _ in 0..5 {
+        fields = &fields[der_element(fields)?.2..];
+    }
+
+    let (_, _, total) = der_element(fields)?;
+    Some(&fields[..total])
+}
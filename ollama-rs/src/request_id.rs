@@ -0,0 +1,71 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use reqwest::header::{HeaderName, HeaderValue};
+
+use crate::Ollama;
+
+impl Ollama {
+    /// Sends an ID with every request in a header, such as `x-request-id`, to find the requests of the client
+    /// in the logs of Ollama or of a reverse proxy. `None` (the default) sends no ID.
+    ///
+    /// A request already carrying the header, such as one set with the default headers of the client
+    /// to propagate the ID of an incoming request, keeps its ID. The errors of the requests, whether
+    /// they failed to get a response or Ollama answered with an error, carry their ID, see
+    /// [`crate::error::OllamaError::request_id`], and the interceptors see it in
+    /// [`crate::interceptor::Exchange::request_id`].
+    ///
+    /// ```
+    /// use ollama_rs::Ollama;
+    /// use reqwest::header::HeaderName;
+    ///
+    /// let mut ollama = Ollama::default();
+    /// ollama.set_request_id_header(Some(HeaderName::from_static("x-request-id")));
+    /// ```
+    pub fn set_request_id_header(&mut self, header: Option<HeaderName>) {
+        self.request_id_header = header;
+    }
+
+    /// Returns the header carrying the ID of the requests, if any
+    pub fn request_id_header(&self) -> Option<&HeaderName> {
+        self.request_id_header.as_ref()
+    }
+
+    /// Attaches an ID to a request, keeping the one it already has if any
+    pub(crate) fn attach_request_id(&self, request: &mut reqwest::Request) -> Option<String> {
+        let header = self.request_id_header.as_ref()?;
+
+        if let Some(id) = request.headers().get(header) {
+            if let Ok(id) = id.to_str() {
+                return Some(id.to_string());
+            }
+        }
+
+        let id = generate_request_id();
+        // An ID of hex digits is always a valid header value
+        let value = HeaderValue::from_str(&id).expect("Invalid request ID");
+        request.headers_mut().insert(header.clone(), value);
+        Some(id)
+    }
+}
+
+/// The ID of the request of a response, to attach it to the error read from the response,
+/// see [`crate::error::OllamaError::from_response`]
+#[derive(Debug, Clone)]
+pub(crate) struct RequestId(pub(crate) String);
+
+/// A random ID of 32 hex digits
+fn generate_request_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let state = RandomState::new();
+    format!(
+        "{:016x}{:016x}",
+        state.hash_one(count),
+        state.hash_one(!count)
+    )
+}
@@ -0,0 +1,226 @@
+use std::{collections::hash_map::RandomState, hash::BuildHasher, sync::Mutex, time::Duration};
+
+use crate::{error::OllamaError, transport::EndpointClass, Ollama};
+
+/// How to retry the failed requests of an [`EndpointClass`], see [`Ollama::set_retry_policy`].
+///
+/// Connection errors, timeouts and the `429`, `502`, `503` and `504` statuses are retried with an
+/// exponential backoff. A `Retry-After` header sent by the server takes precedence over the backoff.
+///
+/// ```
+/// use std::time::Duration;
+/// use ollama_rs::{retry::RetryPolicy, transport::EndpointClass, Ollama};
+///
+/// let mut ollama = Ollama::default();
+/// ollama.set_retry_policy(
+///     EndpointClass::Embeddings,
+///     Some(RetryPolicy::new().max_retries(5).initial_backoff(Duration::from_millis(100))),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of retries after the first attempt (Default: 3)
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The delay before the first retry, doubled at every retry (Default: 200ms)
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// The longest delay between two attempts, including the delays asked by `Retry-After` (Default: 10s)
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Whether to randomize each delay between half and all of the backoff, so that clients failing
+    /// at the same time don't retry at the same time (Default: true)
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// The backoff before the retry `attempt`, starting at 1, without jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.backoff(attempt);
+        if !self.jitter {
+            return backoff;
+        }
+
+        // Every `RandomState` is seeded differently, which is enough randomness for a jitter
+        let random = RandomState::new().hash_one(attempt) as f64 / u64::MAX as f64;
+        backoff.mul_f64(0.5 + random / 2.0)
+    }
+}
+
+/// Limits the retries of a client to a share of its traffic, so that an outage of the server
+/// doesn't multiply the load with retries, see [`Ollama::set_retry_budget`].
+///
+/// Every request adds `ratio` to the budget, up to `capacity`, and every retry spends one.
+/// The budget starts full: a client can retry `capacity` times in a row, then, under sustained failures,
+/// about `ratio` retries per request. The budget is shared by the clones of the client.
+#[derive(Debug)]
+pub struct RetryBudget {
+    ratio: f64,
+    capacity: f64,
+    balance: Mutex<f64>,
+}
+
+impl RetryBudget {
+    /// * `ratio` - The share of the requests that may be retried, such as `0.2`
+    /// * `capacity` - The largest number of retries that can be saved up
+    pub fn new(ratio: f64, capacity: u32) -> Self {
+        Self {
+            ratio: ratio.max(0.0),
+            capacity: capacity as f64,
+            balance: Mutex::new(capacity as f64),
+        }
+    }
+
+    /// The number of retries currently available
+    pub fn available(&self) -> u32 {
+        *self.balance.lock().unwrap() as u32
+    }
+
+    fn deposit(&self) {
+        let mut balance = self.balance.lock().unwrap();
+        *balance = (*balance + self.ratio).min(self.capacity);
+    }
+
+    fn try_withdraw(&self) -> bool {
+        let mut balance = self.balance.lock().unwrap();
+        if *balance >= 1.0 {
+            *balance -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Ollama {
+    /// Retries the failed requests of an endpoint class with a policy, or disables their retries with `None`.
+    /// No requests are retried by default.
+    ///
+    /// Only enable it for the classes whose requests can safely be sent twice, which are
+    /// [`EndpointClass::Metadata`], [`EndpointClass::Embeddings`] and [`EndpointClass::Pull`].
+    /// Only the sending of the request is retried, not a streamed response failing midway.
+    pub fn set_retry_policy(&mut self, class: EndpointClass, policy: Option<RetryPolicy>) {
+        match policy {
+            Some(policy) => self.retry_policies.insert(class, policy),
+            None => self.retry_policies.remove(&class),
+        };
+    }
+
+    /// Returns the retry policy of an endpoint class, if any
+    pub fn retry_policy(&self, class: EndpointClass) -> Option<&RetryPolicy> {
+        self.retry_policies.get(&class)
+    }
+
+    /// Limits the retries of all the endpoint classes with a budget, see [`RetryBudget`].
+    /// `None` (the default) only limits them with the policies.
+    pub fn set_retry_budget(&mut self, budget: Option<RetryBudget>) {
+        self.retry_budget = budget.map(std::sync::Arc::new);
+    }
+
+    /// Returns the retry budget, if any
+    pub fn retry_budget(&self) -> Option<&RetryBudget> {
+        self.retry_budget.as_deref()
+    }
+
+    pub(crate) async fn execute_with_retry(
+        &self,
+        request: reqwest::Request,
+        class: EndpointClass,
+        policy: &RetryPolicy,
+        streaming: bool,
+    ) -> crate::error::Result<reqwest::Response> {
+        if let Some(budget) = &self.retry_budget {
+            budget.deposit();
+        }
+
+        let mut attempt = 0;
+        loop {
+            // The last attempt sends the original request, as do the requests with a streamed body,
+            // which can't be sent twice
+            let attempt_request = if attempt < policy.max_retries {
+                request.try_clone()
+            } else {
+                None
+            };
+            let Some(attempt_request) = attempt_request else {
+                return self.transmit(request, streaming).await;
+            };
+
+            let res = self.transmit(attempt_request, streaming).await;
+            let (retry_after, reason) = match &res {
+                Ok(res) if crate::transport::is_retryable_status(res.status()) => {
+                    (retry_after(res), res.status().to_string())
+                }
+                Err(OllamaError::ReqwestError(e))
+                    if crate::transport::is_connect_error(e) || e.is_timeout() =>
+                {
+                    (None, e.to_string())
+                }
+                _ => return res,
+            };
+
+            if let Some(budget) = &self.retry_budget {
+                if !budget.try_withdraw() {
+                    return res;
+                }
+            }
+
+            attempt += 1;
+            let delay = match retry_after {
+                Some(retry_after) => retry_after.min(policy.max_backoff),
+                None => policy.delay(attempt),
+            };
+            log::warn!(
+                "{class:?} request to {} failed, retrying in {delay:?} (attempt {attempt}): {reason}",
+                request.url()
+            );
+            crate::runtime::sleep(delay).await;
+        }
+    }
+}
+
+/// The delay of a `Retry-After` header in seconds. HTTP dates are ignored.
+fn retry_after(res: &reqwest::Response) -> Option<Duration> {
+    let value = res.headers().get(reqwest::header::RETRY_AFTER)?;
+    let seconds = value.to_str().ok()?.trim().parse::<u64>().ok()?;
+    Some(Duration::from_secs(seconds))
+}
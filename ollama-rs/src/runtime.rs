@@ -0,0 +1,46 @@
+//! The timers used by the crate, which are the only pieces tied to an async runtime besides
+//! the tasks spawned by the concurrent helpers. The streams of the crate implement the
+//! runtime-agnostic `futures_core::Stream` trait, re-exported by `tokio_stream`.
+//!
+//! In browsers, the timers run on the `setTimeout` of the JavaScript event loop instead of Tokio.
+
+use std::time::Duration;
+
+/// Waits for `duration`
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// Waits for `duration`
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await
+}
+
+/// Waits until `deadline`
+#[cfg(feature = "rate-limit")]
+pub(crate) async fn sleep_until(deadline: crate::Instant) {
+    sleep(deadline.saturating_duration_since(crate::Instant::now())).await
+}
+
+/// Runs a future, returning `None` if it didn't complete within `duration`
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub(crate) async fn timeout<F: std::future::Future>(
+    duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    tokio::time::timeout(duration, future).await.ok()
+}
+
+/// Runs a future, returning `None` if it didn't complete within `duration`
+#[cfg(all(feature = "stream", target_arch = "wasm32"))]
+pub(crate) async fn timeout<F: std::future::Future>(
+    duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    tokio::select! {
+        output = future => Some(output),
+        _ = sleep(duration) => None,
+    }
+}
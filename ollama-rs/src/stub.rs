@@ -0,0 +1,329 @@
+//! A transport answering the requests itself, to develop and demo an application without an Ollama server, see [`StubTransport`].
+
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::transport::{BoxFuture, Transport};
+
+/// A response of a [`StubTransport`]
+#[derive(Debug, Clone)]
+pub struct StubResponse {
+    status: u16,
+    body: StubBody,
+}
+
+#[derive(Debug, Clone)]
+enum StubBody {
+    Json(Value),
+    Lines(Vec<Value>),
+    Text(String),
+}
+
+impl StubResponse {
+    /// A JSON body, such as the response of a request with `stream: false`
+    pub fn json(body: Value) -> Self {
+        Self {
+            status: 200,
+            body: StubBody::Json(body),
+        }
+    }
+
+    /// A stream of JSON objects, one per line, such as the chunks of a request with `stream: true`
+    pub fn stream(chunks: impl IntoIterator<Item = Value>) -> Self {
+        Self {
+            status: 200,
+            body: StubBody::Lines(chunks.into_iter().collect()),
+        }
+    }
+
+    /// A plain text body
+    pub fn text(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            body: StubBody::Text(body.into()),
+        }
+    }
+
+    /// An error, with the body `{"error": message}` of the errors of Ollama
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: StubBody::Json(json!({ "error": message.into() })),
+        }
+    }
+
+    /// Sets the status of the response (Default: 200)
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    fn into_response(self) -> crate::error::Result<reqwest::Response> {
+        let builder = http::Response::builder().status(self.status);
+        let res = match self.body {
+            StubBody::Json(body) => builder
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(reqwest::Body::from(body.to_string())),
+            StubBody::Text(body) => builder
+                .header(http::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(reqwest::Body::from(body)),
+            StubBody::Lines(chunks) => builder
+                .header(http::header::CONTENT_TYPE, "application/x-ndjson")
+                .body(ndjson_body(chunks)),
+        };
+        res.map(reqwest::Response::from)
+            .map_err(|e| crate::error::OllamaError::Other(format!("Invalid stub response: {e}")))
+    }
+}
+
+/// The body of a stream, read chunk by chunk as it would arrive from Ollama
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+fn ndjson_body(chunks: Vec<Value>) -> reqwest::Body {
+    let chunks = chunks
+        .into_iter()
+        .map(|chunk| Ok::<_, std::io::Error>(format!("{chunk}\n").into_bytes()));
+    reqwest::Body::wrap_stream(tokio_stream::iter(chunks))
+}
+
+#[cfg(any(not(feature = "stream"), target_arch = "wasm32"))]
+fn ndjson_body(chunks: Vec<Value>) -> reqwest::Body {
+    let body: String = chunks.iter().map(|chunk| format!("{chunk}\n")).collect();
+    reqwest::Body::from(body)
+}
+
+type Handler = dyn Fn(&Value) -> StubResponse + Send + Sync;
+
+/// A [`Transport`] answering the requests with canned or templated responses, without any network,
+/// so that an application can be developed and demoed without a GPU or a running Ollama.
+///
+/// The responses are configured per endpoint, named by its path such as `api/chat`, and are
+/// otherwise the defaults of the stub:
+///
+/// - `api/generate` and `api/chat` answer the reply of [`StubTransport::reply`], streamed word by
+///   word when the request has `stream: true`
+/// - `api/embed` answers deterministic embeddings of [`StubTransport::embedding_dimensions`]
+///   dimensions, the same for the same input
+/// - `api/tags` and `api/ps` answer no models, `api/version` the version of
+///   [`StubTransport::server_version`], and the root of the server that it is running
+/// - The other endpoints answer `404 Not Found`
+///
+/// ```
+/// use ollama_rs::{stub::{StubResponse, StubTransport}, Ollama};
+/// use serde_json::json;
+///
+/// let stub = StubTransport::new()
+///     .reply("Sure! Here is what I found about {prompt}.")
+///     .respond("api/tags", StubResponse::json(json!({ "models": [] })))
+///     .respond_with("api/show", |request| {
+///         let model = request["model"].as_str().unwrap_or_default();
+///         StubResponse::error(404, format!("model '{model}' not found"))
+///     });
+/// let ollama = Ollama::builder().stub(stub).build().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct StubTransport {
+    routes: HashMap<String, Arc<Handler>>,
+    reply: String,
+    embedding_dimensions: usize,
+    server_version: String,
+}
+
+impl Default for StubTransport {
+    fn default() -> Self {
+        Self {
+            routes: HashMap::new(),
+            reply: "This is a stub response to: {prompt}".to_string(),
+            embedding_dimensions: 16,
+            server_version: "0.9.0".to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for StubTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut routes: Vec<_> = self.routes.keys().collect();
+        routes.sort();
+        f.debug_struct("StubTransport")
+            .field("routes", &routes)
+            .field("reply", &self.reply)
+            .field("embedding_dimensions", &self.embedding_dimensions)
+            .field("server_version", &self.server_version)
+            .finish()
+    }
+}
+
+impl StubTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers the requests to an endpoint, such as `api/chat`, with the same response
+    pub fn respond(self, endpoint: impl Into<String>, response: StubResponse) -> Self {
+        self.respond_with(endpoint, move |_| response.clone())
+    }
+
+    /// Answers the requests to an endpoint, such as `api/chat`, with a response built from the
+    /// JSON body of each request, `null` for the requests without a body
+    pub fn respond_with(
+        mut self,
+        endpoint: impl Into<String>,
+        handler: impl Fn(&Value) -> StubResponse + Send + Sync + 'static,
+    ) -> Self {
+        let endpoint = endpoint.into();
+        let endpoint = endpoint.trim_matches('/').to_string();
+        self.routes.insert(endpoint, Arc::new(handler));
+        self
+    }
+
+    /// The reply of the default completions and chats, where `{prompt}` is replaced by the prompt,
+    /// or by the last message of the user (Default: `"This is a stub response to: {prompt}"`)
+    pub fn reply(mut self, reply: impl Into<String>) -> Self {
+        self.reply = reply.into();
+        self
+    }
+
+    /// The number of dimensions of the default embeddings (Default: 16)
+    pub fn embedding_dimensions(mut self, dimensions: usize) -> Self {
+        self.embedding_dimensions = dimensions;
+        self
+    }
+
+    /// The version of the default `api/version` (Default: `"0.9.0"`)
+    pub fn server_version(mut self, version: impl Into<String>) -> Self {
+        self.server_version = version.into();
+        self
+    }
+
+    fn respond_to(&self, request: &reqwest::Request) -> StubResponse {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .and_then(|body| serde_json::from_slice(body).ok())
+            .unwrap_or(Value::Null);
+
+        // The endpoints are named from `api/` or `v1/` on, whatever the path prefix of the client
+        let path = request.url().path();
+        let endpoint = ["api/", "v1/"]
+            .iter()
+            .filter_map(|prefix| path.rfind(prefix))
+            .max()
+            .map_or("", |start| path[start..].trim_end_matches('/'));
+
+        if let Some(handler) = self.routes.get(endpoint) {
+            return handler(&body);
+        }
+
+        match endpoint {
+            "" => StubResponse::text("Ollama is running"),
+            "api/generate" => self.generate(&body),
+            "api/chat" => self.chat(&body),
+            "api/embed" => self.embed(&body),
+            "api/tags" | "api/ps" => StubResponse::json(json!({ "models": [] })),
+            "api/version" => StubResponse::json(json!({ "version": self.server_version })),
+            _ => StubResponse::error(404, format!("The stub has no response for {endpoint}")),
+        }
+    }
+
+    fn generate(&self, body: &Value) -> StubResponse {
+        let prompt = body["prompt"].as_str().unwrap_or_default();
+        let reply = self.reply.replace("{prompt}", prompt);
+        completion(body, &reply, |text| json!({ "response": text }))
+    }
+
+    fn chat(&self, body: &Value) -> StubResponse {
+        let prompt = body["messages"]
+            .as_array()
+            .and_then(|messages| {
+                messages
+                    .iter()
+                    .rev()
+                    .find(|message| message["role"] == "user")
+            })
+            .and_then(|message| message["content"].as_str())
+            .unwrap_or_default();
+        let reply = self.reply.replace("{prompt}", prompt);
+        completion(
+            body,
+            &reply,
+            |text| json!({ "message": { "role": "assistant", "content": text } }),
+        )
+    }
+
+    fn embed(&self, body: &Value) -> StubResponse {
+        let inputs = match &body["input"] {
+            Value::Array(inputs) => inputs.iter().collect(),
+            input => vec![input],
+        };
+        let embeddings: Vec<_> = inputs
+            .iter()
+            .map(|input| match input.as_str() {
+                Some(text) => embedding(text, self.embedding_dimensions),
+                None => embedding(&input.to_string(), self.embedding_dimensions),
+            })
+            .collect();
+        StubResponse::json(json!({
+            "model": body["model"],
+            "embeddings": embeddings,
+        }))
+    }
+}
+
+/// A completion or a chat answering `reply`, streamed word by word if requested,
+/// where `content` is the part of a chunk holding its text
+fn completion(body: &Value, reply: &str, content: impl Fn(&str) -> Value) -> StubResponse {
+    let created_at = chrono::Utc::now().to_rfc3339();
+    let chunk = |text: &str, done: bool| {
+        let mut chunk = content(text);
+        chunk["model"] = body["model"].clone();
+        chunk["created_at"] = json!(created_at);
+        chunk["done"] = json!(done);
+        if done {
+            chunk["done_reason"] = json!("stop");
+            chunk["total_duration"] = json!(0);
+            chunk["load_duration"] = json!(0);
+            chunk["prompt_eval_count"] = json!(0);
+            chunk["prompt_eval_duration"] = json!(0);
+            chunk["eval_count"] = json!(reply.split_inclusive(' ').count());
+            chunk["eval_duration"] = json!(0);
+        }
+        chunk
+    };
+
+    if body["stream"] == true {
+        let words = reply.split_inclusive(' ').map(|word| chunk(word, false));
+        StubResponse::stream(words.chain([chunk("", true)]))
+    } else {
+        StubResponse::json(chunk(reply, true))
+    }
+}
+
+/// A unit vector derived from the digest of the input, so that equal inputs have equal embeddings
+fn embedding(input: &str, dimensions: usize) -> Vec<f32> {
+    let values: Vec<f32> = (0u32..)
+        .flat_map(|block| {
+            Sha256::new()
+                .chain_update(block.to_le_bytes())
+                .chain_update(input)
+                .finalize()
+        })
+        .take(dimensions)
+        .map(|byte| byte as f32 / 127.5 - 1.0)
+        .collect();
+    let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+    match norm > 0.0 {
+        true => values.into_iter().map(|v| v / norm).collect(),
+        false => values,
+    }
+}
+
+impl Transport for StubTransport {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, crate::error::Result<reqwest::Response>> {
+        let res = self.respond_to(&request).into_response();
+        Box::pin(async move { res })
+    }
+}
@@ -0,0 +1,274 @@
+//! The spans and the metrics of the requests, emitted with the `tracing` and `metrics` features.
+//!
+//! With `tracing`, each request is a span named `ollama.request`, following the OpenTelemetry
+//! conventions for HTTP clients and generative AI:
+//!
+//! | Field | Value |
+//! | --- | --- |
+//! | `http.request.method`, `url.full`, `http.response.status_code` | The HTTP exchange |
+//! | `ollama.endpoint`, `ollama.stream`, `ollama.request_id` | The path of the endpoint, whether the response is streamed, and the ID of the request if any |
+//! | `gen_ai.system`, `gen_ai.request.model` | `ollama` and the model of the request |
+//! | `gen_ai.usage.input_tokens`, `gen_ai.usage.output_tokens` | The token counts of a generation or a chat |
+//! | `ollama.total_duration_ms`, `ollama.load_duration_ms` | The durations reported by the server for a generation or a chat |
+//!
+//! The span of a streamed response lasts until the stream ends or is dropped.
+//! With the `otel` feature, the context of the span is propagated to the server in the headers of
+//! the global OpenTelemetry propagator, such as `traceparent`.
+//!
+//! With `metrics`, the requests are counted through the `metrics` facade:
+//!
+//! | Metric | Labels |
+//! | --- | --- |
+//! | `ollama_requests_total` (counter) | `endpoint`, `status`: the status code, or `error` if the request failed |
+//! | `ollama_request_duration_seconds` (histogram), until the headers of the response | `endpoint` |
+//! | `ollama_tokens_total` (counter), of the generations and chats | `model`, `type`: `input` or `output`, `stream` |
+//! | `ollama_tool_calls_total` (counter), of the [`crate::coordinator::Coordinator`] | `tool`, `result`: `ok` or `error` |
+
+use crate::{generation::usage::Usage, transport::EndpointClass};
+
+/// The span and the metrics of a request, a no-op without the `tracing` and `metrics` features
+#[derive(Debug, Clone)]
+pub(crate) struct RequestTelemetry {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "metrics")]
+    labels: Option<std::sync::Arc<MetricLabels>>,
+}
+
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+struct MetricLabels {
+    endpoint: String,
+    model: Option<String>,
+    streaming: bool,
+}
+
+impl RequestTelemetry {
+    pub(crate) fn new(
+        request: &mut reqwest::Request,
+        class: EndpointClass,
+        streaming: bool,
+        request_id: Option<&str>,
+    ) -> Self {
+        #[cfg(not(feature = "tracing"))]
+        let _ = (class, request_id);
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = (&request, streaming);
+
+        #[cfg(feature = "tracing")]
+        let span = {
+            use tracing::field::Empty;
+
+            let span = tracing::info_span!(
+                "ollama.request",
+                otel.kind = "client",
+                otel.status_code = Empty,
+                http.request.method = %request.method(),
+                url.full = %request.url(),
+                http.response.status_code = Empty,
+                ollama.endpoint = request.url().path(),
+                ollama.endpoint_class = ?class,
+                ollama.stream = streaming,
+                ollama.request_id = request_id,
+                gen_ai.system = "ollama",
+                gen_ai.request.model = crate::balance::request_model(request),
+                gen_ai.usage.input_tokens = Empty,
+                gen_ai.usage.output_tokens = Empty,
+                ollama.total_duration_ms = Empty,
+                ollama.load_duration_ms = Empty,
+            );
+
+            #[cfg(feature = "otel")]
+            inject_context(&span, request.headers_mut());
+
+            span
+        };
+
+        Self {
+            #[cfg(feature = "tracing")]
+            span,
+            #[cfg(feature = "metrics")]
+            labels: Some(std::sync::Arc::new(MetricLabels {
+                endpoint: request.url().path().to_string(),
+                model: crate::balance::request_model(request),
+                streaming,
+            })),
+        }
+    }
+
+    /// Runs the sending of the request within the span
+    #[cfg(feature = "tracing")]
+    pub(crate) fn instrument<F: std::future::Future>(
+        &self,
+        future: F,
+    ) -> tracing::instrument::Instrumented<F> {
+        tracing::Instrument::instrument(future, self.span.clone())
+    }
+
+    /// Runs the sending of the request within the span
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn instrument<F: std::future::Future>(&self, future: F) -> F {
+        future
+    }
+
+    /// Records the outcome of the request, sent `elapsed` ago, and keeps the span alive as long as the response
+    pub(crate) fn finish(
+        &self,
+        res: crate::error::Result<reqwest::Response>,
+        elapsed: std::time::Duration,
+    ) -> crate::error::Result<reqwest::Response> {
+        #[cfg(not(feature = "metrics"))]
+        let _ = elapsed;
+
+        #[cfg(feature = "tracing")]
+        match &res {
+            Ok(res) => {
+                self.span
+                    .record("http.response.status_code", res.status().as_u16());
+                if res.status().is_client_error() || res.status().is_server_error() {
+                    self.span.record("otel.status_code", "ERROR");
+                }
+            }
+            Err(e) => {
+                self.span.record("otel.status_code", "ERROR");
+                tracing::warn!(parent: &self.span, error = %e, "Request failed");
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(labels) = &self.labels {
+            let status = match &res {
+                Ok(res) => res.status().as_str().to_string(),
+                Err(_) => "error".to_string(),
+            };
+            metrics::counter!(
+                "ollama_requests_total",
+                "endpoint" => labels.endpoint.clone(),
+                "status" => status
+            )
+            .increment(1);
+            metrics::histogram!(
+                "ollama_request_duration_seconds",
+                "endpoint" => labels.endpoint.clone()
+            )
+            .record(elapsed.as_secs_f64());
+        }
+
+        // The responses of the browsers have no extensions, so their spans end with the headers
+        #[cfg(all(
+            any(feature = "tracing", feature = "metrics"),
+            not(target_arch = "wasm32")
+        ))]
+        let res = res.map(|mut res| {
+            #[cfg(feature = "tracing")]
+            res.extensions_mut()
+                .get_or_insert_default::<crate::transport::ResponseGuards>()
+                .push(std::sync::Arc::new(self.span.clone()));
+            res.extensions_mut().insert(self.clone());
+            res
+        });
+
+        res
+    }
+
+    /// The telemetry of a response, if any
+    pub(crate) fn of(res: &reqwest::Response) -> Self {
+        #[cfg(all(
+            any(feature = "tracing", feature = "metrics"),
+            not(target_arch = "wasm32")
+        ))]
+        if let Some(telemetry) = res.extensions().get::<Self>() {
+            return telemetry.clone();
+        }
+        let _ = res;
+
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::none(),
+            #[cfg(feature = "metrics")]
+            labels: None,
+        }
+    }
+
+    /// Records the usage reported in the final response of a generation or a chat
+    pub(crate) fn record_usage(&self, usage: Option<Usage>) {
+        let Some(usage) = usage else {
+            return;
+        };
+
+        #[cfg(feature = "tracing")]
+        self.span
+            .record("gen_ai.usage.input_tokens", usage.prompt_eval_count)
+            .record("gen_ai.usage.output_tokens", usage.eval_count)
+            .record(
+                "ollama.total_duration_ms",
+                usage.total_duration.as_millis() as u64,
+            )
+            .record(
+                "ollama.load_duration_ms",
+                usage.load_duration.as_millis() as u64,
+            );
+
+        #[cfg(feature = "metrics")]
+        if let Some(labels) = &self.labels {
+            let model = labels.model.clone().unwrap_or_default();
+            let stream = labels.streaming.to_string();
+            metrics::counter!(
+                "ollama_tokens_total",
+                "model" => model.clone(),
+                "type" => "input",
+                "stream" => stream.clone()
+            )
+            .increment(usage.prompt_eval_count);
+            metrics::counter!(
+                "ollama_tokens_total",
+                "model" => model,
+                "type" => "output",
+                "stream" => stream
+            )
+            .increment(usage.eval_count);
+        }
+
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = usage;
+    }
+}
+
+/// Counts a call to a tool by the [`crate::coordinator::Coordinator`]
+pub(crate) fn record_tool_call(tool: &str, ok: bool) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        "ollama_tool_calls_total",
+        "tool" => tool.to_string(),
+        "result" => if ok { "ok" } else { "error" }
+    )
+    .increment(1);
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = (tool, ok);
+}
+
+/// Writes the context of a span in the headers of a request, with the global propagator
+#[cfg(feature = "otel")]
+fn inject_context(span: &tracing::Span, headers: &mut reqwest::header::HeaderMap) {
+    use reqwest::header::{HeaderName, HeaderValue};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct HeaderInjector<'a>(&'a mut reqwest::header::HeaderMap);
+
+    impl opentelemetry::propagation::Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    let context = span.context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(headers))
+    });
+}
@@ -0,0 +1,188 @@
+#[cfg(feature = "stream")]
+use std::ops::Deref;
+use std::time::Duration;
+
+use crate::Ollama;
+
+impl Ollama {
+    /// Sets the timeout of the non-streaming requests, from sending the request to reading the whole response.
+    /// `None` (the default) waits indefinitely.
+    ///
+    /// Non-streaming pulls, pushes and model creations can take much longer than a generation,
+    /// use their streaming variants with [`Ollama::set_stream_idle_timeout`] instead.
+    /// Blob uploads are not affected.
+    pub fn set_request_timeout(&mut self, timeout: Option<Duration>) {
+        self.request_timeout = timeout;
+    }
+
+    /// Returns the timeout of the non-streaming requests, if any
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    /// Sets the longest time to wait for the next chunk of a streamed response, after which the stream
+    /// yields [`crate::error::OllamaError::StreamIdleTimeout`] and ends. `None` (the default) waits indefinitely.
+    ///
+    /// Unlike a timeout on the whole request, it detects a wedged generation without interrupting
+    /// a slow one that keeps producing tokens.
+    pub fn set_stream_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.stream_idle_timeout = timeout;
+    }
+
+    /// Returns the idle timeout of the streamed responses, if any
+    pub fn stream_idle_timeout(&self) -> Option<Duration> {
+        self.stream_idle_timeout
+    }
+
+    /// Applies the request timeout, if any, to a non-streaming request
+    pub(crate) fn with_request_timeout(
+        &self,
+        builder: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        match self.request_timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        }
+    }
+}
+
+/// Reads the chunks of a response body, failing once no chunk arrived for `idle_timeout`,
+/// ahead of the consumer if the buffer allows it. The stream ends after the first error.
+#[cfg(feature = "stream")]
+pub(crate) fn response_chunks(
+    #[cfg_attr(target_arch = "wasm32", allow(unused_mut))] mut res: reqwest::Response,
+    idle_timeout: Option<Duration>,
+    buffer: crate::backpressure::StreamBuffer,
+) -> crate::BoxStream<'static, crate::error::Result<impl Deref<Target = [u8]> + Send>> {
+    use async_stream::stream;
+    use tokio_stream::StreamExt;
+
+    use crate::error::OllamaError;
+
+    // The guards of the response, such as the slot of the request in the limiter, are kept until the end of the stream
+    #[cfg(not(target_arch = "wasm32"))]
+    let guards = res
+        .extensions_mut()
+        .remove::<crate::transport::ResponseGuards>();
+    #[cfg(not(target_arch = "wasm32"))]
+    let registration = res
+        .extensions_mut()
+        .remove::<std::sync::Arc<crate::abort::Registration>>();
+    #[cfg(target_arch = "wasm32")]
+    let registration: Option<std::sync::Arc<crate::abort::Registration>> = None;
+    let mut stream = Box::pin(res.bytes_stream());
+
+    let chunks = stream! {
+        #[cfg(not(target_arch = "wasm32"))]
+        let _guards = guards;
+
+        loop {
+            let next = async {
+                match idle_timeout {
+                    Some(timeout) => crate::runtime::timeout(timeout, stream.next()).await,
+                    None => Some(stream.next().await),
+                }
+            };
+            let next = match &registration {
+                Some(registration) => registration.signal.run(next).await,
+                None => Some(next.await),
+            };
+            let next = match next {
+                Some(Some(next)) => next,
+                Some(None) => {
+                    yield Err(OllamaError::StreamIdleTimeout(idle_timeout.unwrap_or_default()));
+                    break;
+                }
+                None => {
+                    yield Err(OllamaError::Aborted);
+                    break;
+                }
+            };
+
+            match next {
+                Some(Ok(chunk)) => yield Ok(chunk),
+                Some(Err(e)) => {
+                    yield Err(OllamaError::ReqwestError(e));
+                    break;
+                }
+                None => break,
+            }
+        }
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if buffer.read_ahead > 0 {
+        return crate::backpressure::read_ahead(chunks, buffer);
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = buffer;
+
+    Box::pin(chunks)
+}
+
+/// Reads the lines of a response body, such as the JSON objects of a stream, see [`response_chunks`].
+/// Yields the complete lines of each chunk without their line break, skipping the empty ones, and the
+/// last line at the end of the body even without a line break. The stream ends with
+/// [`crate::error::OllamaError::StreamLineTooLong`] once a line exceeds `buffer.max_line_length`.
+#[cfg(feature = "stream")]
+pub(crate) fn response_lines(
+    res: reqwest::Response,
+    idle_timeout: Option<Duration>,
+    buffer: crate::backpressure::StreamBuffer,
+) -> crate::BoxStream<'static, crate::error::Result<Vec<Vec<u8>>>> {
+    use async_stream::stream;
+    use tokio_stream::StreamExt;
+
+    use crate::error::OllamaError;
+
+    let mut chunks = response_chunks(res, idle_timeout, buffer);
+    let max_line_length = buffer.max_line_length;
+
+    let lines = stream! {
+        // The bytes are buffered as is, a character may be split between two chunks
+        let mut pending: Vec<u8> = Vec::new();
+
+        while let Some(chunk) = chunks.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+
+            // The bytes buffered before the chunk hold no line break
+            let mut search_from = pending.len();
+            pending.extend_from_slice(&chunk);
+
+            let mut lines = vec![];
+            let mut start = 0;
+            while let Some(pos) = pending[search_from..].iter().position(|b| *b == b'\n') {
+                let end = search_from + pos;
+                let line = pending[start..end].trim_ascii();
+                if !line.is_empty() {
+                    lines.push(line.to_vec());
+                }
+                start = end + 1;
+                search_from = start;
+            }
+            pending.drain(..start);
+
+            if !lines.is_empty() {
+                yield Ok(lines);
+            }
+
+            if pending.len() > max_line_length {
+                yield Err(OllamaError::StreamLineTooLong(max_line_length));
+                return;
+            }
+        }
+
+        let line = pending.trim_ascii();
+        if !line.is_empty() {
+            yield Ok(vec![line.to_vec()]);
+        }
+    };
+
+    Box::pin(lines)
+}
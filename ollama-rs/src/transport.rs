@@ -0,0 +1,357 @@
+use std::{any::Any, future::Future, pin::Pin, sync::Arc};
+
+use crate::{error::OllamaError, interceptor::Exchange, telemetry::RequestTelemetry, Ollama};
+
+/// The kind of operation of an Ollama endpoint, to configure the transport per class of requests,
+/// such as retrying the idempotent ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointClass {
+    /// Reads without side effects: listing the local and running models, showing a model,
+    /// checking a blob, the server version and the registry manifests
+    Metadata,
+    /// Embedding generations, through the native or the OpenAI-compatible endpoint
+    Embeddings,
+    /// Completions and chats, through the native or the OpenAI-compatible endpoint,
+    /// and loading or unloading models
+    Generation,
+    /// Model pulls. A restarted pull resumes the partially downloaded layers.
+    Pull,
+    /// Model creations, copies, deletions, pushes and blob uploads
+    Management,
+}
+
+/// A boxed future of a [`Transport`]. The futures are `Send`, except in browsers where the
+/// responses are JavaScript objects tied to the thread that created them.
+#[cfg(not(target_arch = "wasm32"))]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+#[cfg(target_arch = "wasm32")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// The HTTP layer of a client, sending the requests built by the endpoints, see [`Ollama::with_transport`].
+///
+/// The default transport is the `reqwest` client of [`Ollama`]. Another transport can send the
+/// requests with another HTTP client, such as `hyper`, through a tunnel, or answer them itself
+/// as a test double. The requests convert to and from the `http` types with
+/// `http::Request::try_from` and `reqwest::Response::from`.
+///
+/// The retries, the limiter, the pool of hosts and the interceptors of the client work the same
+/// on top of any transport.
+///
+/// ```
+/// use ollama_rs::{transport::{BoxFuture, Transport}, Ollama};
+///
+/// #[derive(Debug)]
+/// struct Offline;
+///
+/// impl Transport for Offline {
+///     fn send(&self, _request: reqwest::Request) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+///         Box::pin(async { Err(ollama_rs::error::OllamaError::Other("Offline".to_string())) })
+///     }
+/// }
+///
+/// let ollama = Ollama::default().with_transport(Offline);
+/// ```
+pub trait Transport: Send + Sync {
+    /// Sends a request whose response is read whole
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, crate::error::Result<reqwest::Response>>;
+
+    /// Sends a request whose response body is streamed, such as a generation with `stream: true`.
+    /// The body of the response should yield the chunks as they arrive. Defaults to [`Transport::send`].
+    fn send_stream(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, crate::error::Result<reqwest::Response>> {
+        self.send(request)
+    }
+}
+
+impl std::fmt::Debug for dyn Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Transport")
+    }
+}
+
+/// The default transport, which streams every response body
+impl Transport for reqwest::Client {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, crate::error::Result<reqwest::Response>> {
+        Box::pin(async move { Ok(self.execute(request).await?) })
+    }
+}
+
+/// A value kept alive as long as a response, or its stream of chunks, such as the slot of the request in the limiter
+pub(crate) type ResponseGuard = Arc<dyn Any + Send + Sync>;
+
+/// The guards of a response, stored in its extensions
+#[derive(Clone, Default)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub(crate) struct ResponseGuards(Vec<ResponseGuard>);
+
+impl ResponseGuards {
+    #[cfg_attr(
+        not(any(feature = "rate-limit", feature = "tracing")),
+        allow(dead_code)
+    )]
+    pub(crate) fn push(&mut self, guard: ResponseGuard) {
+        self.0.push(guard);
+    }
+}
+
+impl Ollama {
+    /// Sends the requests with another HTTP layer than the `reqwest` client, see [`Transport`].
+    /// The requests are still built with the `reqwest` client, which sets their default headers.
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Returns the transport sending the requests, which is the `reqwest` client unless set with [`Ollama::with_transport`]
+    pub fn transport(&self) -> &dyn Transport {
+        match &self.transport {
+            Some(transport) => transport.as_ref(),
+            None => &self.reqwest_client,
+        }
+    }
+
+    /// Sends a request with the transport settings of its endpoint class
+    pub(crate) async fn send(
+        &self,
+        builder: reqwest::RequestBuilder,
+        class: EndpointClass,
+    ) -> crate::error::Result<reqwest::Response> {
+        self.send_request(builder, class, false).await
+    }
+
+    /// Sends a request whose response body is read as a stream, see [`Transport::send_stream`]
+    #[cfg(feature = "stream")]
+    pub(crate) async fn send_stream(
+        &self,
+        builder: reqwest::RequestBuilder,
+        class: EndpointClass,
+    ) -> crate::error::Result<reqwest::Response> {
+        self.send_request(builder, class, true).await
+    }
+
+    async fn send_request(
+        &self,
+        builder: reqwest::RequestBuilder,
+        class: EndpointClass,
+        streaming: bool,
+    ) -> crate::error::Result<reqwest::Response> {
+        let mut request = builder.build()?;
+        self.apply_user_agent(&mut request);
+        // A compressed stream is decoded by blocks, which holds back the chunks until a block is complete
+        #[cfg(any(feature = "gzip", feature = "zstd"))]
+        if streaming {
+            request
+                .headers_mut()
+                .entry(reqwest::header::ACCEPT_ENCODING)
+                .or_insert(reqwest::header::HeaderValue::from_static("identity"));
+        }
+        let request_id = self.attach_request_id(&mut request);
+        let telemetry =
+            RequestTelemetry::new(&mut request, class, streaming, request_id.as_deref());
+
+        let registration = self.abort_registry.register(request_id.clone());
+
+        let start = crate::Instant::now();
+        let res = registration
+            .signal
+            .run(telemetry.instrument(self.authenticate(
+                request,
+                class,
+                streaming,
+                request_id.as_deref(),
+            )))
+            .await
+            .unwrap_or(Err(OllamaError::Aborted));
+        let res = telemetry.finish(res, start.elapsed());
+
+        // A streamed response stays in flight until the end of its stream
+        #[cfg(not(target_arch = "wasm32"))]
+        let res = match streaming {
+            true => res.map(|mut res| {
+                res.extensions_mut().insert(Arc::new(registration));
+                res
+            }),
+            false => res,
+        };
+
+        // The errors read later from the body of the response carry the ID too
+        #[cfg(not(target_arch = "wasm32"))]
+        let res = match &request_id {
+            Some(request_id) => res.map(|mut res| {
+                res.extensions_mut()
+                    .insert(crate::request_id::RequestId(request_id.clone()));
+                res
+            }),
+            None => res,
+        };
+
+        res.map_err(|e| e.with_request_id(request_id))
+    }
+
+    /// Sends a request authenticated with the provider of the client, if any, sending it again
+    /// with a refreshed token if Ollama rejects the first one
+    async fn authenticate(
+        &self,
+        mut request: reqwest::Request,
+        class: EndpointClass,
+        streaming: bool,
+        request_id: Option<&str>,
+    ) -> crate::error::Result<reqwest::Response> {
+        let Some(provider) = self.auth_provider_for(&request) else {
+            return self.intercept(request, class, streaming, request_id).await;
+        };
+
+        crate::auth::authorize(provider.as_ref(), &mut request).await?;
+        let retry = request.try_clone();
+        let res = self
+            .intercept(request, class, streaming, request_id)
+            .await?;
+
+        match retry {
+            Some(mut retry) if res.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                provider.refresh().await?;
+                crate::auth::authorize(provider.as_ref(), &mut retry).await?;
+                self.intercept(retry, class, streaming, request_id).await
+            }
+            _ => Ok(res),
+        }
+    }
+
+    /// Sends a request through the interceptors
+    async fn intercept(
+        &self,
+        mut request: reqwest::Request,
+        class: EndpointClass,
+        streaming: bool,
+        request_id: Option<&str>,
+    ) -> crate::error::Result<reqwest::Response> {
+        for interceptor in &self.interceptors {
+            if let Some(res) = interceptor.before_request(&mut request, class)? {
+                return Ok(res);
+            }
+        }
+
+        if self.interceptors.is_empty() {
+            return self.dispatch(request, class, streaming).await;
+        }
+
+        let method = request.method().clone();
+        let url = request.url().clone();
+        let start = crate::Instant::now();
+
+        let res = self.dispatch(request, class, streaming).await;
+
+        let exchange = Exchange {
+            class,
+            method: &method,
+            url: &url,
+            request_id,
+            result: res.as_ref().map(|res| res.status()),
+            duration: start.elapsed(),
+        };
+        for interceptor in &self.interceptors {
+            interceptor.after_response(&exchange);
+        }
+
+        res
+    }
+
+    /// Sends a request through the limiter and the pool of hosts, if any
+    async fn dispatch(
+        &self,
+        request: reqwest::Request,
+        class: EndpointClass,
+        streaming: bool,
+    ) -> crate::error::Result<reqwest::Response> {
+        let mut guards = ResponseGuards::default();
+
+        #[cfg(feature = "rate-limit")]
+        if let Some(limiter) = &self.request_limiter {
+            let priority = self
+                .request_priority
+                .unwrap_or_else(|| limiter.priority_of(class));
+            if let Some(slot) = limiter.acquire(priority).await {
+                guards.push(Arc::new(slot));
+            }
+        }
+
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut res = match &self.host_pool {
+            Some(pool) => {
+                let (res, guard) = self
+                    .execute_balanced(pool.clone(), request, class, streaming)
+                    .await?;
+                guards.0.extend(guard);
+                res
+            }
+            None => self.execute(request, class, streaming).await?,
+        };
+
+        // The responses of the browsers have no extensions, so their guards are released with the headers
+        #[cfg(not(target_arch = "wasm32"))]
+        if !guards.0.is_empty() {
+            res.extensions_mut().insert(guards);
+        }
+
+        Ok(res)
+    }
+
+    /// Sends a request to its URL, retrying it with the policy of its endpoint class if any
+    pub(crate) async fn execute(
+        &self,
+        request: reqwest::Request,
+        class: EndpointClass,
+        streaming: bool,
+    ) -> crate::error::Result<reqwest::Response> {
+        #[cfg(feature = "retry")]
+        if let Some(policy) = self.retry_policies.get(&class) {
+            return self
+                .execute_with_retry(request, class, policy, streaming)
+                .await;
+        }
+        #[cfg(not(feature = "retry"))]
+        let _ = class;
+
+        self.transmit(request, streaming).await
+    }
+
+    /// Sends a request once with the transport
+    pub(crate) async fn transmit(
+        &self,
+        request: reqwest::Request,
+        streaming: bool,
+    ) -> crate::error::Result<reqwest::Response> {
+        let transport = self.transport();
+        if streaming {
+            transport.send_stream(request).await
+        } else {
+            transport.send(request).await
+        }
+    }
+}
+
+/// Whether a status is of a busy or restarting server, such that the request may succeed later
+pub(crate) fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 502 | 503 | 504)
+}
+
+/// Whether a request failed to connect. In browsers, `fetch` doesn't tell a refused connection
+/// apart from other network errors, which are all treated as connection failures.
+pub(crate) fn is_connect_error(error: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        error.is_connect()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        error.is_request()
+    }
+}
@@ -0,0 +1,66 @@
+use reqwest::header::{HeaderValue, USER_AGENT};
+
+use crate::{error::OllamaError, Ollama};
+
+/// The `User-Agent` of the clients built by the crate, such as `ollama-rs/0.3.0`
+pub const DEFAULT_USER_AGENT: &str = concat!("ollama-rs/", env!("CARGO_PKG_VERSION"));
+
+impl Ollama {
+    /// Sends every request with this `User-Agent`, to tell the traffic of several services sharing
+    /// one Ollama host apart in the logs of a reverse proxy. `None` (the default) leaves the
+    /// `User-Agent` of the HTTP client, which is [`DEFAULT_USER_AGENT`] unless the client was
+    /// replaced with [`Ollama::with_client`].
+    ///
+    /// ```
+    /// use ollama_rs::Ollama;
+    ///
+    /// let mut ollama = Ollama::default();
+    /// ollama.set_user_agent(Some("indexer/2.1 (team-search)")).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user agent isn't a valid header value, such as one with a line break.
+    pub fn set_user_agent(&mut self, user_agent: Option<&str>) -> crate::error::Result<()> {
+        self.user_agent = user_agent.map(parse_user_agent).transpose()?;
+        Ok(())
+    }
+
+    /// Identifies the application in the `User-Agent` of the requests, after the default of the crate,
+    /// such as `ollama-rs/0.3.0 indexer/2.1`. See [`Ollama::set_user_agent`].
+    pub fn set_app_name(&mut self, name: &str, version: &str) -> crate::error::Result<()> {
+        self.set_user_agent(Some(&app_user_agent(name, version)))
+    }
+
+    /// Returns the `User-Agent` set with [`Ollama::set_user_agent`] or [`Ollama::set_app_name`], if any
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent
+            .as_ref()
+            .and_then(|user_agent| user_agent.to_str().ok())
+    }
+
+    /// Sets the `User-Agent` of a request, if one was set on the client
+    pub(crate) fn apply_user_agent(&self, request: &mut reqwest::Request) {
+        if let Some(user_agent) = &self.user_agent {
+            request.headers_mut().insert(USER_AGENT, user_agent.clone());
+        }
+    }
+}
+
+/// The default user agent followed by the name and version of an application
+pub(crate) fn app_user_agent(name: &str, version: &str) -> String {
+    format!("{DEFAULT_USER_AGENT} {name}/{version}")
+}
+
+pub(crate) fn parse_user_agent(user_agent: &str) -> crate::error::Result<HeaderValue> {
+    HeaderValue::from_str(user_agent)
+        .map_err(|_| OllamaError::Other(format!("Invalid user agent: {user_agent:?}")))
+}
+
+/// A client builder sending [`DEFAULT_USER_AGENT`]. Browsers send their own user agent.
+pub(crate) fn client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+    #[cfg(not(target_arch = "wasm32"))]
+    let builder = builder.user_agent(DEFAULT_USER_AGENT);
+    builder
+}
@@ -0,0 +1,157 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::{error::OllamaError, transport::EndpointClass, Instant, Ollama};
+
+/// How long [`Ollama::require_version`] assumes the version is unknown after failing to fetch it
+const VERSION_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The version of an Ollama server, compared to gate features added in newer versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServerVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl ServerVersion {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Development builds of Ollama report version 0.0.0
+    pub fn is_development(&self) -> bool {
+        *self == Self::new(0, 0, 0)
+    }
+}
+
+/// The version adding thinking support to chat and generation requests
+pub const THINKING_VERSION: ServerVersion = ServerVersion::new(0, 9, 0);
+/// The version replacing Modelfile strings with the structured create request
+pub const STRUCTURED_CREATE_VERSION: ServerVersion = ServerVersion::new(0, 5, 5);
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for ServerVersion {
+    type Err = OllamaError;
+
+    /// Parses versions such as `0.5.7`, `v0.6.0` or `0.6.0-rc1`, ignoring pre-release suffixes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || OllamaError::Other(format!("Invalid Ollama version: {s}"));
+
+        let core = s.trim().trim_start_matches('v');
+        let core = core.split(['-', '+']).next().unwrap_or(core);
+
+        let mut parts = core.split('.').map(|p| p.parse::<u64>());
+        let major = parts.next().ok_or_else(invalid)?.map_err(|_| invalid())?;
+        let minor = parts.next().unwrap_or(Ok(0)).map_err(|_| invalid())?;
+        let patch = parts.next().unwrap_or(Ok(0)).map_err(|_| invalid())?;
+
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+/// The version of the server, shared by a client and its clones
+#[derive(Debug, Default)]
+pub(crate) struct VersionCache {
+    version: OnceLock<ServerVersion>,
+    /// When fetching the version last failed
+    failed_at: Mutex<Option<Instant>>,
+}
+
+impl VersionCache {
+    fn failed_recently(&self) -> bool {
+        let failed_at = self.failed_at.lock().unwrap();
+        failed_at.is_some_and(|t| t.elapsed() < VERSION_RETRY_INTERVAL)
+    }
+
+    fn record_failure(&self) {
+        *self.failed_at.lock().unwrap() = Some(Instant::now());
+    }
+}
+
+impl Ollama {
+    /// Returns the version of the Ollama server, such as `0.5.7`.
+    pub async fn version(&self) -> crate::error::Result<String> {
+        let url = self.endpoint_url("api/version");
+        let builder = self.reqwest_client.get(url);
+        let builder = self.with_request_timeout(builder);
+
+        #[cfg(feature = "headers")]
+        let builder = builder.headers(self.request_headers.clone());
+
+        let res = self.send(builder, EndpointClass::Metadata).await?;
+
+        if !res.status().is_success() {
+            return Err(OllamaError::from_response(res).await);
+        }
+
+        let res = res.bytes().await?;
+        let res = serde_json::from_slice::<VersionResponse>(&res)?;
+
+        Ok(res.version)
+    }
+
+    /// Returns the parsed version of the Ollama server. It is fetched once, then cached by the client and its clones.
+    pub async fn server_version(&self) -> crate::error::Result<ServerVersion> {
+        if let Some(version) = self.server_version.version.get() {
+            return Ok(*version);
+        }
+
+        let version = self.version().await?.parse::<ServerVersion>()?;
+        let _ = self.server_version.version.set(version);
+
+        Ok(version)
+    }
+
+    /// Fails with [`OllamaError::UnsupportedServerVersion`] if the server is older than `required`.
+    ///
+    /// Servers whose version can't be determined, and development builds, are assumed to support everything.
+    /// After a failed fetch, the version is assumed unknown for a while instead of being fetched before every request.
+    pub(crate) async fn require_version(
+        &self,
+        required: ServerVersion,
+        feature: &str,
+    ) -> crate::error::Result<()> {
+        if self.server_version.version.get().is_none() && self.server_version.failed_recently() {
+            return Ok(());
+        }
+
+        match self.server_version().await {
+            Ok(actual) if !actual.is_development() && actual < required => {
+                Err(OllamaError::UnsupportedServerVersion {
+                    feature: feature.to_string(),
+                    required,
+                    actual,
+                })
+            }
+            Ok(_) => Ok(()),
+            Err(e) => {
+                log::debug!(
+                    "Failed to fetch the version of the server, assuming it is unknown: {e}"
+                );
+                self.server_version.record_failure();
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use ollama_rs::{models::blobs::sha256_digest, Ollama};
+
+#[test]
+fn test_sha256_digest() {
+    assert_eq!(
+        sha256_digest(b"hello"),
+        "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+}
+
+#[tokio::test]
+async fn test_create_blob() {
+    let ollama = Ollama::default();
+
+    let bytes = b"ollama-rs blob test".to_vec();
+    let digest = ollama.create_blob(bytes).await.unwrap();
+
+    assert_eq!(digest, sha256_digest(b"ollama-rs blob test"));
+    assert!(ollama.blob_exists(&digest).await.unwrap());
+    assert!(!ollama
+        .blob_exists(&sha256_digest(b"missing blob"))
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_create_blob_from_file() {
+    let ollama = Ollama::default();
+
+    let path = std::env::temp_dir().join("ollama_rs_test_blob.bin");
+    std::fs::write(&path, b"ollama-rs file blob test").unwrap();
+
+    let digest = ollama.create_blob_from_file(&path).await.unwrap();
+
+    assert_eq!(digest, sha256_digest(b"ollama-rs file blob test"));
+    assert!(ollama.blob_exists(&digest).await.unwrap());
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,70 @@
+use ollama_rs::{
+    blocking::Ollama,
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+    },
+};
+
+const PROMPT: &str = "Why is the sky blue?";
+
+#[test]
+fn test_blocking_generation() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .generate(GenerationRequest::new("llama2:latest".to_string(), PROMPT))
+        .unwrap();
+    dbg!(res.response);
+}
+
+#[test]
+fn test_blocking_generation_stream() {
+    let ollama = Ollama::default();
+
+    let stream = ollama
+        .generate_stream(GenerationRequest::new("llama2:latest".to_string(), PROMPT))
+        .unwrap();
+
+    let mut done = false;
+    for chunk in stream {
+        for res in chunk.unwrap() {
+            dbg!(&res.response);
+            done |= res.done;
+        }
+    }
+    assert!(done);
+}
+
+#[test]
+fn test_blocking_chat_stream() {
+    let ollama = Ollama::default();
+
+    let stream = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama2:latest".to_string(),
+            vec![ChatMessage::user(PROMPT.to_string())],
+        ))
+        .unwrap();
+
+    let last = stream.map(Result::unwrap).last().unwrap();
+    assert!(last.done);
+}
+
+#[test]
+fn test_blocking_list_local_models() {
+    let ollama = Ollama::default();
+
+    let models = ollama.list_local_models().unwrap();
+    dbg!(models);
+}
+
+#[test]
+fn test_blocking_from_async() {
+    let mut ollama = ollama_rs::Ollama::default();
+    ollama.set_model_alias("default", "llama2:latest");
+
+    let ollama = Ollama::from_async(ollama).unwrap();
+    let info = ollama.show_model_info("default".to_string()).unwrap();
+    dbg!(info);
+}
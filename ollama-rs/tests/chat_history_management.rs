@@ -1,11 +1,12 @@
 use ollama_rs::{
     generation::chat::{request::ChatMessageRequest, ChatMessage, MessageRole},
+    history::{drop_last_response, ChatHistory, PrefixTracker},
     Ollama,
 };
 
 #[tokio::test]
 async fn test_chat_history_accumulated() {
-    let mut ollama = Ollama::default();
+    let ollama = Ollama::default();
 
     let mut history = vec![];
 
@@ -39,3 +40,71 @@ async fn test_chat_history_accumulated() {
 
     assert_eq!(history.len(), 4)
 }
+
+#[test]
+fn test_history_prefix_digest() {
+    let mut history = vec![
+        ChatMessage::system("You are a helpful assistant.".to_string()),
+        ChatMessage::user("Why is the sky blue?".to_string()),
+    ];
+    let mut tracker = PrefixTracker::new();
+
+    assert!(!tracker.update(&history));
+
+    // Appending keeps the prefix intact
+    history.push(ChatMessage::assistant("Rayleigh scattering.".to_string()));
+    assert!(tracker.update(&history));
+    assert_eq!(
+        history.prefix_digest(2),
+        history[..2].to_vec().prefix_digest(2)
+    );
+
+    // Editing an earlier message invalidates it
+    history[0].content = "You are a pirate.".to_string();
+    assert!(!tracker.update(&history));
+}
+
+#[test]
+fn test_drop_last_response_with_tool_messages() {
+    let mut history = vec![
+        ChatMessage::user("What is 2 + 2?".to_string()),
+        ChatMessage::assistant("4".to_string()),
+        ChatMessage::user("And 3 * 7?".to_string()),
+        ChatMessage::assistant(String::new()),
+        ChatMessage::tool("21".to_string()),
+        ChatMessage::assistant("21".to_string()),
+    ];
+
+    assert!(drop_last_response(&mut history));
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[2].content, "And 3 * 7?");
+
+    let mut empty: Vec<ChatMessage> = vec![];
+    assert!(!drop_last_response(&mut empty));
+}
+
+#[test]
+fn test_chat_history_default_truncate() {
+    use std::borrow::Cow;
+
+    /// A history implementing the required methods only, that can't drop messages
+    #[derive(Default)]
+    struct AppendOnly(Vec<ChatMessage>);
+
+    impl ChatHistory for AppendOnly {
+        fn push(&mut self, message: ChatMessage) {
+            self.0.push(message);
+        }
+
+        fn messages(&self) -> Cow<'_, [ChatMessage]> {
+            Cow::Borrowed(&self.0)
+        }
+    }
+
+    let mut history = AppendOnly::default();
+    history.push(ChatMessage::user("What is 2 + 2?".to_string()));
+    history.push(ChatMessage::assistant("4".to_string()));
+
+    assert!(!drop_last_response(&mut history));
+    assert_eq!(history.messages().len(), 2);
+}
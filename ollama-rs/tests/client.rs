@@ -0,0 +1,1428 @@
+use std::time::Duration;
+
+use ollama_rs::Ollama;
+
+#[tokio::test]
+async fn test_with_client_builder() {
+    let ollama = Ollama::default()
+        .with_client_builder(|builder| {
+            builder
+                .pool_max_idle_per_host(2)
+                .user_agent("ollama-rs-test")
+        })
+        .unwrap();
+
+    let models = ollama.list_local_models().await.unwrap();
+    dbg!(models);
+}
+
+#[test]
+fn test_with_client() {
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(1))
+        .build()
+        .unwrap();
+    let ollama = Ollama::new("http://localhost", 11434).with_client(client);
+
+    assert_eq!(ollama.url_str(), "http://localhost:11434/");
+}
+
+#[tokio::test]
+async fn test_url_path_prefix() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::transport::{BoxFuture, Transport};
+
+    /// Records the URLs of the requests
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Transport for Recorder {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            self.0.lock().unwrap().push(request.url().to_string());
+            let res = http::Response::builder()
+                .status(200)
+                .body(r#"{"version":"0.9.0","models":[]}"#)
+                .unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    // With or without the trailing slash, the endpoints are under the prefix
+    for url in [
+        "https://gateway.example.com/ollama",
+        "https://gateway.example.com/ollama/",
+    ] {
+        let recorder = Recorder::default();
+        let ollama = Ollama::try_new(url)
+            .unwrap()
+            .with_transport(recorder.clone());
+        assert_eq!(ollama.url_str(), "https://gateway.example.com/ollama/");
+
+        ollama.version().await.unwrap();
+        ollama.list_local_models().await.unwrap();
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "https://gateway.example.com/ollama/api/version",
+                "https://gateway.example.com/ollama/api/tags",
+            ]
+        );
+    }
+
+    let ollama = Ollama::new("http://gateway.internal/team/ollama", 8080);
+    assert_eq!(
+        ollama.url_str(),
+        "http://gateway.internal:8080/team/ollama/"
+    );
+}
+
+#[cfg(feature = "headers")]
+#[test]
+fn test_override_headers() {
+    use ollama_rs::headers::{HeaderMap, HeaderValue, AUTHORIZATION};
+
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer global"));
+    headers.insert("X-Tenant", HeaderValue::from_static("default"));
+    let ollama = Ollama::default().with_headers(headers);
+
+    let mut overrides = HeaderMap::new();
+    overrides.insert("X-Tenant", HeaderValue::from_static("acme"));
+    let scoped = ollama.override_headers(overrides);
+
+    assert_eq!(scoped.headers()[AUTHORIZATION], "Bearer global");
+    assert_eq!(scoped.headers()["X-Tenant"], "acme");
+    assert_eq!(ollama.headers()["X-Tenant"], "default");
+}
+
+#[cfg(all(unix, feature = "unix-socket"))]
+#[tokio::test]
+/// This test needs Ollama to listen on /tmp/ollama.sock to work,
+/// for instance with `socat UNIX-LISTEN:/tmp/ollama.sock,fork TCP:127.0.0.1:11434`
+async fn test_unix_socket() {
+    let ollama = Ollama::new_unix_socket("/tmp/ollama.sock").unwrap();
+
+    let models = ollama.list_local_models().await.unwrap();
+    dbg!(models);
+}
+
+#[test]
+fn test_builder_proxy() {
+    let ollama = Ollama::builder()
+        .url("http://ollama.internal:11434")
+        .proxy("http://proxy.internal:3128")
+        .no_proxy("localhost,127.0.0.1")
+        .no_env_proxy()
+        .build()
+        .unwrap();
+
+    assert_eq!(ollama.url_str(), "http://ollama.internal:11434/");
+
+    assert!(Ollama::builder().url("not a url").build().is_err());
+}
+
+#[tokio::test]
+/// This test needs a SOCKS5 proxy at localhost:1080 reaching Ollama, such as `ssh -D 1080 gpu-box`
+async fn test_builder_socks_proxy() {
+    let res = Ollama::builder().proxy("socks5h://localhost:1080").build();
+    if !cfg!(feature = "socks") {
+        assert!(res.is_err());
+        return;
+    }
+
+    let models = res.unwrap().list_local_models().await.unwrap();
+    dbg!(models);
+}
+
+#[tokio::test]
+async fn test_builder_no_env_proxy() {
+    let ollama = Ollama::builder().no_env_proxy().build().unwrap();
+
+    let models = ollama.list_local_models().await.unwrap();
+    dbg!(models);
+}
+
+#[test]
+fn test_builder_tls() {
+    // An invalid certificate bundle is reported when building the client
+    let res = Ollama::builder()
+        .url("https://ollama.internal")
+        .add_root_certificates(
+            b"-----BEGIN CERTIFICATE-----\ninvalid\n-----END CERTIFICATE-----\n".to_vec(),
+        )
+        .build();
+    assert!(res.is_err());
+
+    let builder =
+        Ollama::builder().client_identity(b"certificate".to_vec(), b"secret key".to_vec());
+    assert!(!format!("{builder:?}").contains("secret"));
+}
+
+#[tokio::test]
+/// This test needs a proxy requiring mutual TLS in front of Ollama at https://localhost:8443,
+/// and the certificates in /tmp/mtls
+async fn test_builder_mutual_tls() {
+    let ollama = Ollama::builder()
+        .url("https://localhost:8443")
+        .add_root_certificates(std::fs::read("/tmp/mtls/ca.pem").unwrap())
+        .client_identity(
+            std::fs::read("/tmp/mtls/client.pem").unwrap(),
+            std::fs::read("/tmp/mtls/client.key").unwrap(),
+        )
+        .build()
+        .unwrap();
+
+    let models = ollama.list_local_models().await.unwrap();
+    dbg!(models);
+}
+
+#[cfg(feature = "rustls")]
+#[tokio::test]
+/// This test needs Ollama behind a TLS proxy at https://localhost:8443, with its certificate in /tmp/pinning/server.der
+async fn test_builder_certificate_pinning() {
+    use base64::Engine;
+    use sha2::{Digest, Sha256};
+
+    assert!(Ollama::builder()
+        .pin_certificate_sha256("not a digest")
+        .build()
+        .is_err());
+
+    let certificate = std::fs::read("/tmp/pinning/server.der").unwrap();
+    let pin = base64::engine::general_purpose::STANDARD.encode(Sha256::digest(&certificate));
+
+    let ollama = Ollama::builder()
+        .url("https://localhost:8443")
+        .pin_certificate_sha256(&pin)
+        .build()
+        .unwrap();
+    dbg!(ollama.list_local_models().await.unwrap());
+
+    // Another certificate is rejected
+    let other = base64::engine::general_purpose::STANDARD.encode([0u8; 32]);
+    let ollama = Ollama::builder()
+        .url("https://localhost:8443")
+        .pin_public_key_sha256(&other)
+        .build()
+        .unwrap();
+    assert!(ollama.list_local_models().await.is_err());
+}
+
+#[test]
+fn test_builder_timeouts() {
+    let ollama = Ollama::builder()
+        .connect_timeout(Duration::from_secs(2))
+        .request_timeout(Duration::from_secs(30))
+        .stream_idle_timeout(Duration::from_secs(10))
+        .build()
+        .unwrap();
+
+    assert_eq!(ollama.request_timeout(), Some(Duration::from_secs(30)));
+    assert_eq!(ollama.stream_idle_timeout(), Some(Duration::from_secs(10)));
+}
+
+#[tokio::test]
+async fn test_builder_http_version() {
+    let ollama = Ollama::builder().http1_only().build().unwrap();
+    dbg!(ollama.version().await.unwrap());
+
+    // Needs a proxy serving HTTP/2 over cleartext in front of Ollama at http://localhost:8080
+    let ollama = Ollama::builder()
+        .url("http://localhost:8080")
+        .http2_prior_knowledge()
+        .http2_keep_alive_interval(Duration::from_secs(20))
+        .build()
+        .unwrap();
+    dbg!(ollama.version().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_compression() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::{
+        generation::completion::request::GenerationRequest,
+        transport::{BoxFuture, Transport},
+    };
+    use tokio_stream::StreamExt;
+
+    /// Records the `Accept-Encoding` of the requests
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Option<String>>>>);
+
+    impl Transport for Recorder {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let encoding = request
+                .headers()
+                .get(reqwest::header::ACCEPT_ENCODING)
+                .map(|value| value.to_str().unwrap().to_string());
+            self.0.lock().unwrap().push(encoding);
+
+            let body = "{\"model\":\"llama2\",\"created_at\":\"2024-01-01T00:00:00Z\",\"response\":\"Hello\",\"done\":true}\n";
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let recorder = Recorder::default();
+    let ollama = Ollama::builder()
+        .gzip(true)
+        .build()
+        .unwrap()
+        .with_transport(recorder.clone());
+
+    ollama
+        .generate(GenerationRequest::new("llama2".into(), "Hello"))
+        .await
+        .unwrap();
+    let mut stream = ollama
+        .generate_stream(GenerationRequest::new("llama2".into(), "Hello"))
+        .await
+        .unwrap();
+    while stream.next().await.is_some() {}
+
+    // The `reqwest` client sets the encodings it accepts when it sends the non-streaming requests
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        [None, Some("identity".to_string())]
+    );
+
+    let ollama = Ollama::builder().gzip(false).build().unwrap();
+    dbg!(ollama.list_local_models().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_builder_resolve() {
+    // The name only resolves through the override
+    let ollama = Ollama::builder()
+        .url("http://ollama.test:11434")
+        .resolve("ollama.test", "127.0.0.1:0".parse().unwrap())
+        .no_env_proxy()
+        .build()
+        .unwrap();
+
+    let models = ollama.list_local_models().await.unwrap();
+    dbg!(models);
+}
+
+#[tokio::test]
+async fn test_builder_connection_pool() {
+    let ollama = Ollama::builder()
+        .pool_max_idle_per_host(16)
+        .pool_idle_timeout(Duration::from_secs(600))
+        .tcp_keepalive(Duration::from_secs(30))
+        .tcp_keepalive_interval(Duration::from_secs(10))
+        .tcp_keepalive_retries(3)
+        .http2_window_sizes(4 * 1024 * 1024, 8 * 1024 * 1024)
+        .build()
+        .unwrap();
+
+    let (first, second) = tokio::join!(ollama.list_local_models(), ollama.version());
+    dbg!(first.unwrap(), second.unwrap());
+}
+
+#[tokio::test]
+async fn test_request_timeout() {
+    use ollama_rs::generation::completion::request::GenerationRequest;
+
+    let mut ollama = Ollama::default();
+    ollama.set_request_timeout(Some(Duration::from_millis(1)));
+
+    let res = ollama
+        .generate(GenerationRequest::new(
+            "llama2:latest".to_string(),
+            "Why is the sky blue?",
+        ))
+        .await;
+    assert!(matches!(res, Err(ollama_rs::error::OllamaError::ReqwestError(e)) if e.is_timeout()));
+}
+
+#[tokio::test]
+async fn test_stream_idle_timeout() {
+    use ollama_rs::generation::completion::request::GenerationRequest;
+    use tokio_stream::StreamExt;
+
+    let mut ollama = Ollama::default();
+    ollama.set_stream_idle_timeout(Some(Duration::from_secs(30)));
+
+    let mut stream = ollama
+        .generate_stream(GenerationRequest::new(
+            "llama2:latest".to_string(),
+            "Why is the sky blue?",
+        ))
+        .await
+        .unwrap();
+
+    while let Some(res) = stream.next().await {
+        let responses = res.unwrap();
+        for resp in responses {
+            dbg!(resp.response);
+        }
+    }
+}
+
+#[test]
+fn test_retry_policy_backoff() {
+    use ollama_rs::retry::RetryPolicy;
+
+    let policy = RetryPolicy::new()
+        .initial_backoff(Duration::from_millis(100))
+        .max_backoff(Duration::from_millis(500));
+
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_millis(500));
+    assert_eq!(policy.backoff(100), Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_retry_budget() {
+    use ollama_rs::{
+        retry::{RetryBudget, RetryPolicy},
+        transport::EndpointClass,
+    };
+
+    // Nothing listens on this port, so every attempt fails to connect
+    let mut ollama = Ollama::try_new("http://127.0.0.1:9").unwrap();
+    ollama.set_retry_policy(
+        EndpointClass::Metadata,
+        Some(
+            RetryPolicy::new()
+                .max_retries(5)
+                .initial_backoff(Duration::from_millis(1)),
+        ),
+    );
+    ollama.set_retry_budget(Some(RetryBudget::new(0.0, 2)));
+
+    let res = ollama.list_local_models().await;
+    assert!(res.is_err());
+    assert_eq!(ollama.retry_budget().unwrap().available(), 0);
+}
+
+#[tokio::test]
+async fn test_retry_policy() {
+    use ollama_rs::{retry::RetryPolicy, transport::EndpointClass};
+
+    let mut ollama = Ollama::default();
+    ollama.set_retry_policy(EndpointClass::Metadata, Some(RetryPolicy::default()));
+
+    let models = ollama.list_local_models().await.unwrap();
+    dbg!(models);
+}
+
+#[tokio::test]
+async fn test_request_limiter_rate() {
+    use ollama_rs::limit::RequestLimiter;
+
+    // Nothing listens on this port, so every request fails right away
+    let mut ollama = Ollama::try_new("http://127.0.0.1:9").unwrap();
+    ollama.set_request_limiter(Some(RequestLimiter::new().max_requests_per_second(10.0)));
+
+    let start = std::time::Instant::now();
+    for _ in 0..3 {
+        let _ = ollama.list_local_models().await;
+    }
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn test_request_limiter_in_flight() {
+    use ollama_rs::{generation::completion::request::GenerationRequest, limit::RequestLimiter};
+
+    let mut ollama = Ollama::default();
+    ollama.set_request_limiter(Some(RequestLimiter::new().max_in_flight(1)));
+
+    let stream = ollama
+        .generate_stream(GenerationRequest::new(
+            "llama2:latest".to_string(),
+            "Why is the sky blue?",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(ollama.request_limiter().unwrap().in_flight(), Some(1));
+
+    drop(stream);
+    assert_eq!(ollama.request_limiter().unwrap().in_flight(), Some(0));
+}
+
+#[tokio::test]
+async fn test_request_limiter_priority() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::{
+        limit::{Priority, RequestLimiter},
+        transport::{BoxFuture, EndpointClass, Transport},
+    };
+
+    /// Records the paths of the requests, answering each one once a permit is released
+    #[derive(Clone)]
+    struct Gated {
+        paths: Arc<Mutex<Vec<String>>>,
+        started: Arc<tokio::sync::Notify>,
+        release: Arc<tokio::sync::Semaphore>,
+    }
+
+    impl Transport for Gated {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            self.paths
+                .lock()
+                .unwrap()
+                .push(request.url().path().to_string());
+            self.started.notify_one();
+
+            let res = http::Response::builder()
+                .status(200)
+                .body(r#"{"models":[]}"#)
+                .unwrap();
+            Box::pin(async move {
+                self.release.acquire().await.unwrap().forget();
+                Ok(res.into())
+            })
+        }
+    }
+
+    let gated = Gated {
+        paths: Default::default(),
+        started: Default::default(),
+        release: Arc::new(tokio::sync::Semaphore::new(0)),
+    };
+    let mut ollama = Ollama::default().with_transport(gated.clone());
+    ollama.set_request_limiter(Some(
+        RequestLimiter::new()
+            .max_in_flight(1)
+            .priority(EndpointClass::Metadata, Priority::Interactive),
+    ));
+    let batch = ollama.with_priority(Priority::Background);
+    assert_eq!(batch.request_priority(), Some(Priority::Background));
+    assert_eq!(
+        ollama
+            .request_limiter()
+            .unwrap()
+            .priority_of(EndpointClass::Embeddings),
+        Priority::Normal
+    );
+
+    let queued = |n: usize| {
+        let ollama = ollama.clone();
+        async move {
+            while ollama.request_limiter().unwrap().queued() < n {
+                tokio::task::yield_now().await;
+            }
+        }
+    };
+
+    // The first request holds the only slot until it is released
+    let first = tokio::spawn({
+        let batch = batch.clone();
+        async move { batch.list_local_models().await }
+    });
+    gated.started.notified().await;
+
+    let background = tokio::spawn({
+        let batch = batch.clone();
+        async move { batch.list_local_models().await }
+    });
+    queued(1).await;
+
+    let interactive = tokio::spawn({
+        let ollama = ollama.clone();
+        async move { ollama.list_running_models().await }
+    });
+    queued(2).await;
+
+    gated.release.add_permits(3);
+    first.await.unwrap().unwrap();
+    background.await.unwrap().unwrap();
+    interactive.await.unwrap().unwrap();
+
+    // The interactive request waited less than the background one queued before it
+    assert_eq!(
+        *gated.paths.lock().unwrap(),
+        ["/api/tags", "/api/ps", "/api/tags"]
+    );
+    assert_eq!(ollama.request_limiter().unwrap().in_flight(), Some(0));
+}
+
+#[test]
+fn test_host_pool() {
+    use ollama_rs::balance::{BalanceStrategy, HostPool};
+
+    assert!(HostPool::new(Vec::<&str>::new(), BalanceStrategy::RoundRobin).is_err());
+    assert!(HostPool::new(["not a url"], BalanceStrategy::RoundRobin).is_err());
+
+    let ollama = Ollama::new_with_hosts(
+        ["http://10.0.0.1:11434", "http://10.0.0.2:11434"],
+        BalanceStrategy::LeastInFlight,
+    )
+    .unwrap();
+    assert_eq!(ollama.url_str(), "http://10.0.0.1:11434/");
+
+    let pool = ollama.host_pool().unwrap();
+    assert_eq!(pool.hosts().len(), 2);
+    assert_eq!(pool.healthy_hosts().len(), 2);
+    assert_eq!(pool.in_flight(pool.hosts()[0]), Some(0));
+}
+
+#[tokio::test]
+/// This test needs Ollama at the default URL, the other host is unreachable
+async fn test_host_pool_failover() {
+    use ollama_rs::balance::{BalanceStrategy, HostPool};
+
+    let mut ollama = Ollama::try_new("http://127.0.0.1:9").unwrap();
+    ollama.set_host_pool(Some(
+        HostPool::new(
+            ["http://127.0.0.1:9", "http://127.0.0.1:11434"],
+            BalanceStrategy::RoundRobin,
+        )
+        .unwrap()
+        .max_failures(1),
+    ));
+
+    for _ in 0..2 {
+        ollama.list_local_models().await.unwrap();
+    }
+
+    let healthy = ollama.host_pool().unwrap().healthy_hosts();
+    assert_eq!(healthy.len(), 1);
+    assert_eq!(healthy[0].as_str(), "http://127.0.0.1:11434/");
+}
+
+#[tokio::test]
+async fn test_interceptor_short_circuit() {
+    use ollama_rs::{error::OllamaError, interceptor::Interceptor, transport::EndpointClass};
+
+    struct Unavailable;
+
+    impl Interceptor for Unavailable {
+        fn before_request(
+            &self,
+            _request: &mut reqwest::Request,
+            class: EndpointClass,
+        ) -> ollama_rs::error::Result<Option<reqwest::Response>> {
+            if class != EndpointClass::Metadata {
+                return Err(OllamaError::Other("injected failure".to_string()));
+            }
+            let res = http::Response::builder()
+                .status(503)
+                .body("server overloaded")
+                .unwrap();
+            Ok(Some(res.into()))
+        }
+    }
+
+    // Nothing is sent, so no server is needed
+    let mut ollama = Ollama::try_new("http://127.0.0.1:9").unwrap();
+    ollama.add_interceptor(Unavailable);
+
+    let res = ollama.list_local_models().await;
+    assert!(matches!(
+        res,
+        Err(OllamaError::ServerUnavailable { status: 503, message }) if message == "server overloaded"
+    ));
+
+    let res = ollama.delete_model("llama2".into()).await;
+    assert!(matches!(res, Err(OllamaError::Other(text)) if text == "injected failure"));
+}
+
+#[tokio::test]
+async fn test_interceptor_observe() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::interceptor::{Exchange, Interceptor};
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Interceptor for Recorder {
+        fn before_request(
+            &self,
+            request: &mut reqwest::Request,
+            _class: ollama_rs::transport::EndpointClass,
+        ) -> ollama_rs::error::Result<Option<reqwest::Response>> {
+            request
+                .headers_mut()
+                .insert("x-request-source", "ollama-rs-test".parse().unwrap());
+            Ok(None)
+        }
+
+        fn after_response(&self, exchange: &Exchange<'_>) {
+            self.0.lock().unwrap().push(format!(
+                "{} {} {:?}",
+                exchange.method, exchange.url, exchange.result
+            ));
+        }
+    }
+
+    let recorder = Recorder::default();
+    let mut ollama = Ollama::default();
+    ollama.add_interceptor(recorder.clone());
+
+    ollama.list_local_models().await.unwrap();
+
+    let exchanges = recorder.0.lock().unwrap();
+    dbg!(&exchanges);
+    assert_eq!(exchanges.len(), 1);
+    assert!(exchanges[0].starts_with("GET http://127.0.0.1:11434/api/tags"));
+}
+
+#[tokio::test]
+async fn test_transport() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::{
+        generation::completion::request::GenerationRequest,
+        transport::{BoxFuture, Transport},
+    };
+    use tokio_stream::StreamExt;
+
+    /// Answers the requests itself, recording how they were sent
+    #[derive(Clone, Default)]
+    struct Canned(Arc<Mutex<Vec<String>>>);
+
+    impl Canned {
+        fn respond(
+            &self,
+            request: reqwest::Request,
+            method: &str,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let path = request.url().path().to_string();
+            self.0.lock().unwrap().push(format!("{method} {path}"));
+
+            let body = match path.as_str() {
+                "/api/version" => r#"{"version":"0.9.0"}"#,
+                _ => "{\"model\":\"llama2\",\"created_at\":\"2024-01-01T00:00:00Z\",\"response\":\"Hello\",\"done\":true}\n",
+            };
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    impl Transport for Canned {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            self.respond(request, "send")
+        }
+
+        fn send_stream(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            self.respond(request, "send_stream")
+        }
+    }
+
+    // Nothing is sent, so no server is needed
+    let transport = Canned::default();
+    let ollama = Ollama::try_new("http://127.0.0.1:9")
+        .unwrap()
+        .with_transport(transport.clone());
+
+    assert_eq!(ollama.version().await.unwrap(), "0.9.0");
+
+    let mut stream = ollama
+        .generate_stream(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+    let mut response = String::new();
+    while let Some(chunk) = stream.next().await {
+        for res in chunk.unwrap() {
+            response += &res.response;
+        }
+    }
+    assert_eq!(response, "Hello");
+
+    let requests = transport.0.lock().unwrap();
+    dbg!(&requests);
+    assert_eq!(
+        *requests,
+        ["send /api/version", "send_stream /api/generate"]
+    );
+}
+
+#[tokio::test]
+async fn test_request_id() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::interceptor::{Exchange, Interceptor};
+    use reqwest::header::{HeaderMap, HeaderName};
+
+    /// The paths of the requests and their IDs
+    type Exchanges = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+    #[derive(Clone, Default)]
+    struct Recorder(Exchanges);
+
+    impl Interceptor for Recorder {
+        fn after_response(&self, exchange: &Exchange<'_>) {
+            self.0.lock().unwrap().push((
+                exchange.url.path().to_string(),
+                exchange.request_id.map(str::to_string),
+            ));
+        }
+    }
+
+    // Nothing listens on the port, so the requests fail to connect
+    let recorder = Recorder::default();
+    let mut ollama = Ollama::try_new("http://127.0.0.1:9").unwrap();
+    ollama.set_request_id_header(Some(HeaderName::from_static("x-request-id")));
+    ollama.add_interceptor(recorder.clone());
+
+    let first = ollama.list_local_models().await.unwrap_err();
+    let second = ollama.version().await.unwrap_err();
+    dbg!(&first, &second);
+
+    let first_id = first.request_id().unwrap();
+    assert_eq!(first_id.len(), 32);
+    assert_ne!(Some(first_id), second.request_id());
+    assert!(first.to_string().contains(first_id));
+
+    // An ID set by the caller is propagated
+    let mut headers = HeaderMap::new();
+    headers.insert("x-request-id", "incoming-request".parse().unwrap());
+    let error = ollama
+        .override_headers(headers)
+        .list_local_models()
+        .await
+        .unwrap_err();
+    assert_eq!(error.request_id(), Some("incoming-request"));
+
+    {
+        let exchanges = recorder.0.lock().unwrap();
+        assert_eq!(
+            exchanges[0],
+            ("/api/tags".to_string(), Some(first_id.to_string()))
+        );
+        assert_eq!(exchanges[2].1.as_deref(), Some("incoming-request"));
+    }
+
+    // The errors read from the body of a response carry the ID too
+    let mut ollama = Ollama::builder()
+        .stub(ollama_rs::stub::StubTransport::new().respond(
+            "api/show",
+            ollama_rs::stub::StubResponse::error(404, "model \"llama2\" not found"),
+        ))
+        .build()
+        .unwrap();
+    ollama.set_request_id_header(Some(HeaderName::from_static("x-request-id")));
+    let error = ollama
+        .show_model_info("llama2".to_string())
+        .await
+        .unwrap_err();
+    assert_eq!(error.request_id().map(str::len), Some(32));
+    assert!(matches!(
+        error.inner(),
+        ollama_rs::error::OllamaError::ModelNotFound(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_user_agent() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::transport::{BoxFuture, Transport};
+
+    /// Records the `User-Agent` of the requests
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Option<String>>>>);
+
+    impl Transport for Recorder {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let user_agent = request
+                .headers()
+                .get(reqwest::header::USER_AGENT)
+                .map(|value| value.to_str().unwrap().to_string());
+            self.0.lock().unwrap().push(user_agent);
+
+            let res = http::Response::builder()
+                .status(200)
+                .body(r#"{"version":"0.9.0"}"#)
+                .unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let recorder = Recorder::default();
+    let mut ollama = Ollama::default().with_transport(recorder.clone());
+    assert_eq!(ollama.user_agent(), None);
+    ollama.version().await.unwrap();
+
+    ollama.set_app_name("indexer", "2.1").unwrap();
+    assert_eq!(
+        ollama.user_agent(),
+        Some(format!("{} indexer/2.1", ollama_rs::DEFAULT_USER_AGENT).as_str())
+    );
+    ollama.version().await.unwrap();
+
+    ollama.set_user_agent(Some("custom/1.0")).unwrap();
+    ollama.version().await.unwrap();
+
+    assert!(ollama.set_user_agent(Some("bad\nagent")).is_err());
+    assert!(Ollama::builder().user_agent("bad\nagent").build().is_err());
+
+    let user_agents = recorder.0.lock().unwrap();
+    // The default user agent is added by the `reqwest` client when it sends the request
+    assert_eq!(user_agents[0], None);
+    assert_eq!(
+        user_agents[1].as_deref(),
+        Some(format!("{} indexer/2.1", ollama_rs::DEFAULT_USER_AGENT).as_str())
+    );
+    assert_eq!(user_agents[2].as_deref(), Some("custom/1.0"));
+}
+
+#[tokio::test]
+async fn test_auth_provider() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    use ollama_rs::{
+        auth::{AccessToken, BasicAuth, BearerToken, RefreshingToken},
+        transport::{BoxFuture, Transport},
+    };
+
+    /// Accepts the second token only, recording the `Authorization` header of the requests
+    #[derive(Clone, Default)]
+    struct Gateway(Arc<Mutex<Vec<Option<String>>>>);
+
+    impl Transport for Gateway {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let authorization = request
+                .headers()
+                .get(reqwest::header::AUTHORIZATION)
+                .map(|value| value.to_str().unwrap().to_string());
+            let res = match authorization.as_deref() {
+                Some("Bearer token-2") => http::Response::builder()
+                    .status(200)
+                    .body(r#"{"version":"0.9.0"}"#),
+                _ => http::Response::builder()
+                    .status(401)
+                    .body(r#"{"error":"unauthorized"}"#),
+            };
+            self.0.lock().unwrap().push(authorization);
+            let res = res.unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let provider = RefreshingToken::new({
+        let fetches = fetches.clone();
+        move || {
+            let count = fetches.fetch_add(1, Ordering::SeqCst) + 1;
+            Box::pin(async move {
+                Ok(AccessToken {
+                    token: format!("token-{count}"),
+                    expires_in: Some(Duration::from_secs(3600)),
+                })
+            })
+        }
+    });
+
+    let gateway = Gateway::default();
+    let ollama = Ollama::default()
+        .with_transport(gateway.clone())
+        .with_auth_provider(provider);
+
+    // The first token is rejected, the request is sent again with a new one which is then kept
+    ollama.version().await.unwrap();
+    ollama.version().await.unwrap();
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        *gateway.0.lock().unwrap(),
+        [
+            Some("Bearer token-1".to_string()),
+            Some("Bearer token-2".to_string()),
+            Some("Bearer token-2".to_string()),
+        ]
+    );
+
+    let basic = BasicAuth::new("user", "password").unwrap();
+    assert!(!format!("{basic:?}").contains("password"));
+    assert!(BearerToken::new("bad\ntoken").is_err());
+}
+
+#[tokio::test]
+async fn test_stream_buffer() {
+    use ollama_rs::{
+        backpressure::{OverflowPolicy, StreamBuffer},
+        error::OllamaError,
+        generation::completion::request::GenerationRequest,
+        transport::{BoxFuture, Transport},
+    };
+    use tokio_stream::StreamExt;
+
+    /// Streams ten generation chunks
+    struct Canned;
+
+    impl Transport for Canned {
+        fn send(
+            &self,
+            _request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let chunks = (0..10).map(|i| {
+                Ok::<_, std::io::Error>(format!(
+                    "{{\"model\":\"llama2\",\"created_at\":\"2024-01-01T00:00:00Z\",\"response\":\"{i}\",\"done\":{}}}\n",
+                    i == 9
+                ))
+            });
+            let body = reqwest::Body::wrap_stream(tokio_stream::iter(chunks));
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    for (policy, expected_chunks) in [(OverflowPolicy::Block, 10), (OverflowPolicy::Error, 2)] {
+        let mut ollama = Ollama::default().with_transport(Canned);
+        ollama.set_stream_buffer(StreamBuffer::new().read_ahead(2).overflow(policy));
+
+        let mut stream = ollama
+            .generate_stream(GenerationRequest::new("llama2".into(), "Count"))
+            .await
+            .unwrap();
+
+        // A slow consumer, letting the buffer fill up
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let mut chunks = 0;
+        let mut error = None;
+        while let Some(res) = stream.next().await {
+            match res {
+                Ok(res) => chunks += res.len(),
+                Err(e) => error = Some(e),
+            }
+        }
+
+        dbg!(policy, chunks, &error);
+        assert_eq!(chunks, expected_chunks);
+        assert_eq!(
+            matches!(error, Some(OllamaError::StreamBufferFull(2))),
+            policy == OverflowPolicy::Error
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_stream_read_error() {
+    use ollama_rs::{
+        error::OllamaError,
+        generation::completion::request::GenerationRequest,
+        transport::{BoxFuture, Transport},
+    };
+    use tokio_stream::StreamExt;
+
+    /// Streams a chunk, then fails to read the rest of the body
+    struct Broken;
+
+    impl Transport for Broken {
+        fn send(
+            &self,
+            _request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let chunks = [
+                Ok(br#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","response":"Hi","done":false}
+"#
+                .to_vec()),
+                Err(std::io::Error::other("connection reset")),
+            ];
+            let body = reqwest::Body::wrap_stream(tokio_stream::iter(chunks));
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let ollama = Ollama::default().with_transport(Broken);
+    let res: Vec<_> = ollama
+        .generate_stream(GenerationRequest::new("llama2".into(), "Hi"))
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(res.len(), 2);
+    assert!(res[0].is_ok());
+    assert!(matches!(&res[1], Err(OllamaError::ReqwestError(e)) if e.is_body() || e.is_decode()));
+}
+
+#[tokio::test]
+async fn test_stream_lines() {
+    use ollama_rs::{
+        backpressure::StreamBuffer,
+        error::OllamaError,
+        generation::{
+            chat::{request::ChatMessageRequest, ChatMessage},
+            completion::request::GenerationRequest,
+        },
+        openai::chat::{ChatCompletionMessage, ChatCompletionRequest},
+        transport::{BoxFuture, Transport},
+    };
+    use tokio_stream::StreamExt;
+
+    /// Streams `body` in chunks of 7 bytes, splitting its lines
+    struct Chunked(&'static str);
+
+    impl Transport for Chunked {
+        fn send(
+            &self,
+            _request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let chunks: Vec<_> = self
+                .0
+                .as_bytes()
+                .chunks(7)
+                .map(|chunk| Ok::<_, std::io::Error>(chunk.to_vec()))
+                .collect();
+            let body = reqwest::Body::wrap_stream(tokio_stream::iter(chunks));
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    // The lines split between chunks are read whole
+    let ollama = Ollama::default().with_transport(Chunked(concat!(
+        r#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","response":"Hello","done":false}"#,
+        "\n",
+        r#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","response":" world","done":true}"#,
+        "\n",
+    )));
+    let mut stream = ollama
+        .generate_stream(GenerationRequest::new("llama2".into(), "Hi"))
+        .await
+        .unwrap();
+    let mut response = String::new();
+    while let Some(res) = stream.next().await {
+        for res in res.unwrap() {
+            response += &res.response;
+        }
+    }
+    assert_eq!(response, "Hello world");
+
+    // A line longer than the limit ends each stream with an error
+    let mut ollama = Ollama::default().with_transport(Chunked(
+        r#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","response":"Hello","done":true}"#,
+    ));
+    ollama.set_stream_buffer(StreamBuffer::new().max_line_length(32));
+
+    let stream = ollama
+        .generate_stream(GenerationRequest::new("llama2".into(), "Hi"))
+        .await
+        .unwrap();
+    let last = stream.collect::<Vec<_>>().await.pop().unwrap();
+    assert!(matches!(last, Err(OllamaError::StreamLineTooLong(32))));
+
+    let stream = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama2".into(),
+            vec![ChatMessage::user("Hi".into())],
+        ))
+        .await
+        .unwrap();
+    let last = stream.collect::<Vec<_>>().await.pop().unwrap();
+    assert!(matches!(last, Err(OllamaError::StreamLineTooLong(32))));
+
+    let stream = ollama
+        .openai_chat_completion_stream(ChatCompletionRequest::new(
+            "llama2".into(),
+            vec![ChatCompletionMessage::user("Hi".into())],
+        ))
+        .await
+        .unwrap();
+    let last = stream.collect::<Vec<_>>().await.pop().unwrap();
+    assert!(matches!(last, Err(OllamaError::StreamLineTooLong(32))));
+}
+
+#[tokio::test]
+async fn test_abort() {
+    use ollama_rs::{
+        error::OllamaError,
+        transport::{BoxFuture, Transport},
+    };
+    use reqwest::header::HeaderName;
+
+    /// Never answers
+    struct Hanging;
+
+    impl Transport for Hanging {
+        fn send(
+            &self,
+            _request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    let mut ollama = Ollama::default().with_transport(Hanging);
+    ollama.set_request_id_header(Some(HeaderName::from_static("x-request-id")));
+
+    let first = tokio::spawn({
+        let ollama = ollama.clone();
+        async move { ollama.list_local_models().await }
+    });
+    let second = tokio::spawn({
+        let ollama = ollama.clone();
+        async move { ollama.version().await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let in_flight = ollama.in_flight_requests();
+    assert_eq!(in_flight.len(), 2);
+
+    // Abort one request by its ID, then the other on shutdown
+    let id = in_flight[0].clone().unwrap();
+    assert!(ollama.abort(&id));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(ollama.in_flight_requests().len(), 1);
+    assert_eq!(ollama.abort_all(), 1);
+
+    for res in [
+        first.await.unwrap().map(|_| ()),
+        second.await.unwrap().map(|_| ()),
+    ] {
+        let error = res.unwrap_err();
+        dbg!(&error);
+        assert!(matches!(
+            error,
+            OllamaError::WithRequestId { source, .. } if matches!(*source, OllamaError::Aborted)
+        ));
+    }
+    assert!(ollama.in_flight_requests().is_empty());
+    assert!(!ollama.abort(&id));
+}
+
+#[tokio::test]
+async fn test_tracing_spans() {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use ollama_rs::{
+        generation::completion::request::GenerationRequest,
+        transport::{BoxFuture, Transport},
+    };
+    use tracing_subscriber::fmt::format::FmtSpan;
+
+    struct Canned;
+
+    impl Transport for Canned {
+        fn send(
+            &self,
+            _request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let body = r#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","response":"Hello","done":true,"total_duration":5000000,"load_duration":1000000,"prompt_eval_count":7,"prompt_eval_duration":1000000,"eval_count":3,"eval_duration":2000000}"#;
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let output = Output::default();
+    let writer = output.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    // Nothing is sent, so no server is needed
+    let ollama = Ollama::try_new("http://127.0.0.1:9")
+        .unwrap()
+        .with_transport(Canned);
+    ollama
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    dbg!(&output);
+    assert!(output.contains("ollama.request"));
+    assert!(output.contains("ollama.endpoint=\"/api/generate\""));
+    assert!(output.contains("gen_ai.request.model=\"llama2\""));
+    assert!(output.contains("gen_ai.usage.input_tokens=7"));
+    assert!(output.contains("gen_ai.usage.output_tokens=3"));
+}
+
+#[tokio::test]
+async fn test_metrics() {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use ollama_rs::{
+        generation::completion::request::GenerationRequest,
+        transport::{BoxFuture, Transport},
+    };
+
+    struct Canned;
+
+    impl Transport for Canned {
+        fn send(
+            &self,
+            _request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let body = r#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","response":"Hello","done":true,"total_duration":5000000,"load_duration":1000000,"prompt_eval_count":7,"prompt_eval_duration":1000000,"eval_count":3,"eval_duration":2000000}"#;
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    // Nothing is sent, so no server is needed
+    let ollama = Ollama::try_new("http://127.0.0.1:9")
+        .unwrap()
+        .with_transport(Canned);
+    ollama
+        .generate(GenerationRequest::new("llama2".to_string(), "Hi"))
+        .await
+        .unwrap();
+
+    let metrics = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let key = key.key();
+            let labels = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect::<Vec<_>>()
+                .join(",");
+            (format!("{}{{{labels}}}", key.name()), value)
+        })
+        .collect::<Vec<_>>();
+    dbg!(&metrics);
+
+    let counter = |name: &str| {
+        metrics.iter().find_map(|(key, value)| match value {
+            DebugValue::Counter(count) if key == name => Some(*count),
+            _ => None,
+        })
+    };
+    assert_eq!(
+        counter("ollama_requests_total{endpoint=/api/generate,status=200}"),
+        Some(1)
+    );
+    assert_eq!(
+        counter("ollama_tokens_total{model=llama2,type=input,stream=false}"),
+        Some(7)
+    );
+    assert_eq!(
+        counter("ollama_tokens_total{model=llama2,type=output,stream=false}"),
+        Some(3)
+    );
+    assert!(metrics
+        .iter()
+        .any(|(key, _)| key.starts_with("ollama_request_duration_seconds")));
+}
+
+#[tokio::test]
+async fn test_with_defaults() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::{
+        defaults::DefaultParams,
+        generation::{completion::request::GenerationRequest, parameters::KeepAlive},
+        models::ModelOptions,
+        transport::{BoxFuture, Transport},
+    };
+
+    /// Records the bodies of the requests
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<serde_json::Value>>>);
+
+    impl Transport for Recorder {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+            self.0
+                .lock()
+                .unwrap()
+                .push(serde_json::from_slice(body).unwrap());
+
+            let res = http::Response::builder()
+                .status(200)
+                .body(r#"{"model":"llama3.2:3b","created_at":"2024-01-01T00:00:00Z","response":"Hi","done":true}"#)
+                .unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let recorder = Recorder::default();
+    let ollama = Ollama::default().with_transport(recorder.clone());
+    let summarizer = ollama.with_defaults(
+        DefaultParams::new()
+            .model("llama3.2:3b")
+            .options(ModelOptions::default().temperature(0.2))
+            .keep_alive(KeepAlive::Indefinitely),
+    );
+    assert!(ollama.defaults().is_none());
+    assert_eq!(
+        summarizer.defaults().unwrap().default_model(),
+        Some("llama3.2:3b")
+    );
+
+    summarizer
+        .generate(GenerationRequest::new(String::new(), "Hello"))
+        .await
+        .unwrap();
+    summarizer
+        .generate(
+            GenerationRequest::new("llama3.1:8b".into(), "Hello")
+                .options(ModelOptions::default().temperature(0.9)),
+        )
+        .await
+        .unwrap();
+    ollama
+        .generate(GenerationRequest::new("llama3.1:8b".into(), "Hello"))
+        .await
+        .unwrap();
+
+    let bodies = recorder.0.lock().unwrap();
+    assert_eq!(bodies[0]["model"], "llama3.2:3b");
+    assert_eq!(bodies[0]["options"]["temperature"], 0.2);
+    assert_eq!(bodies[0]["keep_alive"], -1);
+
+    // The parameters of a request take precedence over the defaults
+    assert_eq!(bodies[1]["model"], "llama3.1:8b");
+    assert_eq!(bodies[1]["options"]["temperature"], 0.9);
+    assert_eq!(bodies[1]["keep_alive"], -1);
+
+    // The defaults apply only to the clone
+    assert!(bodies[2].get("options").is_none());
+    assert!(bodies[2].get("keep_alive").is_none());
+}
@@ -0,0 +1,22 @@
+use ollama_rs::{generation::chat::ChatMessage, Ollama};
+
+#[tokio::test]
+async fn test_generate_title() {
+    let ollama = Ollama::default();
+
+    let history = vec![
+        ChatMessage::user("Why is the sky blue?".to_string()),
+        ChatMessage::assistant(
+            "The sky appears blue because of a phenomenon called Rayleigh scattering.".to_string(),
+        ),
+    ];
+
+    let title = ollama
+        .generate_title(&history, "llama3.2:latest".to_string())
+        .await
+        .unwrap();
+    dbg!(&title);
+
+    assert!(!title.is_empty());
+    assert!(!title.contains('\n'));
+}
@@ -8,3 +8,33 @@ async fn test_copy_model() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn test_copy_model_safe() {
+    use ollama_rs::error::OllamaError;
+
+    let ollama = ollama_rs::Ollama::default();
+
+    assert!(ollama.model_exists("llama2").await.unwrap());
+    assert!(!ollama
+        .model_exists("missing-model-ollama-rs")
+        .await
+        .unwrap());
+
+    let _ = ollama.delete_model("llama2_copy".into()).await;
+    ollama
+        .copy_model_safe("llama2:latest".into(), "llama2_copy".into(), false)
+        .await
+        .unwrap();
+
+    let res = ollama
+        .copy_model_safe("llama2:latest".into(), "llama2_copy".into(), false)
+        .await;
+    assert!(matches!(res, Err(OllamaError::ModelAlreadyExists(_))));
+
+    ollama
+        .copy_model_safe("llama2:latest".into(), "llama2_copy".into(), true)
+        .await
+        .unwrap();
+    ollama.delete_model("llama2_copy".into()).await.unwrap();
+}
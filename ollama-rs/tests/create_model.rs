@@ -1,4 +1,11 @@
-use ollama_rs::{models::create::CreateModelRequest, Ollama};
+use ollama_rs::{
+    generation::chat::MessageRole,
+    models::{
+        create::{CreateModelRequest, QuantizationType},
+        modelfile_builder::ModelfileBuilder,
+    },
+    Ollama,
+};
 use tokio_stream::StreamExt;
 
 #[tokio::test]
@@ -45,3 +52,186 @@ async fn test_create_model() {
 
     assert!(res.message.eq("success"));
 }
+
+#[tokio::test]
+async fn test_create_quantized_model_stream() {
+    let ollama = Ollama::default();
+
+    let request = CreateModelRequest::new("testmodel-q4".into())
+        .from_model("llama2:latest".into())
+        .quantize(QuantizationType::Q4KM);
+
+    let mut res = ollama.create_model_stream(request).await.unwrap();
+
+    let mut done = false;
+    while let Some(res) = res.next().await {
+        let res = res.unwrap();
+        dbg!(&res.message, res.progress());
+        done = res.is_success();
+    }
+
+    assert!(done);
+}
+
+#[tokio::test]
+/// This test needs a GGUF file at /tmp/model.gguf to work
+async fn test_create_model_from_file() {
+    let ollama = Ollama::default();
+
+    let request =
+        CreateModelRequest::new("testmodel-gguf".into()).system("You're a chat bot.".into());
+
+    let mut res = ollama
+        .create_model_from_file(request, "/tmp/model.gguf")
+        .await
+        .unwrap();
+
+    let mut done = false;
+    while let Some(res) = res.next().await {
+        let res = res.unwrap();
+        dbg!(&res.message);
+        done = res.is_success();
+    }
+
+    assert!(done);
+}
+
+#[tokio::test]
+async fn test_create_model_from_directory() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::transport::{BoxFuture, Transport};
+
+    /// Has every blob, and records the body of the create request
+    #[derive(Clone, Default)]
+    struct Server(Arc<Mutex<Option<serde_json::Value>>>);
+
+    impl Transport for Server {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let body = match request.url().path() {
+                "/api/version" => r#"{"version":"0.9.0"}"#.to_string(),
+                "/api/create" => {
+                    let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+                    *self.0.lock().unwrap() = Some(serde_json::from_slice(body).unwrap());
+                    "{\"status\":\"success\"}\n".to_string()
+                }
+                _ => String::new(),
+            };
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("ollama-rs-create-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("adapter")).unwrap();
+    for file in [
+        "model.safetensors",
+        "config.json",
+        ".hidden.safetensors",
+        "README.md",
+        "adapter/adapter_model.safetensors",
+    ] {
+        std::fs::write(dir.join(file), file).unwrap();
+    }
+    std::fs::write(dir.join("Modelfile"), "FROM llama3.2\nADAPTER ./adapter\n").unwrap();
+
+    let server = Server::default();
+    let ollama = Ollama::default().with_transport(server.clone());
+    let mut res = ollama
+        .create_model_from_file(CreateModelRequest::new("testmodel".into()), &dir)
+        .await
+        .unwrap();
+    while res.next().await.is_some() {}
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let request = server.0.lock().unwrap().take().unwrap();
+    let names = |key: &str| {
+        let mut names: Vec<_> = request[key].as_object().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names("files"), ["config.json", "model.safetensors"]);
+    assert_eq!(names("adapters"), ["adapter_model.safetensors"]);
+}
+
+#[test]
+fn test_create_model_request_serialization() {
+    let request = CreateModelRequest::new("testmodel".into())
+        .file("model.gguf".into(), "sha256:abc".into())
+        .quantize(QuantizationType::Q80);
+
+    let json = serde_json::to_value(&request).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "model": "testmodel",
+            "files": { "model.gguf": "sha256:abc" },
+            "stream": false,
+            "quantize": "q8_0",
+        })
+    );
+}
+
+#[test]
+fn test_modelfile_builder() {
+    let modelfile = ModelfileBuilder::new("llama3.2")
+        .template("{{ .Prompt }}")
+        .parameter("num_ctx", 4096)
+        .parameter("stop", "<|eot_id|>")
+        .parameter("stop", "<|end|>")
+        .license("MIT")
+        .message(MessageRole::User, "Is Toronto in Canada?")
+        .message(MessageRole::Assistant, "Yes");
+
+    assert_eq!(
+        modelfile.render().unwrap(),
+        r#"FROM llama3.2
+TEMPLATE """{{ .Prompt }}"""
+PARAMETER num_ctx 4096
+PARAMETER stop "<|eot_id|>"
+PARAMETER stop "<|end|>"
+LICENSE """MIT"""
+MESSAGE user """Is Toronto in Canada?"""
+MESSAGE assistant """Yes"""
+"#
+    );
+
+    let json = serde_json::to_value(modelfile.create_request("mymodel".into()).unwrap()).unwrap();
+    assert_eq!(json["from"], "llama3.2");
+    assert_eq!(json["parameters"]["num_ctx"], 4096);
+    assert_eq!(
+        json["parameters"]["stop"],
+        serde_json::json!(["<|eot_id|>", "<|end|>"])
+    );
+    assert_eq!(json["messages"][1]["role"], "assistant");
+}
+
+#[test]
+fn test_modelfile_builder_triple_quotes() {
+    let modelfile = ModelfileBuilder::new("llama3.2").system(r#"Answer with """ around the code"#);
+    assert!(modelfile.render().is_err());
+
+    let modelfile = ModelfileBuilder::new("llama3.2")
+        .message(MessageRole::User, r#"Say "hi""#)
+        .license(r#"""""#);
+    assert!(modelfile.render().is_err());
+
+    let modelfile = ModelfileBuilder::new("llama3.2").system(r#"Say "hi""#);
+    assert_eq!(
+        modelfile.render().unwrap(),
+        "FROM llama3.2\nSYSTEM \"\"\"Say \"hi\"\"\"\"\n"
+    );
+}
+
+#[test]
+fn test_modelfile_builder_invalid_parameter() {
+    let modelfile = ModelfileBuilder::new("llama3.2")
+        .parameter("num_ctx", 4096)
+        .parameter("temperature", "hot");
+
+    assert!(modelfile.create_request("mymodel".into()).is_err());
+}
@@ -7,3 +7,45 @@ async fn test_delete_model() {
 
     ollama.delete_model("mario_copy".into()).await.unwrap();
 }
+
+#[test]
+fn test_glob_match() {
+    use ollama_rs::models::cleanup::glob_match;
+
+    assert!(glob_match("testmodel*", "testmodel-q4:latest"));
+    assert!(glob_match("*:*-q4_*", "llama3:8b-q4_K_M"));
+    assert!(!glob_match("*:*-q4_*", "llama3:8b-q5_K_M"));
+    assert!(glob_match("llama?:latest", "llama3:latest"));
+    assert!(!glob_match("llama3", "llama3:latest"));
+}
+
+#[tokio::test]
+async fn test_delete_models_matching_dry_run() {
+    let ollama = Ollama::default();
+
+    let models = ollama
+        .delete_models_matching("llama2:*", true)
+        .await
+        .unwrap();
+    dbg!(&models);
+
+    assert!(!models.is_empty());
+    assert!(ollama
+        .list_local_models()
+        .await
+        .unwrap()
+        .iter()
+        .any(|m| m.name == "llama2:latest"));
+}
+
+#[tokio::test]
+async fn test_prune_unused_dry_run() {
+    let ollama = Ollama::default();
+
+    let models = ollama
+        .prune_unused(std::time::Duration::from_secs(30 * 24 * 3600), true)
+        .await
+        .unwrap();
+
+    dbg!(models);
+}
@@ -0,0 +1,61 @@
+use ollama_rs::{
+    generation::embeddings::{chunking::Chunker, request::EmbeddingsInput},
+    Ollama,
+};
+
+#[test]
+fn test_sentence_chunking() {
+    let chunks = Chunker::sentences(20).split("One two. Three four five. Six!");
+
+    assert_eq!(chunks, vec!["One two.", "Three four five.", "Six!"]);
+}
+
+#[test]
+fn test_paragraph_chunking() {
+    let text = "First paragraph.\n\nSecond paragraph.\n\nA third, much longer paragraph.";
+    let chunks = Chunker::paragraphs(40).split(text);
+
+    assert_eq!(
+        chunks,
+        vec![
+            "First paragraph.\n\nSecond paragraph.",
+            "A third, much longer paragraph."
+        ]
+    );
+}
+
+#[test]
+fn test_recursive_chunking_with_overlap() {
+    let chunks = Chunker::recursive(3)
+        .overlap(1)
+        .length_fn(|s| s.split_whitespace().count())
+        .split("a b c d e f g");
+
+    assert_eq!(chunks, vec!["a b c", "c d e", "e f g"]);
+}
+
+#[test]
+fn test_chunk_size_is_respected() {
+    let text = "abcdefghijklmnopqrstuvwxyz ".repeat(10);
+    let chunks = Chunker::recursive(10).split(&text);
+
+    assert!(chunks.iter().all(|c| c.chars().count() <= 10));
+}
+
+#[tokio::test]
+async fn test_embed_chunks() {
+    let ollama = Ollama::default();
+
+    let chunker = Chunker::sentences(100);
+    let request = chunker.embeddings_request(
+        "nomic-embed-text:latest".to_string(),
+        "The sky is blue. Grass is green. The sun is bright.",
+    );
+    let len = request.input().len();
+    assert!(matches!(request.input(), EmbeddingsInput::Multiple(_)));
+
+    let res = ollama.generate_embeddings(request).await.unwrap();
+    dbg!(&res);
+
+    assert_eq!(res.embeddings.len(), len);
+}
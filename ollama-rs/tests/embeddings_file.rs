@@ -0,0 +1,54 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::embeddings::file::{EmbeddingFile, EmbeddingFileWriter},
+};
+
+#[test]
+fn test_embedding_file_search() {
+    let path = std::env::temp_dir().join("ollama_rs_test_embeddings.bin");
+
+    let mut writer = EmbeddingFileWriter::create(&path, 2).unwrap();
+    writer.push(&[1.0, 0.0]).unwrap();
+    writer.push(&[0.0, 1.0]).unwrap();
+    writer.push(&[1.0, 1.0]).unwrap();
+    assert!(matches!(
+        writer.push(&[1.0]),
+        Err(OllamaError::EmbeddingDimensionMismatch { .. })
+    ));
+    writer.finish().unwrap();
+
+    let file = EmbeddingFile::open(&path).unwrap();
+    assert_eq!(file.len(), 3);
+    assert_eq!(file.dimension(), 2);
+    assert_eq!(file.get(1), Some(vec![0.0, 1.0]));
+    assert_eq!(file.get(3), None);
+
+    let scores = file.scores(&[1.0, 0.0]).unwrap().collect::<Vec<_>>();
+    assert_eq!(scores.len(), 3);
+    assert_eq!(scores[0], (0, 1.0));
+
+    let best = file.top_k(&[0.1, 1.0], 2).unwrap();
+    assert_eq!(best.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![1, 2]);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_embedding_file_corrupted_header() {
+    let path = std::env::temp_dir().join("ollama_rs_test_embeddings_corrupted.bin");
+
+    // Dimensions whose records overflow, or don't fit in the file
+    for dimension in [u64::MAX, u64::MAX / 4 + 1, 0, 3] {
+        let mut bytes = b"OLEMBED1".to_vec();
+        bytes.extend(dimension.to_le_bytes());
+        bytes.extend([0u8; 8]);
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(matches!(
+            EmbeddingFile::open(&path),
+            Err(OllamaError::Io(e)) if e.kind() == std::io::ErrorKind::InvalidData
+        ));
+    }
+
+    std::fs::remove_file(path).unwrap();
+}
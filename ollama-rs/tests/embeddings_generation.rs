@@ -1,4 +1,23 @@
-use ollama_rs::{generation::embeddings::request::GenerateEmbeddingsRequest, Ollama};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        embeddings::{
+            math::norm,
+            pipeline::EmbeddingPipeline,
+            request::{GenerateEmbeddingsRequest, Truncation},
+            router::SemanticRouter,
+        },
+        parameters::{KeepAlive, TimeUnit},
+    },
+    models::ModelOptions,
+    Ollama,
+};
+use tokio_stream::StreamExt;
 
 #[tokio::test]
 async fn test_embeddings_generation() {
@@ -29,3 +48,366 @@ async fn test_batch_embeddings_generation() {
 
     dbg!(res);
 }
+
+#[tokio::test]
+async fn test_embeddings_generation_batch() {
+    let ollama = Ollama::default();
+
+    let inputs = vec![
+        "Why is the sky blue?".to_string(),
+        "Why is the sky red?".to_string(),
+        "Why is the grass green?".to_string(),
+    ];
+
+    let res = ollama
+        .generate_embeddings_batch("llama2:latest".to_string(), inputs)
+        .await
+        .unwrap();
+
+    assert_eq!(res.len(), 3);
+    assert!(res.iter().all(|e| e.len() == res[0].len()));
+}
+
+#[tokio::test]
+async fn test_embedding_pipeline() {
+    let ollama = Ollama::default();
+
+    let texts: Vec<String> = (0..10).map(|i| format!("Document number {i}")).collect();
+
+    let mut res = EmbeddingPipeline::new(ollama, "llama2:latest".to_string())
+        .batch_size(3)
+        .max_concurrency(2)
+        .min_interval(Duration::from_millis(10))
+        .run(texts);
+
+    let mut seen = [false; 10];
+    while let Some((index, embedding)) = res.next().await {
+        assert!(!embedding.unwrap().is_empty());
+        seen[index] = true;
+    }
+
+    assert!(seen.iter().all(|s| *s));
+}
+
+#[tokio::test]
+async fn test_embedding_pipeline_retries() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    /// A pipeline embedding 4 texts in batches of 2 with a server answering `responses`, then embeddings
+    async fn run(responses: Vec<StubResponse>) -> (usize, Vec<Result<Vec<f32>, OllamaError>>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let requests_clone = requests.clone();
+        let stub = StubTransport::new().respond_with("api/embed", move |_| {
+            let request = requests_clone.fetch_add(1, Ordering::SeqCst);
+            responses.get(request).cloned().unwrap_or_else(|| {
+                StubResponse::json(json!({ "model": "all-minilm", "embeddings": [[1.0], [2.0]] }))
+            })
+        });
+        let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+        let texts = tokio_stream::iter((0..4).map(|i| format!("Document number {i}")));
+        let mut res: Vec<_> = EmbeddingPipeline::new(ollama, "all-minilm".to_string())
+            .batch_size(2)
+            .max_concurrency(1)
+            .retry_delay(Duration::from_millis(1))
+            .run_stream(texts)
+            .collect()
+            .await;
+
+        res.sort_by_key(|(index, _)| *index);
+        let res = res.into_iter().map(|(_, embedding)| embedding).collect();
+        (requests.load(Ordering::SeqCst), res)
+    }
+
+    // A busy server is retried
+    let (requests, res) = run(vec![StubResponse::error(
+        503,
+        "server busy, please try again",
+    )])
+    .await;
+    assert_eq!(requests, 3);
+    assert!(res.iter().all(|embedding| embedding.is_ok()));
+
+    // A rejected request is not
+    let (requests, res) = run(vec![StubResponse::error(400, "invalid input type")]).await;
+    assert_eq!(requests, 2);
+    assert!(res[0].is_err() && res[1].is_err());
+    assert!(res[2].is_ok() && res[3].is_ok());
+
+    // Every text of a failed batch keeps the variant of the error
+    let (_, res) = run(vec![StubResponse::error(
+        404,
+        "model \"all-minilm\" not found, try pulling it first",
+    )])
+    .await;
+    for embedding in &res[..2] {
+        let error = embedding.as_ref().unwrap_err();
+        assert!(matches!(error.inner(), OllamaError::ModelNotFound(_)));
+    }
+}
+
+#[tokio::test]
+async fn test_embedding_pipeline_progress() {
+    let ollama = Ollama::default();
+
+    let texts: Vec<String> = (0..10).map(|i| format!("Document number {i}")).collect();
+    let reports = Arc::new(Mutex::new(vec![]));
+
+    let reports_clone = reports.clone();
+    let mut res = EmbeddingPipeline::new(ollama, "llama2:latest".to_string())
+        .batch_size(4)
+        .on_progress(move |progress| reports_clone.lock().unwrap().push(progress.clone()))
+        .run(texts);
+
+    while res.next().await.is_some() {}
+
+    let reports = reports.lock().unwrap();
+    dbg!(&reports);
+
+    assert_eq!(reports.len(), 3);
+    let last = reports.last().unwrap();
+    assert_eq!(last.total, Some(10));
+    assert_eq!(last.processed(), 10);
+    assert_eq!(last.remaining(), Some(0));
+    assert!(last.throughput() > 0.0);
+}
+
+#[tokio::test]
+async fn test_embedding_pipeline_progress_failures() {
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    // The batch of the texts 4 to 7 is rejected
+    let stub = StubTransport::new().respond_with("api/embed", |request| {
+        let inputs = request["input"].as_array().unwrap();
+        if inputs[0] == "Document number 4" {
+            return StubResponse::error(400, "invalid input");
+        }
+        let embeddings: Vec<_> = inputs.iter().map(|_| vec![1.0]).collect();
+        StubResponse::json(json!({ "model": "all-minilm", "embeddings": embeddings }))
+    });
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let texts: Vec<String> = (0..10).map(|i| format!("Document number {i}")).collect();
+    let reports = Arc::new(Mutex::new(vec![]));
+
+    let reports_clone = reports.clone();
+    let mut res = EmbeddingPipeline::new(ollama, "all-minilm".to_string())
+        .batch_size(4)
+        .max_concurrency(1)
+        .on_progress(move |progress| reports_clone.lock().unwrap().push(progress.clone()))
+        .run(texts);
+
+    while res.next().await.is_some() {}
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[1].batches, 2);
+    assert_eq!(reports[1].failed_batches, 1);
+    let last = reports.last().unwrap();
+    assert_eq!((last.done, last.failed), (6, 4));
+    assert_eq!((last.batches, last.failed_batches), (3, 1));
+    assert_eq!(last.remaining(), Some(0));
+}
+
+#[tokio::test]
+async fn test_embeddings_generation_without_truncation() {
+    let ollama = Ollama::default();
+
+    let long_input = "Why is the sky blue? ".repeat(10_000);
+
+    let res = ollama
+        .generate_embeddings(
+            GenerateEmbeddingsRequest::new("llama2:latest".to_string(), long_input.into())
+                .options(ModelOptions::default().num_ctx(512))
+                .truncation(Truncation::Error),
+        )
+        .await;
+
+    assert!(matches!(res, Err(OllamaError::ContextLengthExceeded(_))));
+}
+
+#[tokio::test]
+async fn test_embeddings_generation_with_keep_alive_and_options() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .generate_embeddings(
+            GenerateEmbeddingsRequest::new(
+                "llama2:latest".to_string(),
+                vec!["Why is the sky blue?", "Why is the sky red?"].into(),
+            )
+            .keep_alive(KeepAlive::Until {
+                time: 10,
+                unit: TimeUnit::Minutes,
+            })
+            .options(ModelOptions::default().num_ctx(2048).num_gpu(1)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.embeddings.len(), 2);
+}
+
+#[tokio::test]
+async fn test_embed_fixed() {
+    let ollama = Ollama::default();
+
+    let request = || {
+        GenerateEmbeddingsRequest::new(
+            "nomic-embed-text:latest".to_string(),
+            "Why is the sky blue?".into(),
+        )
+    };
+
+    let res = ollama.embed_fixed::<768>(request()).await.unwrap();
+    assert_eq!(res.len(), 1);
+
+    let res = ollama.embed_fixed::<3>(request()).await;
+    assert!(matches!(
+        res,
+        Err(OllamaError::EmbeddingDimensionMismatch {
+            expected: 3,
+            actual: 768
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_embeddings_generation_with_truncate_dim() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .generate_embeddings(
+            GenerateEmbeddingsRequest::new(
+                "nomic-embed-text:latest".to_string(),
+                "Why is the sky blue?".into(),
+            )
+            .truncate_dim(256),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.embeddings[0].len(), 256);
+    assert!((norm(&res.embeddings[0]) - 1.0).abs() < 1e-5);
+}
+
+#[tokio::test]
+async fn test_rerank() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .rerank(
+            "Which animal barks?",
+            vec![
+                "Cats purr when they are happy.".to_string(),
+                "Dogs bark at strangers.".to_string(),
+                "The stock market fell today.".to_string(),
+            ],
+            "nomic-embed-text:latest".to_string(),
+        )
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[0].index, 1);
+    assert!(res[0].score >= res[1].score && res[1].score >= res[2].score);
+}
+
+const IMAGE_URL: &str = "https://images.pexels.com/photos/1054655/pexels-photo-1054655.jpeg";
+
+#[tokio::test]
+async fn test_embed_image() {
+    let ollama = Ollama::default();
+
+    let bytes = reqwest::get(IMAGE_URL)
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+
+    let res = ollama
+        .embed_image("nomic-embed-vision:latest".to_string(), &bytes)
+        .await
+        .unwrap();
+
+    assert!(!res.is_empty());
+}
+
+#[tokio::test]
+async fn test_embed_image_formats() {
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    let stub = StubTransport::new().respond_with("api/embed", |request| {
+        assert_eq!(request["input"], json!([]));
+        assert_eq!(request["images"].as_array().unwrap().len(), 1);
+        StubResponse::json(json!({ "embeddings": [[0.5, 0.5]] }))
+    });
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    let res = ollama
+        .embed_image("nomic-embed-vision:latest".to_string(), png)
+        .await
+        .unwrap();
+    assert_eq!(res, [0.5, 0.5]);
+
+    let webp = b"RIFF\0\0\0\0WEBPVP8 ";
+    assert!(ollama
+        .embed_image("nomic-embed-vision:latest".to_string(), webp)
+        .await
+        .is_ok());
+
+    let gif = b"GIF89a\x01\0\x01\0";
+    let err = ollama
+        .embed_image("nomic-embed-vision:latest".to_string(), gif)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OllamaError::UnsupportedImageFormat));
+}
+
+#[tokio::test]
+async fn test_semantic_router() {
+    let ollama = Ollama::default();
+
+    let router = SemanticRouter::builder(ollama, "nomic-embed-text:latest".to_string())
+        .route(
+            "weather",
+            vec![
+                "Will it rain tomorrow?".to_string(),
+                "What's the temperature outside?".to_string(),
+            ],
+            "weather_tools",
+        )
+        .route(
+            "math",
+            vec![
+                "What is 12 times 7?".to_string(),
+                "Solve x + 2 = 5".to_string(),
+            ],
+            "calculator",
+        )
+        .build()
+        .await
+        .unwrap();
+
+    let res = router
+        .route("Is it going to be sunny this weekend?")
+        .await
+        .unwrap()
+        .unwrap();
+    dbg!(&res);
+
+    assert_eq!(res.name, "weather");
+    assert_eq!(*res.target, "weather_tools");
+
+    let scores = router.scores("Compute 3 squared").await.unwrap();
+    assert_eq!(scores.len(), 2);
+    assert!(scores[1].score > scores[0].score);
+}
@@ -0,0 +1,46 @@
+use ollama_rs::generation::embeddings::math::{
+    cosine_similarity, dot_product, euclidean_distance, norm, normalize, top_k, truncate_dim,
+};
+
+#[test]
+fn test_vector_math() {
+    let a = [1.0, 0.0, 0.0];
+    let b = [0.0, 2.0, 0.0];
+
+    assert_eq!(dot_product(&a, &b), 0.0);
+    assert_eq!(norm(&b), 2.0);
+    assert_eq!(cosine_similarity(&a, &a), 1.0);
+    assert_eq!(cosine_similarity(&a, &b), 0.0);
+    assert_eq!(cosine_similarity(&a, &[0.0, 0.0, 0.0]), 0.0);
+    assert!((euclidean_distance(&a, &b) - 5.0f32.sqrt()).abs() < 1e-6);
+}
+
+#[test]
+fn test_top_k() {
+    let query = vec![1.0, 1.0];
+    let candidates = vec![vec![-1.0, -1.0], vec![1.0, 0.9], vec![0.0, 1.0]];
+
+    let best = top_k(&query, &candidates, 2);
+
+    assert_eq!(best.len(), 2);
+    assert_eq!(best[0].0, 1);
+    assert_eq!(best[1].0, 2);
+}
+
+#[test]
+fn test_normalize() {
+    let mut v = vec![3.0, 4.0];
+    normalize(&mut v);
+    assert_eq!(v, vec![0.6, 0.8]);
+
+    let mut null = vec![0.0, 0.0];
+    normalize(&mut null);
+    assert_eq!(null, vec![0.0, 0.0]);
+}
+
+#[test]
+fn test_truncate_dim() {
+    let mut v = vec![3.0, 4.0, 12.0];
+    truncate_dim(&mut v, 2);
+    assert_eq!(v, vec![0.6, 0.8]);
+}
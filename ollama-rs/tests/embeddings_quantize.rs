@@ -0,0 +1,54 @@
+use ollama_rs::generation::embeddings::{
+    math::{cosine_similarity, normalized},
+    quantize::{
+        f16_to_f32, f32_to_f16, F16Embedding, Int8Embedding, Precision, QuantizedEmbedding,
+    },
+    store::{EmbeddingRecord, InMemoryVectorStore, VectorStore},
+};
+
+#[test]
+fn test_f16_conversion() {
+    assert_eq!(f32_to_f16(1.0), 0x3c00);
+    assert_eq!(f32_to_f16(-2.5), 0xc100);
+    assert_eq!(f32_to_f16(65504.0), 0x7bff);
+    assert_eq!(f32_to_f16(1e6), 0x7c00);
+    assert_eq!(f32_to_f16(1e-10), 0);
+
+    for bits in [0x0001, 0x03ff, 0x3555, 0x7bff, 0xc100] {
+        assert_eq!(f32_to_f16(f16_to_f32(bits)), bits);
+    }
+}
+
+#[test]
+fn test_quantization_preserves_similarity() {
+    let a = normalized(&[0.1, -0.4, 0.25, 0.8, -0.05, 0.3]);
+    let b = normalized(&[0.2, -0.3, 0.2, 0.7, 0.1, 0.2]);
+    let similarity = cosine_similarity(&a, &b);
+
+    let f16 = F16Embedding::from_f32(&a);
+    assert!((cosine_similarity(&f16.to_f32(), &b) - similarity).abs() < 1e-3);
+
+    let int8 = Int8Embedding::from_f32(&a);
+    assert!((int8.cosine_similarity(&Int8Embedding::from_f32(&b)) - similarity).abs() < 1e-2);
+
+    let quantized = QuantizedEmbedding::new(&a, Precision::Int8);
+    assert_eq!(quantized.precision(), Precision::Int8);
+    assert!((quantized.cosine_similarity(&b) - similarity).abs() < 1e-2);
+}
+
+#[tokio::test]
+async fn test_quantized_in_memory_store() {
+    let store = InMemoryVectorStore::with_precision(Precision::F16);
+
+    store
+        .upsert(vec![
+            EmbeddingRecord::new("a", vec![1.0, 0.0]),
+            EmbeddingRecord::new("b", vec![0.0, 1.0]),
+        ])
+        .await
+        .unwrap();
+
+    let res = store.search(&[0.1, 1.0], 2).await.unwrap();
+    assert_eq!(res[0].id, "b");
+    assert_eq!(res[1].id, "a");
+}
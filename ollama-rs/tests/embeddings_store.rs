@@ -0,0 +1,79 @@
+use ollama_rs::{
+    generation::embeddings::store::{EmbeddingRecord, InMemoryVectorStore, VectorStore},
+    Ollama,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_in_memory_store() {
+    let store = InMemoryVectorStore::new();
+
+    store
+        .upsert(vec![
+            EmbeddingRecord::new("a", vec![1.0, 0.0]),
+            EmbeddingRecord::new("b", vec![0.0, 1.0]).payload(json!({ "text": "b" })),
+        ])
+        .await
+        .unwrap();
+    store
+        .upsert(vec![EmbeddingRecord::new("a", vec![1.0, 1.0])])
+        .await
+        .unwrap();
+    assert_eq!(store.len(), 2);
+
+    let res = store.search(&[0.0, 1.0], 1).await.unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].id, "b");
+    assert_eq!(res[0].payload, json!({ "text": "b" }));
+}
+
+#[tokio::test]
+async fn test_in_memory_store_bulk_upsert() {
+    let store = InMemoryVectorStore::new();
+
+    let records = (0..10_000)
+        .map(|i| EmbeddingRecord::new(format!("{i}"), vec![1.0, i as f32]))
+        .chain([EmbeddingRecord::new("42", vec![0.0, -1.0]).payload(json!({ "text": "42" }))])
+        .collect();
+    store.upsert(records).await.unwrap();
+    assert_eq!(store.len(), 10_000);
+
+    // The last record with an id replaces the previous ones
+    let res = store.search(&[0.0, -1.0], 1).await.unwrap();
+    assert_eq!(res[0].id, "42");
+    assert_eq!(res[0].payload, json!({ "text": "42" }));
+}
+
+#[tokio::test]
+async fn test_embed_into_store() {
+    let ollama = Ollama::default();
+    let store = InMemoryVectorStore::new();
+
+    ollama
+        .embed_into(
+            &store,
+            "nomic-embed-text:latest".to_string(),
+            vec![
+                ("1".to_string(), "The sky is blue.".to_string()),
+                (
+                    "2".to_string(),
+                    "Rust is a programming language.".to_string(),
+                ),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let query = ollama
+        .generate_embeddings_batch(
+            "nomic-embed-text:latest".to_string(),
+            vec!["What language should I learn?".to_string()],
+        )
+        .await
+        .unwrap();
+
+    let res = store.search(&query[0], 2).await.unwrap();
+    dbg!(&res);
+
+    assert_eq!(res[0].id, "2");
+}
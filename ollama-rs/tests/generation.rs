@@ -2,9 +2,11 @@
 use base64::Engine;
 use ollama_rs::{
     generation::{
-        completion::{request::GenerationRequest, GenerationResponseStream},
+        completion::{best_of::BestOfScorer, request::GenerationRequest, GenerationResponseStream},
+        grammar::Grammar,
         images::Image,
     },
+    models::ModelOptions,
     Ollama,
 };
 use tokio::io::AsyncWriteExt;
@@ -37,6 +39,65 @@ async fn test_generation_stream() {
     assert!(done);
 }
 
+#[tokio::test]
+async fn test_generation_stream_multiplexed() {
+    let ollama = Ollama::default();
+
+    let requests = vec![
+        GenerationRequest::new("llama2:latest".to_string(), PROMPT),
+        GenerationRequest::new("llama2:latest".to_string(), "Why is the grass green?"),
+        GenerationRequest::new("llama2:latest".to_string(), "Why is the sea salty?"),
+    ];
+
+    let mut res = ollama.generate_stream_multiplexed(requests, 2);
+
+    let mut done = [false; 3];
+    while let Some((index, res)) = res.next().await {
+        for ele in res.unwrap() {
+            if ele.done {
+                done[index] = true;
+            }
+        }
+    }
+
+    assert!(done.iter().all(|d| *d));
+}
+
+#[tokio::test]
+async fn test_generation_stream_multiplexed_sequential() {
+    use ollama_rs::stub::StubTransport;
+
+    let ollama = Ollama::builder()
+        .stub(StubTransport::new().reply("{prompt}"))
+        .build()
+        .unwrap();
+
+    let prompts = [
+        "Why is the sky blue?",
+        "Why is the grass green?",
+        "Why is the sea salty?",
+    ];
+    let requests = prompts
+        .iter()
+        .map(|prompt| GenerationRequest::new("llama2:latest".to_string(), *prompt))
+        .collect();
+
+    // A single generation at a time, the next one starts once the previous one finished
+    let mut res = ollama.generate_stream_multiplexed(requests, 1);
+
+    let mut responses = vec![String::new(); prompts.len()];
+    let mut order = vec![];
+    while let Some((index, res)) = res.next().await {
+        assert!(order.last().is_none_or(|last| *last <= index));
+        order.push(index);
+        for ele in res.unwrap() {
+            responses[index] += &ele.response;
+        }
+    }
+
+    assert_eq!(responses, prompts);
+}
+
 #[tokio::test]
 async fn test_generation() {
     let ollama = Ollama::default();
@@ -48,6 +109,68 @@ async fn test_generation() {
     dbg!(res);
 }
 
+#[tokio::test]
+async fn test_generation_best_of() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .generate_best_of(
+            GenerationRequest::new("llama2:latest".to_string(), PROMPT),
+            3,
+            BestOfScorer::custom(|c| -(c.response.len() as f32)),
+        )
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert_eq!(res.candidates.len(), 3);
+    let shortest = res.candidates.iter().map(|c| c.response.len()).min();
+    assert_eq!(Some(res.best().response.len()), shortest);
+}
+
+#[tokio::test]
+async fn test_generation_with_grammar() {
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    // Ollama ignores the grammar, which is only checked to be sent unchanged
+    let stub = StubTransport::new().respond_with("api/generate", |request| {
+        StubResponse::json(json!({
+            "model": request["model"],
+            "created_at": "2024-01-01T00:00:00Z",
+            "response": request["options"]["grammar"],
+            "done": true,
+        }))
+    });
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let res = ollama
+        .generate(
+            GenerationRequest::new("llama2:latest".to_string(), "Is the sky blue?")
+                .options(ModelOptions::default().grammar(Grammar::yes_no())),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.response, Grammar::yes_no().as_str());
+}
+
+#[tokio::test]
+async fn test_generation_with_banned_strings() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .generate(
+            GenerationRequest::new("llama2:latest".to_string(), PROMPT)
+                .options(ModelOptions::default().ban_strings(["Rayleigh"])),
+        )
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(!res.response.contains("Rayleigh"));
+}
+
 const IMAGE_URL: &str = "https://images.pexels.com/photos/1054655/pexels-photo-1054655.jpeg";
 
 #[tokio::test]
@@ -75,3 +198,57 @@ async fn test_generation_with_images() {
         .unwrap();
     dbg!(res);
 }
+
+#[tokio::test]
+async fn test_generation_auto_pull() {
+    use ollama_rs::models::auto_pull::AutoPullPolicy;
+
+    let mut ollama = Ollama::default();
+    let _ = ollama.delete_model("smollm:135m".into()).await;
+
+    let res = ollama
+        .generate(GenerationRequest::new("smollm:135m".into(), PROMPT))
+        .await;
+    assert!(res.is_err());
+
+    ollama.set_auto_pull(Some(AutoPullPolicy::new().on_progress(|progress| {
+        dbg!(progress.phase(), progress.percent());
+    })));
+
+    let res = ollama
+        .generate(GenerationRequest::new("smollm:135m".into(), PROMPT))
+        .await
+        .unwrap();
+    dbg!(res);
+}
+
+#[tokio::test]
+async fn test_generation_model_alias() {
+    let mut ollama = Ollama::default();
+    ollama.set_model_alias("fast", "llama2:latest");
+
+    let res = ollama
+        .generate(GenerationRequest::new("fast".into(), PROMPT))
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert_eq!(res.model, "llama2:latest");
+}
+
+#[test]
+fn test_resolve_model_alias() {
+    let mut ollama = Ollama::default();
+    ollama.set_model_alias("fast", "llama3.2:3b");
+    ollama.set_model_alias("smart", "llama3.1:70b");
+
+    assert_eq!(ollama.resolve_model_alias("fast".into()), "llama3.2:3b");
+    assert_eq!(ollama.resolve_model_alias("smart".into()), "llama3.1:70b");
+    assert_eq!(ollama.resolve_model_alias("mistral".into()), "mistral");
+
+    assert_eq!(
+        ollama.remove_model_alias("fast").as_deref(),
+        Some("llama3.2:3b")
+    );
+    assert_eq!(ollama.resolve_model_alias("fast".into()), "fast");
+}
@@ -0,0 +1,55 @@
+use ollama_rs::{
+    health::{HealthStatus, ReadinessStatus},
+    Ollama,
+};
+
+#[tokio::test]
+async fn test_health() {
+    let ollama = Ollama::default();
+
+    let health = ollama.health().await;
+    dbg!(&health);
+    assert!(health.is_healthy());
+
+    // Nothing listens on the port
+    let ollama = Ollama::try_new("http://127.0.0.1:9").unwrap();
+    assert!(matches!(
+        ollama.health().await,
+        HealthStatus::Unreachable { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_ready() {
+    let ollama = Ollama::default();
+
+    let readiness = ollama.ready("llama2:latest".to_string()).await;
+    dbg!(&readiness);
+    assert!(readiness.is_ready());
+
+    let readiness = ollama.ready("no-such-model:latest".to_string()).await;
+    assert_eq!(readiness, ReadinessStatus::ModelNotFound);
+}
+
+#[tokio::test]
+async fn test_ready_registry_with_port() {
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    let running = json!({ "models": [{
+        "name": "registry.local:5000/model:latest",
+        "size": 1000,
+        "expires_at": "2099-01-01T00:00:00Z",
+    }] });
+    let stub = StubTransport::new().respond("api/ps", StubResponse::json(running));
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let readiness = ollama.ready("registry.local:5000/model".to_string()).await;
+    assert!(matches!(
+        readiness,
+        ReadinessStatus::Ready {
+            already_loaded: true,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_extend_keep_alive_and_unload() {
+    let ollama = ollama_rs::Ollama::default();
+
+    ollama
+        .extend_keep_alive("llama2:latest".to_string(), Duration::from_secs(3600))
+        .await
+        .unwrap();
+
+    let models = ollama.list_running_models().await.unwrap();
+    let model = models.iter().find(|m| m.name == "llama2:latest").unwrap();
+    assert!(model.expires_in().unwrap() > Duration::from_secs(3000));
+
+    ollama
+        .unload_model("llama2:latest".to_string())
+        .await
+        .unwrap();
+
+    let models = ollama.list_running_models().await.unwrap();
+    assert!(models.iter().all(|m| m.name != "llama2:latest"));
+}
+
+#[tokio::test]
+async fn test_unload_all_models() {
+    let ollama = ollama_rs::Ollama::default();
+
+    ollama
+        .extend_keep_alive("llama2:latest".to_string(), Duration::from_secs(60))
+        .await
+        .unwrap();
+
+    let unloaded = ollama.unload_all_models().await.unwrap();
+    assert!(unloaded.contains(&"llama2:latest".to_string()));
+
+    assert!(ollama.list_running_models().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_warm_up_models() {
+    use ollama_rs::generation::parameters::{KeepAlive, TimeUnit};
+
+    let ollama = ollama_rs::Ollama::default();
+    let keep_alive = KeepAlive::Until {
+        time: 5,
+        unit: TimeUnit::Minutes,
+    };
+
+    let model = ollama
+        .warm_up("llama2".to_string(), keep_alive.clone())
+        .await
+        .unwrap();
+    assert_eq!(model.name, "llama2:latest");
+
+    let results = ollama
+        .warm_up_models(
+            vec![
+                "llama2:latest".to_string(),
+                "nomic-embed-text:latest".to_string(),
+            ],
+            keep_alive,
+        )
+        .await;
+    dbg!(&results);
+
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.is_ok()));
+}
+
+#[tokio::test]
+async fn test_warm_up_registry_with_port() {
+    use ollama_rs::{
+        generation::parameters::{KeepAlive, TimeUnit},
+        stub::{StubResponse, StubTransport},
+    };
+    use serde_json::json;
+
+    let stub = StubTransport::new()
+        .respond(
+            "api/generate",
+            StubResponse::json(json!({
+                "model": "registry.local:5000/model",
+                "created_at": "2024-01-01T00:00:00Z",
+                "response": "",
+                "done": true,
+            })),
+        )
+        .respond(
+            "api/ps",
+            StubResponse::json(json!({ "models": [{
+                "name": "registry.local:5000/model:latest",
+                "size": 1000,
+                "expires_at": "2099-01-01T00:00:00Z",
+            }] })),
+        );
+    let ollama = ollama_rs::Ollama::builder().stub(stub).build().unwrap();
+
+    let model = ollama
+        .warm_up(
+            "registry.local:5000/model".to_string(),
+            KeepAlive::Until {
+                time: 5,
+                unit: TimeUnit::Minutes,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(model.name, "registry.local:5000/model:latest");
+}
+
+#[test]
+fn test_keep_alive_deserialize() {
+    use ollama_rs::generation::parameters::{KeepAlive, TimeUnit};
+
+    let parse = |value: serde_json::Value| serde_json::from_value::<KeepAlive>(value).unwrap();
+    let seconds = |keep_alive: KeepAlive| match keep_alive {
+        KeepAlive::Until { time, unit } => match unit {
+            TimeUnit::Seconds => time,
+            TimeUnit::Minutes => time * 60,
+            TimeUnit::Hours => time * 3600,
+        },
+        other => panic!("expected a duration, got {other:?}"),
+    };
+
+    assert_eq!(seconds(parse(serde_json::json!(300))), 300);
+    assert_eq!(seconds(parse(serde_json::json!(1.5))), 2);
+    assert_eq!(seconds(parse(serde_json::json!("300"))), 300);
+    assert_eq!(seconds(parse(serde_json::json!("5m"))), 300);
+    assert_eq!(seconds(parse(serde_json::json!("1h30m"))), 5400);
+    assert_eq!(seconds(parse(serde_json::json!("0.1h"))), 360);
+    assert_eq!(seconds(parse(serde_json::json!("1m30.5s"))), 91);
+    assert!(matches!(
+        parse(serde_json::json!(-1)),
+        KeepAlive::Indefinitely
+    ));
+    assert!(matches!(
+        parse(serde_json::json!("-1m")),
+        KeepAlive::Indefinitely
+    ));
+    assert!(matches!(
+        parse(serde_json::json!("0")),
+        KeepAlive::UnloadOnCompletion
+    ));
+    assert!(serde_json::from_value::<KeepAlive>(serde_json::json!("5 minutes")).is_err());
+    assert!(serde_json::from_value::<KeepAlive>(serde_json::json!("m")).is_err());
+}
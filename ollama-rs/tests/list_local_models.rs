@@ -6,3 +6,197 @@ async fn test_list_local_models() {
 
     dbg!(models);
 }
+
+#[tokio::test]
+async fn test_disk_usage() {
+    let ollama = ollama_rs::Ollama::default();
+
+    let report = ollama.disk_usage().await.unwrap();
+    dbg!(&report);
+
+    assert!(report.deduplicated_size() <= report.total_size());
+    assert!(report.models.iter().all(|m| !m.blobs.is_empty()));
+}
+
+#[tokio::test]
+async fn test_disk_usage_blobs() {
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    let weights = format!("sha256-{}", "a".repeat(64));
+    let adapter = format!("sha256-{}", "b".repeat(64));
+    let modelfile = format!(
+        "# Modelfile generated by \"ollama show\"\nFROM /root/.ollama/models/blobs/{weights}\n\
+         TEMPLATE {{{{ .Prompt }}}}\nadapter /root/.ollama/models/blobs/{adapter}\n"
+    );
+    let stub = StubTransport::new()
+        .respond(
+            "api/tags",
+            StubResponse::json(json!({ "models": [{
+                "name": "custom:latest",
+                "modified_at": "2024-01-01T00:00:00Z",
+                "size": 1000,
+            }] })),
+        )
+        .respond(
+            "api/show",
+            StubResponse::json(json!({ "modelfile": modelfile })),
+        );
+    let ollama = ollama_rs::Ollama::builder().stub(stub).build().unwrap();
+
+    let report = ollama.disk_usage().await.unwrap();
+    assert_eq!(
+        report.models[0].blobs,
+        [
+            format!("sha256:{}", "a".repeat(64)),
+            format!("sha256:{}", "b".repeat(64)),
+        ]
+    );
+}
+
+#[test]
+fn test_disk_usage_deduplicated_size() {
+    use ollama_rs::models::disk_usage::{DiskUsageReport, ModelDiskUsage};
+
+    let model = |name: &str, size, weights: &str, shared_with: &[&str]| ModelDiskUsage {
+        name: name.into(),
+        size,
+        blobs: vec![weights.into()],
+        shared_with: shared_with.iter().map(|s| s.to_string()).collect(),
+    };
+
+    let report = DiskUsageReport {
+        models: vec![
+            model("llama3.2:latest", 2_000, "sha256:a", &["llama3.2:3b"]),
+            model("llama3.2:3b", 2_000, "sha256:a", &["llama3.2:latest"]),
+            model(
+                "custom:latest",
+                2_010,
+                "sha256:a",
+                &["llama3.2:latest", "llama3.2:3b"],
+            ),
+            model("mistral:latest", 4_000, "sha256:b", &[]),
+        ],
+    };
+
+    assert_eq!(report.total_size(), 10_010);
+    assert_eq!(report.deduplicated_size(), 6_010);
+}
+
+#[test]
+fn test_diff_local_models() {
+    use ollama_rs::models::{
+        watch::{diff_local_models, LocalModelEvent},
+        LocalModel,
+    };
+
+    let model = |name: &str, digest: &str| LocalModel {
+        name: name.into(),
+        modified_at: "2024-05-01T10:00:00Z".into(),
+        size: 1,
+        digest: digest.into(),
+        details: Default::default(),
+    };
+
+    let old = vec![model("llama3.2:latest", "a"), model("mistral:latest", "b")];
+    let new = vec![model("llama3.2:latest", "c"), model("qwen2:latest", "d")];
+
+    let events = diff_local_models(&old, &new);
+    dbg!(&events);
+
+    assert!(matches!(&events[0], LocalModelEvent::Removed(m) if m.name == "mistral:latest"));
+    assert!(
+        matches!(&events[1], LocalModelEvent::Updated { old, new } if old.digest == "a" && new.digest == "c")
+    );
+    assert!(matches!(&events[2], LocalModelEvent::Added(m) if m.name == "qwen2:latest"));
+    assert!(diff_local_models(&new, &new).is_empty());
+}
+
+#[tokio::test]
+async fn test_watch_local_models() {
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+
+    let ollama = ollama_rs::Ollama::default();
+    let mut events = ollama.watch_local_models(Duration::from_millis(200));
+
+    let _ = ollama.delete_model("smollm:135m".into()).await;
+    tokio::spawn({
+        let ollama = ollama.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            ollama
+                .pull_model("smollm:135m".into(), false)
+                .await
+                .unwrap();
+        }
+    });
+
+    let event = events.next().await.unwrap().unwrap();
+    dbg!(&event);
+    assert_eq!(event.model_name(), "smollm:135m");
+}
+
+#[test]
+fn test_local_model_query() {
+    use ollama_rs::models::{
+        list_local::{LocalModelQuery, LocalModelSort},
+        LocalModel,
+    };
+
+    let models: Vec<LocalModel> = serde_json::from_value(serde_json::json!([
+        {
+            "name": "llama3.2:latest",
+            "modified_at": "2024-10-01T10:00:00.123456789+02:00",
+            "size": 2_000_000_000u64,
+            "details": { "family": "llama", "families": ["llama"], "parameter_size": "3.2B" }
+        },
+        {
+            "name": "llama3.1:70b",
+            "modified_at": "2024-08-01T10:00:00Z",
+            "size": 40_000_000_000u64,
+            "details": { "family": "llama", "parameter_size": "70.6B" }
+        },
+        {
+            "name": "smollm:135m",
+            "modified_at": "2024-09-01T10:00:00Z",
+            "size": 90_000_000u64,
+            "details": { "family": "llama", "parameter_size": "135M" }
+        },
+        {
+            "name": "qwen2:7b",
+            "modified_at": "2024-11-01T10:00:00Z",
+            "size": 4_000_000_000u64,
+            "details": { "family": "qwen2", "parameter_size": "7.6B" }
+        }
+    ]))
+    .unwrap();
+
+    let names = |models: Vec<LocalModel>| models.into_iter().map(|m| m.name).collect::<Vec<_>>();
+
+    let query = LocalModelQuery::new()
+        .by_family("llama")
+        .sort_by(LocalModelSort::Size)
+        .descending(true);
+    assert_eq!(
+        names(query.apply(models.clone())),
+        vec!["llama3.1:70b", "llama3.2:latest", "smollm:135m"]
+    );
+
+    let query = LocalModelQuery::new()
+        .by_parameter_size_range(1_000_000_000, 10_000_000_000)
+        .sort_by(LocalModelSort::Name);
+    assert_eq!(
+        names(query.apply(models.clone())),
+        vec!["llama3.2:latest", "qwen2:7b"]
+    );
+
+    let since = "2024-09-15T00:00:00Z".parse().unwrap();
+    let query = LocalModelQuery::new()
+        .modified_since(since)
+        .sort_by(LocalModelSort::ModifiedAt);
+    assert_eq!(
+        names(query.apply(models)),
+        vec!["llama3.2:latest", "qwen2:7b"]
+    );
+}
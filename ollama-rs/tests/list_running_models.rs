@@ -0,0 +1,21 @@
+use ollama_rs::generation::completion::request::GenerationRequest;
+
+#[tokio::test]
+async fn test_list_running_models() {
+    let ollama = ollama_rs::Ollama::default();
+
+    ollama
+        .generate(GenerationRequest::new(
+            "llama2:latest".to_string(),
+            "Hi".to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let models = ollama.list_running_models().await.unwrap();
+    dbg!(&models);
+
+    let model = models.iter().find(|m| m.name == "llama2:latest").unwrap();
+    assert!(model.size > 0);
+    assert!(model.expires_in().is_some());
+}
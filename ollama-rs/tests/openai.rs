@@ -0,0 +1,188 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::{
+        chat::{ChatMessage, MessageRole},
+        images::Image,
+    },
+    openai::{
+        chat::{
+            ChatCompletionContent, ChatCompletionContentPart, ChatCompletionFunctionCall,
+            ChatCompletionImageUrl, ChatCompletionMessage, ChatCompletionRequest,
+            ChatCompletionToolCall,
+        },
+        embeddings::EmbeddingRequest,
+    },
+    Ollama,
+};
+use tokio_stream::StreamExt;
+
+#[allow(dead_code)]
+const PROMPT: &str = "Why is the sky blue?";
+
+#[tokio::test]
+async fn test_openai_chat_completion() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .openai_chat_completion(ChatCompletionRequest::new(
+            "llama2:latest".to_string(),
+            vec![ChatCompletionMessage::user(PROMPT.to_string())],
+        ))
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert_eq!(res.choices.len(), 1);
+    assert!(res.choices[0].message.content.is_some());
+}
+
+#[tokio::test]
+async fn test_openai_chat_completion_stream() {
+    let ollama = Ollama::default();
+
+    let mut res = ollama
+        .openai_chat_completion_stream(ChatCompletionRequest::new(
+            "llama2:latest".to_string(),
+            vec![ChatCompletionMessage::user(PROMPT.to_string())],
+        ))
+        .await
+        .unwrap();
+
+    let mut finished = false;
+    while let Some(res) = res.next().await {
+        let res = res.unwrap();
+        dbg!(&res);
+        if res.choices.iter().any(|c| c.finish_reason.is_some()) {
+            finished = true;
+        }
+    }
+
+    assert!(finished);
+}
+
+#[tokio::test]
+async fn test_openai_chat_completion_stream_split_characters() {
+    use ollama_rs::transport::{BoxFuture, Transport};
+
+    /// Streams the events byte by byte, splitting the characters encoded on several bytes
+    struct Bytewise;
+
+    impl Transport for Bytewise {
+        fn send(
+            &self,
+            _request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let events = ["Café", " ☕ ", "über 🌍"]
+                .iter()
+                .map(|content| {
+                    let chunk = serde_json::json!({
+                        "id": "chatcmpl-1",
+                        "object": "chat.completion.chunk",
+                        "created": 1700000000,
+                        "model": "llama2:latest",
+                        "choices": [{ "index": 0, "delta": { "content": content } }],
+                    });
+                    format!("data: {chunk}\n\n")
+                })
+                .chain(["data: [DONE]\n\n".to_string()])
+                .collect::<String>();
+            let bytes = events
+                .into_bytes()
+                .into_iter()
+                .map(|byte| Ok::<_, std::io::Error>(vec![byte]));
+            let body = reqwest::Body::wrap_stream(tokio_stream::iter(bytes));
+            let res = http::Response::builder().status(200).body(body).unwrap();
+            Box::pin(async move { Ok(res.into()) })
+        }
+    }
+
+    let ollama = Ollama::default().with_transport(Bytewise);
+    let mut res = ollama
+        .openai_chat_completion_stream(ChatCompletionRequest::new(
+            "llama2:latest".to_string(),
+            vec![ChatCompletionMessage::user(PROMPT.to_string())],
+        ))
+        .await
+        .unwrap();
+
+    let mut content = String::new();
+    while let Some(res) = res.next().await {
+        for choice in res.unwrap().choices {
+            content += &choice.delta.content.unwrap_or_default();
+        }
+    }
+
+    assert_eq!(content, "Café ☕ über 🌍");
+}
+
+#[tokio::test]
+async fn test_openai_embeddings() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .openai_embeddings(EmbeddingRequest::new(
+            "llama2:latest".to_string(),
+            vec!["Why is the sky blue?", "Why is the sky red?"].into(),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(res.data.len(), 2);
+}
+
+#[test]
+fn test_openai_tool_message_round_trip() {
+    let message = ChatMessage::tool("42".to_string()).with_tool_call_id("call_1");
+
+    let converted: ChatCompletionMessage = message.into();
+    assert_eq!(converted.tool_call_id.as_deref(), Some("call_1"));
+
+    let back: ChatMessage = converted.try_into().unwrap();
+    assert_eq!(back.role, MessageRole::Tool);
+    assert_eq!(back.content, "42");
+    assert_eq!(back.tool_call_id.as_deref(), Some("call_1"));
+}
+
+#[test]
+fn test_openai_message_round_trip_images_and_thinking() {
+    // The magic bytes of a PNG image
+    let image = Image::from_bytes(b"\x89PNG\r\n\x1a\n\0\0\0\0").unwrap();
+    let mut message =
+        ChatMessage::assistant("Here it is".to_string()).with_images(vec![image.clone()]);
+    message.thinking = Some("Let me look at it".to_string());
+
+    let converted: ChatCompletionMessage = message.into();
+    assert_eq!(converted.reasoning.as_deref(), Some("Let me look at it"));
+    let Some(ChatCompletionContent::Parts(parts)) = &converted.content else {
+        panic!("expected content parts, got {:?}", converted.content);
+    };
+    assert_eq!(
+        parts[1],
+        ChatCompletionContentPart::ImageUrl {
+            image_url: ChatCompletionImageUrl {
+                url: format!("data:image/png;base64,{}", image.to_base64()),
+            },
+        }
+    );
+
+    let back: ChatMessage = converted.try_into().unwrap();
+    assert_eq!(back.content, "Here it is");
+    assert_eq!(back.thinking.as_deref(), Some("Let me look at it"));
+    assert_eq!(back.images.unwrap()[0].to_base64(), image.to_base64());
+}
+
+#[test]
+fn test_openai_message_invalid_tool_arguments() {
+    let mut message = ChatCompletionMessage::assistant(String::new());
+    message.tool_calls = vec![ChatCompletionToolCall {
+        id: "call_1".to_string(),
+        call_type: "function".to_string(),
+        function: ChatCompletionFunctionCall {
+            name: "get_weather".to_string(),
+            arguments: "{\"city\": ".to_string(),
+        },
+    }];
+
+    let res: Result<ChatMessage, _> = message.try_into();
+    assert!(matches!(res, Err(OllamaError::JsonError(_))));
+}
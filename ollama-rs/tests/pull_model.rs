@@ -1,4 +1,7 @@
-use ollama_rs::Ollama;
+use ollama_rs::{
+    models::pull::{PullModelStatus, PullPhase, PullProgress},
+    Ollama,
+};
 use tokio_stream::StreamExt;
 
 #[tokio::test]
@@ -17,3 +20,289 @@ async fn test_pull_model() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_pull_model_progress() {
+    let ollama = Ollama::default();
+
+    let mut res = ollama
+        .pull_model_progress_stream("llama2:latest".into(), false)
+        .await
+        .unwrap();
+
+    let mut last = None;
+    while let Some(res) = res.next().await {
+        let progress = res.unwrap();
+        println!(
+            "{:?} {:?}% eta {:?}",
+            progress.phase(),
+            progress.percent(),
+            progress.eta()
+        );
+        last = Some(progress);
+    }
+
+    let last = last.unwrap();
+    assert_eq!(last.phase(), &PullPhase::Success);
+    assert_eq!(last.percent(), Some(100.0));
+}
+
+#[test]
+fn test_pull_progress_aggregation() {
+    let status = |json: serde_json::Value| serde_json::from_value::<PullModelStatus>(json).unwrap();
+
+    let mut progress = PullProgress::new();
+    progress.update(&status(serde_json::json!({ "status": "pulling manifest" })));
+    assert_eq!(progress.phase(), &PullPhase::Manifest);
+    assert_eq!(progress.percent(), None);
+
+    progress.update(&status(serde_json::json!({
+        "status": "pulling 6a0746a1ec1a",
+        "digest": "sha256:6a0746a1ec1a",
+        "total": 300,
+        "completed": 150
+    })));
+    progress.update(&status(serde_json::json!({
+        "status": "pulling 4fa551d4f938",
+        "digest": "sha256:4fa551d4f938",
+        "total": 100
+    })));
+
+    assert_eq!(progress.phase(), &PullPhase::Downloading);
+    assert_eq!(progress.layers(), 2);
+    assert_eq!(progress.completed_bytes(), 150);
+    assert_eq!(progress.total_bytes(), 400);
+    assert_eq!(progress.percent(), Some(37.5));
+
+    progress.update(&status(
+        serde_json::json!({ "status": "verifying sha256 digest" }),
+    ));
+    assert_eq!(progress.phase(), &PullPhase::Verifying);
+}
+
+#[tokio::test]
+async fn test_pull_model_verified() {
+    let ollama = Ollama::default();
+
+    let report = ollama
+        .pull_model_verified("llama2:latest".into(), false)
+        .await
+        .unwrap();
+
+    dbg!(&report);
+    assert!(!report.layers.is_empty());
+    assert!(
+        report.is_ok(),
+        "missing layers: {:?}",
+        report.missing().collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_verify_pulled_model() {
+    use std::sync::{Arc, Mutex};
+
+    use ollama_rs::transport::{BoxFuture, Transport};
+
+    /// Serves the manifest of the registry, and the blobs of the server but one
+    #[derive(Clone, Default)]
+    struct Registry(Arc<Mutex<Vec<String>>>);
+
+    impl Transport for Registry {
+        fn send(
+            &self,
+            request: reqwest::Request,
+        ) -> BoxFuture<'_, ollama_rs::error::Result<reqwest::Response>> {
+            let url = request.url().clone();
+            self.0.lock().unwrap().push(url.to_string());
+
+            let res = match url.path() {
+                "/v2/library/llama2/manifests/latest" => http::Response::builder()
+                    .status(200)
+                    .body(
+                        serde_json::json!({
+                            "config": { "mediaType": "application/vnd.docker.container.image.v1+json", "digest": "sha256:aaaa", "size": 10 },
+                            "layers": [
+                                { "mediaType": "application/vnd.ollama.image.model", "digest": "sha256:bbbb", "size": 100 },
+                                { "mediaType": "application/vnd.ollama.image.template", "digest": "sha256:cccc", "size": 20 },
+                            ],
+                        })
+                        .to_string(),
+                    ),
+                "/api/blobs/sha256:cccc" => http::Response::builder().status(404).body(String::new()),
+                _ => http::Response::builder().status(200).body(String::new()),
+            };
+            Box::pin(async move { Ok(res.unwrap().into()) })
+        }
+    }
+
+    let registry = Registry::default();
+    let ollama = Ollama::default().with_transport(registry.clone());
+
+    let report = ollama
+        .verify_pulled_model("llama2".into(), false)
+        .await
+        .unwrap();
+
+    let layers = report
+        .layers
+        .iter()
+        .map(|l| (l.digest.as_str(), l.size, l.present))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        layers,
+        [
+            ("sha256:aaaa", 10, true),
+            ("sha256:bbbb", 100, true),
+            ("sha256:cccc", 20, false),
+        ]
+    );
+    assert!(!report.is_ok());
+    assert_eq!(
+        report.missing().map(|l| &l.media_type).collect::<Vec<_>>(),
+        ["application/vnd.ollama.image.template"]
+    );
+    assert_eq!(
+        registry.0.lock().unwrap()[0],
+        "https://registry.ollama.ai/v2/library/llama2/manifests/latest"
+    );
+}
+
+#[tokio::test]
+async fn test_pull_model_resumable() {
+    let ollama = Ollama::default();
+
+    let mut res = ollama.pull_model_resumable("llama2:latest".into(), false, 3);
+
+    let mut last = None;
+    while let Some(progress) = res.next().await {
+        let progress = progress.unwrap();
+        dbg!(progress.phase(), progress.percent());
+        last = Some(progress);
+    }
+
+    assert_eq!(last.unwrap().phase(), &PullPhase::Success);
+}
+
+#[tokio::test]
+async fn test_pull_model_resumable_missing_model() {
+    let ollama = Ollama::default();
+
+    let mut res = ollama.pull_model_resumable("missing-model-ollama-rs:latest".into(), false, 5);
+
+    let mut error = None;
+    while let Some(progress) = res.next().await {
+        if let Err(e) = progress {
+            error = Some(e);
+        }
+    }
+
+    dbg!(&error);
+    assert!(error.is_some());
+}
+
+#[test]
+fn test_registry_credentials_debug() {
+    use ollama_rs::models::registry::RegistryCredentials;
+
+    let credentials = RegistryCredentials::Basic {
+        username: "user".into(),
+        password: "secret-password".into(),
+    };
+    let debug = format!("{credentials:?}");
+    assert!(debug.contains("user"));
+    assert!(!debug.contains("secret-password"));
+
+    let mut ollama = Ollama::default();
+    ollama.set_registry_credentials(Some(RegistryCredentials::Bearer("secret-token".into())));
+    assert!(!format!("{ollama:?}").contains("secret-token"));
+}
+
+#[tokio::test]
+async fn test_pull_model_resumable_retryable_errors() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use ollama_rs::{
+        error::OllamaError,
+        stub::{StubResponse, StubTransport},
+    };
+    use serde_json::json;
+
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let stub = StubTransport::new().respond_with("api/pull", {
+        let attempts = attempts.clone();
+        move |_| {
+            let error = match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => "max retries exceeded: unexpected EOF",
+                _ => "pull model manifest: file does not exist",
+            };
+            StubResponse::stream([
+                json!({ "status": "pulling manifest" }),
+                json!({ "error": error }),
+            ])
+        }
+    });
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let mut res = ollama.pull_model_resumable("llama2:latest".into(), false, 5);
+
+    let mut error = None;
+    while let Some(progress) = res.next().await {
+        if let Err(e) = progress {
+            error = Some(e);
+        }
+    }
+
+    // The failed download is retried, the missing model isn't
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert!(matches!(error, Some(OllamaError::ModelNotFound(_))));
+}
+
+#[tokio::test]
+async fn test_pull_models() {
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    let stub = StubTransport::new().respond_with("api/pull", |request| {
+        let digest = format!("sha256:{}", request["model"].as_str().unwrap_or_default());
+        StubResponse::stream([
+            json!({ "status": "pulling manifest" }),
+            json!({ "status": "downloading", "digest": digest, "total": 100, "completed": 50 }),
+            json!({ "status": "downloading", "digest": digest, "total": 100, "completed": 100 }),
+            json!({ "status": "success" }),
+        ])
+    });
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    // A single pull at a time, the next model is pulled once the previous one finished
+    let mut res = ollama.pull_models(
+        vec![
+            "llama2:latest".into(),
+            "nomic-embed-text:latest".into(),
+            "llama2:latest".into(),
+            "llava:latest".into(),
+        ],
+        false,
+        1,
+    );
+
+    let mut order: Vec<String> = vec![];
+    let mut last = std::collections::HashMap::new();
+    while let Some((model_name, progress)) = res.next().await {
+        let progress = progress.unwrap();
+        if order.last() != Some(&model_name) {
+            assert!(!order.contains(&model_name));
+            order.push(model_name.clone());
+        }
+        last.insert(model_name, progress);
+    }
+
+    assert_eq!(
+        order,
+        ["llama2:latest", "nomic-embed-text:latest", "llava:latest"]
+    );
+    assert!(last.values().all(|p| p.phase() == &PullPhase::Success));
+}
@@ -1,4 +1,8 @@
-use ollama_rs::Ollama;
+use ollama_rs::{
+    error::OllamaError,
+    models::push::{PushEvent, PushModelStatus},
+    Ollama,
+};
 use tokio_stream::StreamExt;
 
 #[tokio::test]
@@ -13,8 +17,51 @@ async fn test_push_model() {
 
     while let Some(res) = res.next().await {
         match res {
-            Ok(res) => println!("{res:?}"),
+            Ok(res) => println!("{:?}", res.event()),
             Err(e) => panic!("{e:?}"),
         }
     }
 }
+
+#[tokio::test]
+/// This test needs a local model named `test_model:latest`, pushed to a namespace we don't own
+async fn test_push_model_permission_denied() {
+    let ollama = Ollama::default();
+
+    let res = ollama
+        .push_model("library/test_model:latest".into(), false)
+        .await;
+
+    assert!(matches!(
+        res,
+        Err(OllamaError::RegistryUnauthorized(_) | OllamaError::RegistryPermissionDenied(_))
+    ));
+}
+
+#[test]
+fn test_push_events() {
+    let status = |json: serde_json::Value| serde_json::from_value::<PushModelStatus>(json).unwrap();
+
+    assert_eq!(
+        status(serde_json::json!({ "status": "retrieving manifest" })).event(),
+        PushEvent::RetrievingManifest
+    );
+    assert_eq!(
+        status(serde_json::json!({
+            "status": "pushing bc07c81de745",
+            "digest": "sha256:bc07c81de745",
+            "total": 1928429856,
+            "completed": 1024
+        }))
+        .event(),
+        PushEvent::Uploading {
+            digest: "sha256:bc07c81de745".to_string(),
+            completed: 1024,
+            total: 1928429856
+        }
+    );
+    assert_eq!(
+        status(serde_json::json!({ "status": "success" })).event(),
+        PushEvent::Success
+    );
+}
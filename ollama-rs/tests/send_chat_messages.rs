@@ -1,11 +1,15 @@
 use base64::Engine;
-use std::sync::{Arc, Mutex};
+use serde::Deserialize;
 use tokio_stream::StreamExt;
 
 use ollama_rs::{
     generation::{
-        chat::{request::ChatMessageRequest, ChatMessage},
-        images::Image,
+        chat::{
+            content::ContentPart, few_shot::FewShot, request::ChatMessageRequest, ChatMessage,
+            ChatMessageResponseStreamExt, MessageRole,
+        },
+        images::{Image, ImageHints},
+        parameters::{FormatType, JsonSchema, JsonStructure, KeepAlive, TimeUnit},
     },
     Ollama,
 };
@@ -54,12 +58,16 @@ async fn test_send_chat_messages() {
     dbg!(&res);
 
     assert!(res.done);
+
+    let usage = res.usage().unwrap();
+    assert!(usage.eval_count > 0);
+    assert!(usage.tokens_per_second() > 0.0);
 }
 
 #[tokio::test]
 async fn test_send_chat_messages_with_history_stream() {
     let ollama = Ollama::default();
-    let history = Arc::new(Mutex::new(vec![]));
+    let mut history = vec![];
 
     let messages = vec![ChatMessage::user(PROMPT.to_string())];
 
@@ -67,7 +75,7 @@ async fn test_send_chat_messages_with_history_stream() {
 
     let mut res = ollama
         .send_chat_messages_with_history_stream(
-            history.clone(),
+            &mut history,
             ChatMessageRequest::new("llama2:latest".to_string(), messages),
         )
         .await
@@ -78,19 +86,19 @@ async fn test_send_chat_messages_with_history_stream() {
 
         if res.done {
             done = true;
-            break;
         }
     }
+    drop(res);
 
     assert!(done);
     // Should have user's message as well as AI's response
-    dbg!(&history.lock().unwrap());
-    assert_eq!(history.lock().unwrap().len(), 2);
+    dbg!(&history);
+    assert_eq!(history.len(), 2);
 }
 
 #[tokio::test]
 async fn test_send_chat_messages_with_history() {
-    let mut ollama = Ollama::default();
+    let ollama = Ollama::default();
     let mut history = vec![];
     let second_message = vec![ChatMessage::user("Second message".to_string())];
 
@@ -159,3 +167,515 @@ async fn test_send_chat_messages_with_images() {
 
     assert!(res.done);
 }
+
+#[tokio::test]
+async fn test_send_chat_messages_with_image_bytes() {
+    let ollama = Ollama::default();
+
+    let bytes = reqwest::get(IMAGE_URL)
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+
+    let messages = vec![
+        ChatMessage::user("What can we see in this image?".to_string())
+            .with_image_bytes(&bytes)
+            .unwrap(),
+    ];
+    let res = ollama
+        .send_chat_messages(ChatMessageRequest::new(
+            "llava:latest".to_string(),
+            messages,
+        ))
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(res.done);
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_with_prefill() {
+    let ollama = Ollama::default();
+
+    let messages = vec![
+        ChatMessage::user("Give me a JSON object describing the sky.".to_string()),
+        ChatMessage::assistant_prefill("{\"color\": \"".to_string()),
+    ];
+    let res = ollama
+        .send_chat_messages(ChatMessageRequest::new(
+            "llama2:latest".to_string(),
+            messages,
+        ))
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(res.done);
+    assert!(!res.message.content.starts_with("{\"color\""));
+}
+
+#[derive(JsonSchema, Deserialize, Debug)]
+struct Country {
+    name: String,
+    capital: String,
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_structured() {
+    let ollama = Ollama::default();
+
+    let messages = vec![ChatMessage::user(
+        "Tell me about the country north of the USA".to_string(),
+    )];
+    let res = ollama
+        .send_chat_messages_structured::<Country>(ChatMessageRequest::new(
+            "llama3.2:latest".to_string(),
+            messages,
+        ))
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(!res.name.is_empty());
+    assert!(!res.capital.is_empty());
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_stream_accumulate() {
+    let ollama = Ollama::default();
+
+    let messages = vec![ChatMessage::user(PROMPT.to_string())];
+    let res = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama2:latest".to_string(),
+            messages,
+        ))
+        .await
+        .unwrap()
+        .accumulate()
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(res.done);
+    assert!(res.final_data.is_some());
+    assert!(!res.message.content.is_empty());
+}
+
+#[test]
+fn test_custom_message_role_round_trip() {
+    let message = ChatMessage::new(MessageRole::custom("ipython"), "print(1)".to_string());
+
+    let json = serde_json::to_value(&message).unwrap();
+    assert_eq!(json["role"], "ipython");
+
+    let back: ChatMessage = serde_json::from_value(json).unwrap();
+    assert_eq!(back.role, MessageRole::Custom("ipython".to_string()));
+    assert_eq!(MessageRole::custom("user"), MessageRole::User);
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_with_thinking() {
+    let ollama = Ollama::default();
+
+    let messages = vec![ChatMessage::user(PROMPT.to_string())];
+    let res = ollama
+        .send_chat_messages(
+            ChatMessageRequest::new("qwen3:latest".to_string(), messages).think(true),
+        )
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(res.done);
+    assert!(res.message.thinking.is_some());
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_with_multiple_images() {
+    let ollama = Ollama::default();
+
+    let bytes = reqwest::get(IMAGE_URL)
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    let base64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+
+    let messages = vec![
+        ChatMessage::user("Are these two images the same?".to_string())
+            .add_image_with_hints(
+                Image::from_base64(&base64),
+                ImageHints::default().label("first"),
+            )
+            .add_image_with_hints(
+                Image::from_base64(&base64),
+                ImageHints::default().label("second").downscale_to(336),
+            ),
+    ];
+    let res = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llava:latest".to_string(),
+            messages,
+        ))
+        .await
+        .unwrap()
+        .accumulate()
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(res.done);
+}
+
+#[test]
+fn test_image_hints_are_not_serialized() {
+    let message = ChatMessage::user("Describe".to_string()).add_images([
+        Image::from_base64("aGVsbG8="),
+        Image::from_base64("d29ybGQ=").with_hints(ImageHints::default().downscale_to(512)),
+    ]);
+
+    let json = serde_json::to_value(&message).unwrap();
+    assert_eq!(json["images"], serde_json::json!(["aGVsbG8=", "d29ybGQ="]));
+}
+
+#[test]
+fn test_chat_message_request_round_trip() {
+    let request = ChatMessageRequest::new(
+        "llama3.2:latest".to_string(),
+        vec![ChatMessage::user(PROMPT.to_string())],
+    )
+    .format(FormatType::StructuredJson(Box::new(JsonStructure::new::<
+        Country,
+    >())))
+    .keep_alive(KeepAlive::Until {
+        time: 5,
+        unit: TimeUnit::Minutes,
+    })
+    .think(true);
+
+    // Requests can be queued as JSON and executed later
+    let queued = serde_json::to_string(&request).unwrap();
+    let restored: ChatMessageRequest = serde_json::from_str(&queued).unwrap();
+
+    assert_eq!(serde_json::to_string(&restored).unwrap(), queued);
+}
+
+#[test]
+fn test_chat_message_with_content_parts() {
+    let message = ChatMessage::from_content(
+        MessageRole::User,
+        vec![
+            ContentPart::from("Compare this image"),
+            ContentPart::from(Image::from_base64("aGVsbG8=")),
+            ContentPart::from("with this one"),
+            ContentPart::from(Image::from_base64("d29ybGQ=")),
+        ],
+    );
+
+    assert_eq!(message.content, "Compare this image\nwith this one");
+    assert_eq!(message.images.as_ref().map(Vec::len), Some(2));
+    assert_eq!(message.content_parts().into_parts().len(), 3);
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_with_callback() {
+    let ollama = Ollama::default();
+
+    let messages = vec![ChatMessage::user(PROMPT.to_string())];
+    let mut streamed = String::new();
+    let res = ollama
+        .send_chat_messages_with_callback(
+            ChatMessageRequest::new("llama2:latest".to_string(), messages),
+            |delta| streamed.push_str(delta),
+        )
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(res.done);
+    assert_eq!(streamed, res.message.content);
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_idempotent_retry() {
+    let mut ollama = Ollama::default();
+    ollama.set_idempotency_ttl(Some(std::time::Duration::from_secs(60)));
+
+    let request = ChatMessageRequest::new(
+        "llama2:latest".to_string(),
+        vec![ChatMessage::user(PROMPT.to_string())],
+    )
+    .with_fingerprint();
+
+    let first = ollama.send_chat_messages(request.clone()).await.unwrap();
+    // A retry of the same request returns the cached response instead of regenerating
+    let retry = ollama.send_chat_messages(request).await.unwrap();
+
+    assert_eq!(first.message.content, retry.message.content);
+    assert_eq!(first.created_at, retry.created_at);
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_idempotency_key_client_side() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let stub = StubTransport::new().respond_with("api/chat", {
+        let calls = calls.clone();
+        move |request| {
+            assert!(request.get("idempotency_key").is_none());
+            assert!(request.get("keep_thinking").is_none());
+            calls.fetch_add(1, Ordering::SeqCst);
+            StubResponse::json(json!({
+                "model": "llama2:latest",
+                "created_at": "2024-01-01T00:00:00Z",
+                "message": { "role": "assistant", "content": "Rayleigh scattering" },
+                "done": true,
+            }))
+        }
+    });
+    let mut ollama = Ollama::builder().stub(stub).build().unwrap();
+    ollama.set_idempotency_ttl(Some(std::time::Duration::from_secs(60)));
+
+    let request = ChatMessageRequest::new(
+        "llama2:latest".to_string(),
+        vec![ChatMessage::user(PROMPT.to_string())],
+    )
+    .idempotency_key("request-1")
+    .keep_thinking_in_history(true);
+
+    ollama.send_chat_messages(request.clone()).await.unwrap();
+    ollama.send_chat_messages(request.clone()).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // The key isn't serialized, a request read back from JSON is sent again
+    let json = serde_json::to_string(&request).unwrap();
+    let request = serde_json::from_str::<ChatMessageRequest>(&json).unwrap();
+    ollama.send_chat_messages(request).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_coordinator_checks_tool_capability() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use ollama_rs::{
+        coordinator::Coordinator,
+        error::OllamaError,
+        generation::tools::implementations::Calculator,
+        stub::{StubResponse, StubTransport},
+    };
+    use serde_json::json;
+
+    let client = |capabilities: serde_json::Value, shows: Arc<AtomicUsize>| {
+        let stub = StubTransport::new()
+            .respond_with("api/show", move |_| {
+                shows.fetch_add(1, Ordering::SeqCst);
+                StubResponse::json(json!({ "capabilities": capabilities }))
+            })
+            .respond(
+                "api/chat",
+                StubResponse::json(json!({
+                    "model": "llama2:latest",
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "message": { "role": "assistant", "content": "4" },
+                    "done": true,
+                })),
+            );
+        Ollama::builder().stub(stub).build().unwrap()
+    };
+
+    let shows = Arc::new(AtomicUsize::new(0));
+    let ollama = client(json!(["completion"]), shows.clone());
+    let mut coordinator =
+        Coordinator::new(ollama, "llama2:latest".to_string(), vec![]).add_tool(Calculator {});
+    let res = coordinator
+        .chat(vec![ChatMessage::user("What is 2 + 2?".to_string())])
+        .await;
+    assert!(matches!(
+        res,
+        Err(OllamaError::MissingCapability { capability, .. }) if capability == "tools"
+    ));
+
+    // The check runs once, on the first turn
+    let shows = Arc::new(AtomicUsize::new(0));
+    let ollama = client(json!(["completion", "tools"]), shows.clone());
+    let mut coordinator =
+        Coordinator::new(ollama, "llama2:latest".to_string(), vec![]).add_tool(Calculator {});
+    for _ in 0..2 {
+        coordinator
+            .chat(vec![ChatMessage::user("What is 2 + 2?".to_string())])
+            .await
+            .unwrap();
+    }
+    assert_eq!(shows.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_few_shot() {
+    let ollama = Ollama::default();
+
+    let few_shot = FewShot::new()
+        .system("Classify the sentiment of the message. Answer with one word.")
+        .example("I love it!", "positive")
+        .example("This is terrible.", "negative");
+
+    let res = ollama
+        .send_chat_messages(few_shot.request("llama2:latest".to_string(), "What a great day"))
+        .await
+        .unwrap();
+    dbg!(&res);
+
+    assert!(res.done);
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_with_history_thinking() {
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    let chunk = |thinking: &str, content: &str, done: bool| {
+        json!({
+            "model": "qwen3:latest",
+            "created_at": "2024-01-01T00:00:00Z",
+            "message": { "role": "assistant", "content": content, "thinking": thinking },
+            "done": done,
+        })
+    };
+    let stub = StubTransport::new().respond(
+        "api/chat",
+        StubResponse::stream([
+            chunk("Rayleigh", "", false),
+            chunk(" scattering", "", false),
+            chunk("", "Because of", false),
+            chunk("", " the air.", false),
+            chunk("", "", true),
+        ]),
+    );
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    for keep_thinking in [false, true] {
+        let mut history = vec![];
+        let request = ChatMessageRequest::new(
+            "qwen3:latest".to_string(),
+            vec![ChatMessage::user(PROMPT.to_string())],
+        )
+        .think(true)
+        .keep_thinking_in_history(keep_thinking);
+
+        let mut stream = ollama
+            .send_chat_messages_with_history_stream(&mut history, request)
+            .await
+            .unwrap();
+        while let Some(res) = stream.next().await {
+            res.unwrap();
+        }
+        drop(stream);
+
+        // The thinking is dropped from the history unless it is kept explicitly
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content, "Because of the air.");
+        assert_eq!(
+            history[1].thinking.as_deref(),
+            keep_thinking.then_some("Rayleigh scattering")
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_send_chat_messages_with_history_stream_tool_calls() {
+    use std::sync::Arc;
+
+    use ollama_rs::stub::{StubResponse, StubTransport};
+    use serde_json::json;
+
+    let stub = StubTransport::new().respond(
+        "api/chat",
+        StubResponse::stream([
+            json!({
+                "model": "qwen3:latest",
+                "created_at": "2024-01-01T00:00:00Z",
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "function": { "name": "get_weather", "arguments": { "city": "Paris" } },
+                    }],
+                },
+                "done": false,
+            }),
+            json!({
+                "model": "qwen3:latest",
+                "created_at": "2024-01-01T00:00:00Z",
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{
+                        "id": "call_2",
+                        "function": { "name": "get_weather", "arguments": { "city": "Rome" } },
+                    }],
+                },
+                "done": true,
+            }),
+        ]),
+    );
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let request = || {
+        ChatMessageRequest::new(
+            "qwen3:latest".to_string(),
+            vec![ChatMessage::user("What's the weather?".to_string())],
+        )
+    };
+    let tool_call_ids = |message: &ChatMessage| {
+        message
+            .tool_calls
+            .iter()
+            .map(|call| call.id.clone().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let mut history = vec![];
+    let mut stream = ollama
+        .send_chat_messages_with_history_stream(&mut history, request())
+        .await
+        .unwrap();
+    while let Some(res) = stream.next().await {
+        res.unwrap();
+    }
+    drop(stream);
+
+    assert_eq!(history.len(), 2);
+    assert_eq!(tool_call_ids(&history[1]), ["call_1", "call_2"]);
+    assert_eq!(
+        history[1].tool_calls[0].function.arguments,
+        json!({ "city": "Paris" })
+    );
+
+    let history = Arc::new(tokio::sync::Mutex::new(vec![]));
+    let mut stream = ollama
+        .send_chat_messages_with_history_stream_tokio(history.clone(), request())
+        .await
+        .unwrap();
+    while let Some(res) = stream.next().await {
+        res.unwrap();
+    }
+
+    let history = history.lock().await;
+    assert_eq!(tool_call_ids(&history[1]), ["call_1", "call_2"]);
+}
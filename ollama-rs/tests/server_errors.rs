@@ -0,0 +1,182 @@
+use ollama_rs::{
+    error::OllamaError,
+    generation::completion::request::GenerationRequest,
+    stub::{StubResponse, StubTransport},
+    Ollama,
+};
+use serde_json::json;
+
+/// A client whose generations fail with `message`
+fn failing_client(status: u16, message: &'static str) -> Ollama {
+    let stub = StubTransport::new()
+        .respond("api/generate", StubResponse::error(status, message))
+        .respond(
+            "v1/chat/completions",
+            StubResponse::json(json!({
+                "error": { "message": message, "type": "api_error" }
+            }))
+            .status(status),
+        );
+    Ollama::builder().stub(stub).build().unwrap()
+}
+
+async fn generate(ollama: &Ollama) -> OllamaError {
+    ollama
+        .generate(GenerationRequest::new("llama3.2".to_string(), "Hello"))
+        .await
+        .unwrap_err()
+}
+
+#[tokio::test]
+async fn test_server_error_variants() {
+    let ollama = failing_client(404, "model \"llama3.2\" not found, try pulling it first");
+    assert!(matches!(
+        generate(&ollama).await,
+        OllamaError::ModelNotFound(message) if message.starts_with("model \"llama3.2\"")
+    ));
+
+    let ollama = failing_client(
+        500,
+        "model requires more system memory (10.2 GiB) than is available (4.0 GiB)",
+    );
+    assert!(matches!(
+        generate(&ollama).await,
+        OllamaError::OutOfMemory(_)
+    ));
+
+    let ollama = failing_client(400, "\"llama3.2\" does not support thinking");
+    assert!(matches!(
+        generate(&ollama).await,
+        OllamaError::UnsupportedOption(_)
+    ));
+
+    let ollama = failing_client(400, "the input length exceeds the context length");
+    assert!(matches!(
+        generate(&ollama).await,
+        OllamaError::ContextLengthExceeded(_)
+    ));
+
+    // The errors that aren't recognized keep their message
+    let ollama = failing_client(500, "something went wrong");
+    assert!(matches!(
+        generate(&ollama).await,
+        OllamaError::Other(message) if message == "something went wrong"
+    ));
+}
+
+#[tokio::test]
+async fn test_server_error_variants_mid_stream() {
+    use ollama_rs::generation::chat::{request::ChatMessageRequest, ChatMessage};
+    use tokio_stream::StreamExt;
+
+    let error =
+        json!({ "error": "model requires more system memory (10.2 GiB) than is available" });
+    let stub = StubTransport::new()
+        .respond(
+            "api/generate",
+            StubResponse::stream([
+                json!({ "model": "llama3.2", "created_at": "2024-01-01T00:00:00Z", "response": "Hi", "done": false }),
+                error.clone(),
+            ]),
+        )
+        .respond(
+            "api/chat",
+            StubResponse::stream([error.clone()]),
+        )
+        .respond(
+            "api/pull",
+            StubResponse::stream([
+                json!({ "status": "pulling manifest" }),
+                json!({ "error": "pull model manifest: file does not exist" }),
+            ]),
+        )
+        .respond(
+            "api/push",
+            StubResponse::stream([json!({ "error": "something went wrong" })]),
+        );
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let mut stream = ollama
+        .generate_stream(GenerationRequest::new("llama3.2".to_string(), "Hello"))
+        .await
+        .unwrap();
+    let mut errors = vec![];
+    while let Some(res) = stream.next().await {
+        if let Err(e) = res {
+            errors.push(e);
+        }
+    }
+    assert!(matches!(errors.as_slice(), [OllamaError::OutOfMemory(_)]));
+
+    let mut stream = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama3.2".to_string(),
+            vec![ChatMessage::user("Hello".to_string())],
+        ))
+        .await
+        .unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(OllamaError::OutOfMemory(_)))
+    ));
+
+    let mut stream = ollama
+        .pull_model_stream("llama3.2".to_string(), false)
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_ok());
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(OllamaError::ModelNotFound(_)))
+    ));
+
+    let mut stream = ollama
+        .push_model_stream("user/llama3.2".to_string(), false)
+        .await
+        .unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(OllamaError::Other(message))) if message == "something went wrong"
+    ));
+}
+
+#[tokio::test]
+async fn test_copy_model_safe_missing_source() {
+    let stub =
+        StubTransport::new().respond("api/tags", StubResponse::json(json!({ "models": [] })));
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let res = ollama
+        .copy_model_safe("llama3.2".to_string(), "llama3.2-copy".to_string(), false)
+        .await;
+    assert!(matches!(res, Err(OllamaError::ModelNotFound(name)) if name == "llama3.2"));
+}
+
+#[tokio::test]
+async fn test_server_error_variants_openai() {
+    use ollama_rs::openai::chat::{
+        ChatCompletionMessage, ChatCompletionRequest, ChatCompletionRole,
+    };
+
+    let ollama = failing_client(404, "model \"llama3.2\" not found, try pulling it first");
+    let res = ollama
+        .openai_chat_completion(ChatCompletionRequest::new(
+            "llama3.2".to_string(),
+            vec![ChatCompletionMessage::new(
+                ChatCompletionRole::User,
+                "Hello".to_string(),
+            )],
+        ))
+        .await;
+    assert!(matches!(res, Err(OllamaError::ModelNotFound(_))));
+}
+
+#[test]
+fn test_server_error_inner() {
+    let error = OllamaError::WithRequestId {
+        request_id: "abc".to_string(),
+        source: Box::new(OllamaError::OutOfMemory("cudaMalloc failed".to_string())),
+    };
+    assert_eq!(error.request_id(), Some("abc"));
+    assert!(matches!(error.inner(), OllamaError::OutOfMemory(_)));
+}
@@ -34,3 +34,176 @@ async fn test_model_info_modelfile_param_stop() {
 
     assert_eq!(stop, "[INST]");
 }
+
+#[tokio::test]
+async fn test_show_model_info_verbose() {
+    let ollama = ollama_rs::Ollama::default();
+
+    let model_info = ollama
+        .show_model_info_verbose("llama2:latest".to_string())
+        .await
+        .unwrap();
+
+    let metadata = model_info.metadata();
+    dbg!(&metadata);
+
+    assert_eq!(metadata.architecture.as_deref(), Some("llama"));
+    assert_eq!(metadata.context_length, Some(4096));
+    assert_eq!(metadata.embedding_length, Some(4096));
+    assert!(metadata.quantization_level.is_some());
+    assert!(model_info
+        .info("tokenizer.ggml.tokens")
+        .and_then(|t| t.as_array())
+        .is_some_and(|t| !t.is_empty()));
+}
+
+#[tokio::test]
+async fn test_model_capabilities() {
+    let ollama = ollama_rs::Ollama::default();
+
+    let capabilities = ollama
+        .model_capabilities("llama3.2:latest".to_string())
+        .await
+        .unwrap();
+    dbg!(&capabilities);
+
+    assert!(capabilities.supports_tools());
+    assert!(!capabilities.is_embedding_model());
+
+    let capabilities = ollama
+        .model_capabilities("nomic-embed-text:latest".to_string())
+        .await
+        .unwrap();
+
+    assert!(capabilities.is_embedding_model());
+    assert!(ollama
+        .require_tools("nomic-embed-text:latest".to_string())
+        .await
+        .is_err());
+}
+
+#[cfg(not(feature = "modelfile"))]
+#[test]
+fn test_model_capabilities_inference() {
+    use ollama_rs::models::{capabilities::ModelCapabilities, ModelInfo};
+
+    let info: ModelInfo = serde_json::from_value(serde_json::json!({
+        "template": "{{ if .Tools }}{{ .Tools }}{{ end }}{{ .Prompt }}",
+        "details": { "families": ["llama", "clip"] },
+        "model_info": { "general.architecture": "llama" }
+    }))
+    .unwrap();
+    let capabilities = ModelCapabilities::from(&info);
+
+    assert!(capabilities.completion);
+    assert!(capabilities.supports_tools());
+    assert!(capabilities.supports_vision());
+    assert!(!capabilities.is_embedding_model());
+
+    let info: ModelInfo = serde_json::from_value(serde_json::json!({
+        "capabilities": ["embedding"]
+    }))
+    .unwrap();
+
+    assert!(ModelCapabilities::from(&info).is_embedding_model());
+}
+
+#[cfg(not(feature = "modelfile"))]
+#[test]
+fn test_model_info_options() {
+    use ollama_rs::models::ModelInfo;
+
+    let info: ModelInfo = serde_json::from_value(serde_json::json!({
+        "parameters": "num_ctx                        4096\nstop                           \"[INST]\"\nstop                           \"[/INST]\"\ntemperature                    0.7\nunknown                        1"
+    }))
+    .unwrap();
+
+    assert_eq!(info.stop_sequences(), vec!["[INST]", "[/INST]"]);
+
+    let options = serde_json::to_value(info.options()).unwrap();
+    assert_eq!(
+        options,
+        serde_json::json!({
+            "num_ctx": 4096,
+            "stop": ["[INST]", "[/INST]"],
+            "temperature": 0.7f32,
+        })
+    );
+}
+
+#[cfg(not(feature = "modelfile"))]
+#[test]
+fn test_model_info_modelfile_components() {
+    use ollama_rs::models::ModelInfo;
+
+    let info: ModelInfo = serde_json::from_value(serde_json::json!({
+        "modelfile": "# Modelfile generated by \"ollama show\"\nFROM /root/.ollama/models/blobs/sha256-8934d96d\nTEMPLATE \"\"\"{{ .Prompt }}\"\"\"\nSYSTEM You are Mario.\nADAPTER /root/.ollama/models/blobs/sha256-1a2b3c4d\n",
+        "template": "{{ .Prompt }}",
+        "system": "You are Mario.",
+        "license": "MIT"
+    }))
+    .unwrap();
+
+    assert_eq!(
+        info.base_model().as_deref(),
+        Some("/root/.ollama/models/blobs/sha256-8934d96d")
+    );
+    assert_eq!(
+        info.adapters(),
+        vec!["/root/.ollama/models/blobs/sha256-1a2b3c4d"]
+    );
+    assert_eq!(info.system, "You are Mario.");
+    assert_eq!(info.template, "{{ .Prompt }}");
+    assert_eq!(info.license, "MIT");
+}
+
+#[tokio::test]
+async fn test_cached_model_info() {
+    let mut ollama = ollama_rs::Ollama::default();
+    ollama.set_model_cache_ttl(Some(std::time::Duration::from_secs(60)));
+
+    let info = ollama
+        .cached_model_info("llama2:latest".to_string())
+        .await
+        .unwrap();
+    let cache = ollama.model_cache().unwrap();
+    assert!(cache.model_info("llama2:latest").is_some());
+    assert_eq!(
+        cache.model_info("llama2:latest").unwrap().template,
+        info.template
+    );
+
+    ollama.cached_local_models().await.unwrap();
+    assert!(cache.local_models().is_some());
+
+    // Changing the local models invalidates the cache
+    ollama
+        .copy_model("llama2:latest".into(), "llama2_cache_copy".into())
+        .await
+        .unwrap();
+    assert!(cache.model_info("llama2:latest").is_none());
+    assert!(cache.local_models().is_none());
+
+    ollama
+        .delete_model("llama2_cache_copy".into())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_compare_models() {
+    let ollama = ollama_rs::Ollama::default();
+
+    let comparison = ollama
+        .compare_models(
+            "llama2:latest".to_string(),
+            "llama2:7b-chat-q5_K_M".to_string(),
+        )
+        .await
+        .unwrap();
+    dbg!(&comparison);
+
+    assert!(comparison.quantization_level.is_different());
+    assert!(!comparison.context_length.is_different());
+    assert!(comparison.differences().contains(&"quantization_level"));
+}
@@ -0,0 +1,132 @@
+use ollama_rs::{
+    generation::{
+        chat::{request::ChatMessageRequest, ChatMessage},
+        completion::request::GenerationRequest,
+        embeddings::request::{EmbeddingsInput, GenerateEmbeddingsRequest},
+    },
+    stub::{StubResponse, StubTransport},
+    Ollama,
+};
+use serde_json::json;
+use tokio_stream::StreamExt;
+
+fn stub_client(stub: StubTransport) -> Ollama {
+    // Nothing listens on the port, the stub answers every request
+    Ollama::builder()
+        .url("http://127.0.0.1:9")
+        .stub(stub)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_stub_generation() {
+    let ollama = stub_client(StubTransport::new().reply("You asked: {prompt}"));
+
+    let res = ollama
+        .generate(GenerationRequest::new(
+            "llama2:latest".to_string(),
+            "Why is the sky blue?",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.model, "llama2:latest");
+    assert_eq!(res.response, "You asked: Why is the sky blue?");
+
+    let mut stream = ollama
+        .generate_stream(GenerationRequest::new(
+            "llama2:latest".to_string(),
+            "Why is the sky blue?",
+        ))
+        .await
+        .unwrap();
+    let mut response = String::new();
+    let mut chunks = 0;
+    while let Some(res) = stream.next().await {
+        for res in res.unwrap() {
+            response += &res.response;
+            chunks += 1;
+        }
+    }
+    assert_eq!(response, "You asked: Why is the sky blue?");
+    assert!(chunks > 1);
+
+    assert!(ollama.health().await.is_healthy());
+    assert_eq!(ollama.version().await.unwrap(), "0.9.0");
+    assert!(ollama.list_local_models().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_stub_chat() {
+    let ollama = stub_client(StubTransport::new());
+
+    let mut stream = ollama
+        .send_chat_messages_stream(ChatMessageRequest::new(
+            "llama2:latest".to_string(),
+            vec![ChatMessage::user("Hello!".to_string())],
+        ))
+        .await
+        .unwrap();
+    let mut response = String::new();
+    let mut done = false;
+    while let Some(res) = stream.next().await {
+        let res = res.unwrap();
+        response += &res.message.content;
+        done = res.done;
+    }
+    assert_eq!(response, "This is a stub response to: Hello!");
+    assert!(done);
+}
+
+#[tokio::test]
+async fn test_stub_embeddings() {
+    let ollama = stub_client(StubTransport::new().embedding_dimensions(8));
+
+    let res = ollama
+        .generate_embeddings(GenerateEmbeddingsRequest::new(
+            "nomic-embed-text:latest".to_string(),
+            EmbeddingsInput::Multiple(vec!["a".to_string(), "b".to_string(), "a".to_string()]),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.embeddings.len(), 3);
+    assert_eq!(res.embeddings[0].len(), 8);
+    assert_eq!(res.embeddings[0], res.embeddings[2]);
+    assert_ne!(res.embeddings[0], res.embeddings[1]);
+}
+
+#[tokio::test]
+async fn test_stub_responses() {
+    let ollama = stub_client(
+        StubTransport::new()
+            .respond(
+                "api/tags",
+                StubResponse::json(json!({
+                    "models": [{
+                        "name": "llama2:latest",
+                        "modified_at": "2024-01-01T00:00:00Z",
+                        "size": 3825819519u64,
+                    }]
+                })),
+            )
+            .respond_with("api/generate", |request| {
+                let model = request["model"].as_str().unwrap_or_default();
+                StubResponse::error(404, format!("model '{model}' not found"))
+            }),
+    );
+
+    let models = ollama.list_local_models().await.unwrap();
+    assert_eq!(models[0].name, "llama2:latest");
+
+    let res = ollama
+        .generate(GenerationRequest::new("missing".to_string(), "Hello"))
+        .await;
+    dbg!(&res);
+    assert!(res.unwrap_err().to_string().contains("not found"));
+
+    // The endpoints without a response are not found
+    assert!(ollama
+        .show_model_info("llama2:latest".to_string())
+        .await
+        .is_err());
+}
@@ -0,0 +1,71 @@
+use ollama_rs::{version::ServerVersion, Ollama};
+
+#[tokio::test]
+async fn test_version() {
+    let ollama = Ollama::default();
+
+    let version = ollama.version().await.unwrap();
+    dbg!(&version);
+
+    let parsed = ollama.server_version().await.unwrap();
+    assert_eq!(parsed, version.parse().unwrap());
+}
+
+#[test]
+fn test_parse_server_version() {
+    assert_eq!(
+        "0.5.7".parse::<ServerVersion>().unwrap(),
+        ServerVersion::new(0, 5, 7)
+    );
+    assert_eq!(
+        "v0.6.0-rc1".parse::<ServerVersion>().unwrap(),
+        ServerVersion::new(0, 6, 0)
+    );
+    assert!("latest".parse::<ServerVersion>().is_err());
+    assert!(ServerVersion::new(0, 4, 9) < ServerVersion::new(0, 5, 0));
+    assert_eq!(ServerVersion::new(0, 9, 0).to_string(), "0.9.0");
+}
+
+#[tokio::test]
+async fn test_require_version_failure_cached() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use ollama_rs::{
+        generation::chat::{request::ChatMessageRequest, ChatMessage},
+        stub::{StubResponse, StubTransport},
+    };
+    use serde_json::json;
+
+    let version_calls = Arc::new(AtomicUsize::new(0));
+    let stub = StubTransport::new()
+        .respond_with("api/version", {
+            let version_calls = version_calls.clone();
+            move |_| {
+                version_calls.fetch_add(1, Ordering::SeqCst);
+                StubResponse::error(500, "internal error")
+            }
+        })
+        .respond(
+            "api/chat",
+            StubResponse::json(json!({
+                "model": "qwen3",
+                "created_at": "2024-01-01T00:00:00Z",
+                "message": { "role": "assistant", "content": "Hi" },
+                "done": true,
+            })),
+        );
+    let ollama = Ollama::builder().stub(stub).build().unwrap();
+
+    let request =
+        ChatMessageRequest::new("qwen3".to_string(), vec![ChatMessage::user("Hi".into())])
+            .think(true);
+    for _ in 0..3 {
+        ollama.send_chat_messages(request.clone()).await.unwrap();
+    }
+
+    // The failed fetch isn't repeated before every request
+    assert_eq!(version_calls.load(Ordering::SeqCst), 1);
+}